@@ -1,3 +1,143 @@
-fn main() {
-    println!("Hello, world!");
+use std::io::{self, BufRead};
+use std::net::TcpStream;
+
+use poker_common::action::PlayerAction;
+use poker_common::protocol::{receive, send, ClientMessage, ProtocolError, ServerMessage, TableSummary, PROTOCOL_VERSION};
+use poker_common::stakes::{BettingStructure, Stakes};
+use poker_common::variant::Variant;
+
+// Turns a typed command into a message for the server, e.g. "u jane",
+// "j 3 500" or "a 3 raise 60". "r <token>" resumes an earlier session and
+// "n 4 6 5 10" opens a no-limit table of the fifth variant for six players
+// at 5/10 blinds; a trailing "straights" has straights beat three of a kind
+// in short deck. "o 3" sits out of table 3 and "i 3" sits back in.
+fn parse_command(line: &str) -> Option<ClientMessage> {
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    let message = match tokens.as_slice() {
+        ["u", name] => ClientMessage::Username(name.to_string()),
+        ["p", password] => ClientMessage::Password(password.to_string()),
+        ["r", token] => ClientMessage::Resume { token: token.to_string() },
+        ["l"] => ClientMessage::ListTables,
+        ["n", variant, max_seats, rest @ ..] => {
+            let (blinds, trips_beat_straight) = match rest {
+                [blinds @ .., "straights"] => (blinds, false),
+                blinds => (blinds, true),
+            };
+            let variant = *Variant::ALL.get(variant.parse::<usize>().ok()?)?;
+            let mut stakes = Stakes::default();
+            if let [small_blind, big_blind] = blinds {
+                stakes.small_blind = small_blind.parse().ok()?;
+                stakes.big_blind = big_blind.parse().ok()?;
+            } else if !blinds.is_empty() {
+                return None;
+            }
+            let max_seats = max_seats.parse().ok()?;
+            ClientMessage::CreateTable { variant, stakes, structure: BettingStructure::NoLimit, max_seats, trips_beat_straight }
+        }
+        ["j", table, buy_in] => ClientMessage::JoinTable { table: table.parse().ok()?, buy_in: buy_in.parse().ok()? },
+        ["s", table] => ClientMessage::StandUp { table: table.parse().ok()? },
+        ["w", table] => ClientMessage::Watch { table: table.parse().ok()? },
+        ["o", table] => ClientMessage::SitOut { table: table.parse().ok()? },
+        ["i", table] => ClientMessage::SitIn { table: table.parse().ok()? },
+        ["x", table] => ClientMessage::LeaveTable { table: table.parse().ok()? },
+        ["a", table, action @ ..] => {
+            let action = match action {
+                ["fold"] => PlayerAction::Fold,
+                ["check"] => PlayerAction::Check,
+                ["call"] => PlayerAction::Call,
+                ["bet", amount] => PlayerAction::Bet(amount.parse().ok()?),
+                ["raise", amount] => PlayerAction::Raise(amount.parse().ok()?),
+                ["allin"] => PlayerAction::AllIn,
+                _ => return None,
+            };
+            ClientMessage::Action { table: table.parse().ok()?, action }
+        }
+        ["d", table, discards @ ..] => {
+            let discards = discards.iter().map(|card| card.parse().ok()).collect::<Option<_>>()?;
+            ClientMessage::Draw { table: table.parse().ok()?, discards }
+        }
+        ["c", table, text @ ..] => ClientMessage::Chat { table: table.parse().ok()?, text: text.join(" ") },
+        ["q"] => ClientMessage::Quit,
+        _ => return None,
+    };
+    Some(message)
+}
+
+fn print_summary(table: &TableSummary) {
+    let stakes = table.stakes;
+    println!("{} {} {}/{} ({}/{}), average pot {}",
+        table.id, table.variant, stakes.small_blind, stakes.big_blind, table.players, table.max_seats, table.average_pot);
+}
+
+fn print_message(message: &ServerMessage) {
+    match message {
+        ServerMessage::Welcome { .. } => println!("connected"),
+        ServerMessage::NewUser => println!("new user, choose a password"),
+        ServerMessage::ReturningUser => println!("returning user, enter your password"),
+        ServerMessage::LoggedIn { chips, token } => {
+            println!("logged in with {} chips, resume later with: r {}", chips, token)
+        }
+        ServerMessage::LoginFailed(reason) => println!("login failed: {}", reason),
+        ServerMessage::Tables(tables) => {
+            if tables.is_empty() {
+                println!("no tables yet, open one with: n <variant> <seats>");
+            }
+            for table in tables {
+                print_summary(table);
+            }
+        }
+        ServerMessage::TableCreated { table } => println!("opened table {}", table),
+        ServerMessage::TableUpdated(table) => print_summary(table),
+        ServerMessage::TableClosed { table } => println!("table {} closed", table),
+        ServerMessage::Seated { table, seat } => println!("seated at table {} in seat {}", table, seat),
+        ServerMessage::StoodUp { table, chips } => println!("stood up from table {} with {} chips", table, chips),
+        ServerMessage::Watching { table } => println!("watching table {}", table),
+        ServerMessage::Left { table } => println!("left table {}", table),
+        ServerMessage::TableState { table, view } => {
+            let hand: Vec<String> = view.hand().iter().map(|card| card.to_string()).collect();
+            println!("table {}: {:?}, pot {}, your hand {}", table, view.round, view.pot, hand.join(" "));
+        }
+        ServerMessage::Chat { table, from, text } => println!("[{}] {}: {}", table, from, text),
+        ServerMessage::ClockWarning { table, seconds_left } => {
+            println!("table {}: {} seconds left to act", table, seconds_left)
+        }
+        ServerMessage::HandResult { table, awards, shown } => {
+            for hand in shown {
+                let cards: Vec<String> = hand.cards.iter().map(|card| card.to_string()).collect();
+                println!("table {}: {} shows {}", table, hand.name, cards.join(" "));
+            }
+            for award in awards {
+                println!("table {}: {} wins {}", table, award.name, award.chips);
+            }
+        }
+        ServerMessage::Error(error) => println!("error: {}", error),
+        ServerMessage::Goodbye => println!("Goodbye!"),
+    }
+}
+
+fn main() -> Result<(), ProtocolError> {
+    let mut stream = TcpStream::connect("127.0.0.1:8080")?;
+    send(&mut stream, &ClientMessage::Hello { version: PROTOCOL_VERSION })?;
+
+    let mut incoming = stream.try_clone()?;
+    std::thread::spawn(move || {
+        while let Ok(Some(message)) = receive::<_, ServerMessage>(&mut incoming) {
+            print_message(&message);
+        }
+    });
+
+    for line in io::stdin().lock().lines() {
+        let line = line?;
+        match parse_command(&line) {
+            Some(message) => {
+                let quit = message == ClientMessage::Quit;
+                send(&mut stream, &message)?;
+                if quit {
+                    break;
+                }
+            }
+            None => println!("unknown command: {}", line),
+        }
+    }
+    Ok(())
 }
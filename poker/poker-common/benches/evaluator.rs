@@ -0,0 +1,110 @@
+//! Compares the lookup-table evaluator with the HashMap-based one it replaced.
+//!
+//! Run with `cargo bench -p poker-common`.
+
+use std::collections::HashMap;
+use std::hint::black_box;
+use std::time::Instant;
+
+use poker_common::card::{Card, Deck, Suit};
+use poker_common::evaluator;
+
+const HANDS: usize = 100_000;
+const ROUNDS: usize = 5;
+
+// The evaluator previously used by FiveDrawDealer, kept here as the baseline.
+fn legacy_evaluate_hand(hand: &[Card]) -> (u8, Vec<u8>) {
+    let mut values: Vec<u8> = hand.iter().map(|c| c.value.rank()).collect();
+    let suits: Vec<Suit> = hand.iter().map(|c| c.suit.clone()).collect();
+    let mut value_counts: HashMap<u8, u8> = HashMap::new();
+
+    for &v in &values {
+        *value_counts.entry(v).or_insert(0) += 1;
+    }
+
+    values.sort_by(|a, b| b.cmp(a));
+
+    let is_flush = suits.iter().all(|s| *s == suits[0]);
+    let is_straight = values.windows(2).all(|w| w[1] == w[0] - 1);
+    let ace_low_straight = values == vec![14, 5, 4, 3, 2];
+
+    let mut counts: Vec<(u8, u8)> = value_counts.iter().map(|(&v, &c)| (c, v)).collect();
+    counts.sort_by(|a, b| b.cmp(a));
+
+    let hand_rank = if is_flush && (is_straight || ace_low_straight) {
+        if values.contains(&14) { 10 } else { 9 }
+    } else if counts[0].0 == 4 {
+        8
+    } else if counts[0].0 == 3 && counts[1].0 == 2 {
+        7
+    } else if is_flush {
+        6
+    } else if is_straight || ace_low_straight {
+        5
+    } else if counts[0].0 == 3 {
+        4
+    } else if counts.len() > 1 && counts[0].0 == 2 && counts[1].0 == 2 {
+        3
+    } else if counts[0].0 == 2 {
+        2
+    } else {
+        1
+    };
+
+    let mut tiebreaker_values: Vec<u8> = counts.iter().map(|(_, v)| *v).collect();
+    tiebreaker_values.extend(values);
+
+    (hand_rank, tiebreaker_values)
+}
+
+fn deal_hands(size: usize) -> Vec<Vec<Card>> {
+    (0..HANDS as u64)
+        .map(|seed| {
+            let mut deck = Deck::shuffled(seed);
+            (0..size).map(|_| deck.deal().unwrap()).collect()
+        })
+        .collect()
+}
+
+fn bench<F: FnMut()>(name: &str, mut run: F) {
+    // the first pass warms up caches and builds the lookup tables
+    run();
+    let mut best = f64::MAX;
+    for _ in 0..ROUNDS {
+        let start = Instant::now();
+        run();
+        best = best.min(start.elapsed().as_nanos() as f64 / HANDS as f64);
+    }
+    println!("{:<28} {:>10.1} ns/hand", name, best);
+}
+
+fn main() {
+    let fives = deal_hands(5);
+    let sevens = deal_hands(7);
+
+    bench("legacy evaluate_hand", || {
+        for hand in &fives {
+            black_box(legacy_evaluate_hand(black_box(hand)));
+        }
+    });
+    bench("evaluator::evaluate", || {
+        for hand in &fives {
+            black_box(evaluator::evaluate(black_box(hand)));
+        }
+    });
+
+    let encoded: Vec<[u32; 5]> = fives.iter()
+        .map(|hand| [0, 1, 2, 3, 4].map(|i| evaluator::encode(&hand[i])))
+        .collect();
+    bench("evaluator::evaluate_encoded", || {
+        for hand in &encoded {
+            black_box(evaluator::evaluate_encoded(black_box(*hand)));
+        }
+    });
+
+    bench("evaluator::best_hand (7)", || {
+        for hand in &sevens {
+            black_box(evaluator::best_hand(black_box(hand)));
+        }
+    });
+}
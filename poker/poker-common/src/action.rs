@@ -0,0 +1,16 @@
+/// A betting decision made by the player whose turn it is.
+///
+/// Amounts are counted in chips for the current betting round, so `Raise(60)`
+/// against a bet of 20 raises to a total of 60 rather than by 60.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlayerAction {
+    Fold,
+    Check,
+    Call,
+    /// Open the betting for this many chips.
+    Bet(u32),
+    /// Raise the current bet to this total.
+    Raise(u32),
+    /// Put every remaining chip in the pot.
+    AllIn,
+}
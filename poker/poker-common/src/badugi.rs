@@ -0,0 +1,143 @@
+use std::fmt;
+
+use crate::card::Card;
+
+/// A Badugi hand: the largest set of cards with no two sharing a suit or a
+/// rank, aces low. More cards beat fewer, so any four-card badugi beats any
+/// three-card hand; between hands of the same size the lower ranks win,
+/// compared from the highest card down.
+///
+/// As with the lowball hands, the best hand compares smallest: A-2-3-4 of
+/// four suits is the nuts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BadugiHand {
+    // how many cards short of a four-card badugi, so bigger hands compare smaller
+    missing: u8,
+    // the cards kept, highest first, padded with zeros
+    ranks: [u8; 4],
+}
+
+impl BadugiHand {
+    /// How many cards the hand plays, from one to four.
+    pub fn size(&self) -> usize {
+        4 - usize::from(self.missing)
+    }
+
+    /// The ranks kept, highest first, with an ace as 1.
+    pub fn ranks(&self) -> Vec<u8> {
+        self.ranks[..self.size()].to_vec()
+    }
+
+    /// Whether all four cards play.
+    pub fn is_badugi(&self) -> bool {
+        self.missing == 0
+    }
+}
+
+impl fmt::Display for BadugiHand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let ranks: Vec<String> = self.ranks().iter().map(|&rank| match rank {
+            1 => "A".to_owned(),
+            10 => "T".to_owned(),
+            11 => "J".to_owned(),
+            12 => "Q".to_owned(),
+            13 => "K".to_owned(),
+            rank => rank.to_string(),
+        }).collect();
+        write!(f, "{}", ranks.join("-"))
+    }
+}
+
+/// The best Badugi hand that can be kept from `cards`, or `None` if there
+/// are none. Every subset is tried, since throwing away a low card can free
+/// its suit for two others and make a bigger hand.
+pub fn best_badugi(cards: &[Card]) -> Option<BadugiHand> {
+    assert!(cards.len() <= 4, "a badugi hand has at most four cards");
+    let mut best: Option<BadugiHand> = None;
+    for subset in 1..1u32 << cards.len() {
+        let kept: Vec<&Card> = cards.iter().enumerate()
+            .filter(|(i, _)| subset & (1 << i) != 0)
+            .map(|(_, card)| card)
+            .collect();
+        let distinct = kept.iter().enumerate().all(|(i, a)| {
+            kept[i + 1..].iter().all(|b| a.suit != b.suit && a.value.low_rank() != b.value.low_rank())
+        });
+        if !distinct {
+            continue;
+        }
+
+        let mut ranks = [0; 4];
+        for (slot, card) in ranks.iter_mut().zip(&kept) {
+            *slot = card.value.low_rank();
+        }
+        ranks.sort_unstable_by(|a, b| b.cmp(a));
+        let hand = BadugiHand { missing: (4 - kept.len()) as u8, ranks };
+        if best.is_none_or(|current| hand < current) {
+            best = Some(hand);
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::badugi::best_badugi;
+    use crate::card::cards;
+
+    #[test]
+    fn test_four_card_badugi() {
+        let nuts = best_badugi(&cards("Ah 2d 3c 4s")).unwrap();
+        assert!(nuts.is_badugi());
+        assert_eq!(nuts.to_string(), "4-3-2-A");
+        assert!(nuts < best_badugi(&cards("Ah 2d 3c 5s")).unwrap());
+        // compared from the top card down: 8-4-3-2 loses to 7-6-5-4
+        assert!(best_badugi(&cards("7h 6d 5c 4s")).unwrap() < best_badugi(&cards("8h 4d 3c 2s")).unwrap());
+    }
+
+    #[test]
+    fn test_more_cards_beat_lower_cards() {
+        let king_badugi = best_badugi(&cards("Kh Qd Jc Ts")).unwrap();
+        let three_card = best_badugi(&cards("Ah 2d 3c 4c")).unwrap();
+        assert_eq!(three_card.size(), 3);
+        assert_eq!(three_card.to_string(), "3-2-A");
+        assert!(king_badugi < three_card);
+        assert!(three_card < best_badugi(&cards("Ah 2d 2c 4d")).unwrap());
+    }
+
+    #[test]
+    fn test_suited_cards_keep_the_lowest() {
+        // two clubs: keep the ace of clubs, not the four
+        let hand = best_badugi(&cards("Ac 4c 2d 3h")).unwrap();
+        assert_eq!(hand.to_string(), "3-2-A");
+        // paired: keep one deuce, whichever leaves the other suits free
+        let paired = best_badugi(&cards("2c 2d 3d 4h")).unwrap();
+        assert_eq!(paired.to_string(), "4-3-2");
+    }
+
+    #[test]
+    fn test_subset_choice_changes_the_hand() {
+        // keeping the ace of diamonds gives up the three, which is worth it
+        let hand = best_badugi(&cards("Ad 2h 3d 4s")).unwrap();
+        assert_eq!(hand.to_string(), "4-2-A");
+
+        // either ace plays, but the ace of clubs would strand the three of
+        // clubs, so only the ace of diamonds makes a three-card hand
+        let hand = best_badugi(&cards("Ac 3c Ad 2h")).unwrap();
+        assert_eq!(hand.to_string(), "3-2-A");
+
+        // three hearts: only one plays, so the hand is the ace and the three
+        let hand = best_badugi(&cards("Ah 2h 3s 5h")).unwrap();
+        assert_eq!(hand.to_string(), "3-A");
+
+        // a lower two-card hand is worse than a higher three-card hand
+        assert!(best_badugi(&cards("Kc Qd Jh Js")).unwrap() < best_badugi(&cards("Ac 2c 3c 4d")).unwrap());
+    }
+
+    #[test]
+    fn test_one_card_hands() {
+        let hand = best_badugi(&cards("Kh Qh Jh Th")).unwrap();
+        assert_eq!(hand.size(), 1);
+        assert_eq!(hand.to_string(), "T");
+        assert!(best_badugi(&[]).is_none());
+    }
+}
@@ -1,10 +1,13 @@
-#[derive(Clone, PartialEq)]
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Card {
     pub suit: Suit,
     pub value: Value,
 }
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Suit {
     Hearts,
     Diamonds,
@@ -12,7 +15,7 @@ pub enum Suit {
     Spades,
 }
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Value {
     Two,
     Three,
@@ -27,9 +30,113 @@ pub enum Value {
     Queen,
     King,
     Ace,
+    /// Only in a deck built with a joker. Jokers carry a suit so they fit in a
+    /// `Card`, but it plays no part: the joker is always the spade.
+    Joker,
+}
+
+impl Card {
+    pub fn new(suit: Suit, value: Value) -> Card {
+        Card { suit, value }
+    }
+
+    pub fn joker() -> Card {
+        Card { suit: Suit::Spades, value: Value::Joker }
+    }
+
+    pub fn is_joker(&self) -> bool {
+        self.value == Value::Joker
+    }
+}
+
+/// Short form such as "Ah" or "Td", and "Jk" for the joker.
+impl fmt::Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_joker() {
+            return write!(f, "Jk");
+        }
+        let value = match self.value {
+            Value::Ten => 'T',
+            Value::Jack => 'J',
+            Value::Queen => 'Q',
+            Value::King => 'K',
+            Value::Ace => 'A',
+            ref v => char::from(b'0' + v.rank()),
+        };
+        let suit = match self.suit {
+            Suit::Hearts => 'h',
+            Suit::Diamonds => 'd',
+            Suit::Clubs => 'c',
+            Suit::Spades => 's',
+        };
+        write!(f, "{}{}", value, suit)
+    }
+}
+
+/// Parses the short form written by `Display`, e.g. "Ah", "Td" or "Jk".
+impl FromStr for Card {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("jk") {
+            return Ok(Card::joker());
+        }
+        let mut chars = s.chars();
+        let (Some(value), Some(suit), None) = (chars.next(), chars.next(), chars.next()) else {
+            return Err(format!("invalid card: {}", s));
+        };
+        let value = match value.to_ascii_uppercase() {
+            'T' => Value::Ten,
+            'J' => Value::Jack,
+            'Q' => Value::Queen,
+            'K' => Value::King,
+            'A' => Value::Ace,
+            digit @ '2'..='9' => Value::ALL[digit as usize - '2' as usize].clone(),
+            _ => return Err(format!("invalid card value: {}", s)),
+        };
+        let suit = match suit.to_ascii_lowercase() {
+            'h' => Suit::Hearts,
+            'd' => Suit::Diamonds,
+            'c' => Suit::Clubs,
+            's' => Suit::Spades,
+            _ => return Err(format!("invalid card suit: {}", s)),
+        };
+        Ok(Card { suit, value })
+    }
+}
+
+impl Suit {
+    pub const ALL: [Suit; 4] = [Suit::Hearts, Suit::Diamonds, Suit::Clubs, Suit::Spades];
+
+    /// Suits in bridge order, clubs lowest and spades highest, for the rare
+    /// cases where suits break a tie, such as deciding the stud bring-in.
+    pub fn rank(&self) -> u8 {
+        match self {
+            Suit::Clubs => 1,
+            Suit::Diamonds => 2,
+            Suit::Hearts => 3,
+            Suit::Spades => 4,
+        }
+    }
 }
 
 impl Value {
+    pub const ALL: [Value; 13] = [
+        Value::Two,
+        Value::Three,
+        Value::Four,
+        Value::Five,
+        Value::Six,
+        Value::Seven,
+        Value::Eight,
+        Value::Nine,
+        Value::Ten,
+        Value::Jack,
+        Value::Queen,
+        Value::King,
+        Value::Ace,
+    ];
+
     pub fn rank(&self) -> u8 {
         match self {
             Value::Two => 2,
@@ -44,7 +151,321 @@ impl Value {
             Value::Jack => 11,
             Value::Queen => 12,
             Value::King => 13,
-            Value::Ace => 14, // Ace is high by default (later handle A-2-3-4-5 case)
+            Value::Ace => 14, // Ace is high; the evaluator plays it low in an A-2-3-4-5 straight
+            Value::Joker => 15, // ranks above everything until it is given a value
+        }
+    }
+
+    /// The rank in games where the ace is the lowest card.
+    pub fn low_rank(&self) -> u8 {
+        match self {
+            Value::Ace => 1,
+            value => value.rank(),
+        }
+    }
+
+    pub fn plural(&self) -> &'static str {
+        match self {
+            Value::Two => "Twos",
+            Value::Three => "Threes",
+            Value::Four => "Fours",
+            Value::Five => "Fives",
+            Value::Six => "Sixes",
+            Value::Seven => "Sevens",
+            Value::Eight => "Eights",
+            Value::Nine => "Nines",
+            Value::Ten => "Tens",
+            Value::Jack => "Jacks",
+            Value::Queen => "Queens",
+            Value::King => "Kings",
+            Value::Ace => "Aces",
+            Value::Joker => "Jokers",
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Value::Two => "Two",
+            Value::Three => "Three",
+            Value::Four => "Four",
+            Value::Five => "Five",
+            Value::Six => "Six",
+            Value::Seven => "Seven",
+            Value::Eight => "Eight",
+            Value::Nine => "Nine",
+            Value::Ten => "Ten",
+            Value::Jack => "Jack",
+            Value::Queen => "Queen",
+            Value::King => "King",
+            Value::Ace => "Ace",
+            Value::Joker => "Joker",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A standard 52 card deck with its own discard pile.
+///
+/// Shuffling is driven by a caller-supplied seed so that a hand can be
+/// replayed exactly. When the deck runs out, the discard pile is reshuffled
+/// and dealt from.
+pub struct Deck {
+    cards: Vec<Card>,
+    discard: Vec<Card>,
+    rng: DeckRng,
+}
+
+impl Deck {
+    /// Builds an unshuffled deck, ordered by suit then value.
+    pub fn new() -> Deck {
+        let mut cards = Vec::with_capacity(52);
+        for suit in Suit::ALL.iter() {
+            for value in Value::ALL.iter() {
+                cards.push(Card { suit: suit.clone(), value: value.clone() });
+            }
+        }
+        Deck {
+            cards,
+            discard: Vec::new(),
+            rng: DeckRng::new(0),
+        }
+    }
+
+    /// Builds an unshuffled deck holding only `lowest` and the values above
+    /// it in every suit: 36 cards from the six up for short deck.
+    pub fn with_lowest(lowest: Value) -> Deck {
+        let mut deck = Deck::new();
+        deck.cards.retain(|card| card.value.rank() >= lowest.rank());
+        deck
+    }
+
+    /// Builds an unshuffled deck of 53 cards, the joker on the bottom.
+    pub fn with_joker() -> Deck {
+        let mut deck = Deck::new();
+        deck.cards.insert(0, Card::joker());
+        deck
+    }
+
+    /// Builds a deck that deals `cards` in the order given, to set up a
+    /// particular hand.
+    pub fn stacked(mut cards: Vec<Card>) -> Deck {
+        cards.reverse();
+        Deck { cards, discard: Vec::new(), rng: DeckRng::new(0) }
+    }
+
+    /// Builds a full deck and shuffles it with the given seed.
+    pub fn shuffled(seed: u64) -> Deck {
+        let mut deck = Deck::new();
+        deck.shuffle(seed);
+        deck
+    }
+
+    /// Builds a deck with the joker and shuffles it with the given seed.
+    pub fn shuffled_with_joker(seed: u64) -> Deck {
+        let mut deck = Deck::with_joker();
+        deck.shuffle(seed);
+        deck
+    }
+
+    /// Reseeds the deck and shuffles the cards that are left in it.
+    pub fn shuffle(&mut self, seed: u64) {
+        self.rng = DeckRng::new(seed);
+        self.rng.shuffle(&mut self.cards);
+    }
+
+    /// Deals the top card, reshuffling the discard pile back in if the deck is empty.
+    /// Returns `None` only once both the deck and the discard pile are exhausted.
+    pub fn deal(&mut self) -> Option<Card> {
+        if self.cards.is_empty() {
+            self.reshuffle_discards();
+        }
+        self.cards.pop()
+    }
+
+    /// Deals the top card face down onto the discard pile.
+    pub fn burn(&mut self) -> bool {
+        match self.deal() {
+            Some(card) => {
+                self.discard.push(card);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn discard(&mut self, card: Card) {
+        self.discard.push(card);
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.cards.len()
+    }
+
+    pub fn discarded(&self) -> usize {
+        self.discard.len()
+    }
+
+    fn reshuffle_discards(&mut self) {
+        self.cards.append(&mut self.discard);
+        self.rng.shuffle(&mut self.cards);
+    }
+}
+
+impl Default for Deck {
+    fn default() -> Self {
+        Deck::new()
+    }
+}
+
+// SplitMix64, small and fully deterministic for a given seed.
+struct DeckRng {
+    state: u64,
+}
+
+impl DeckRng {
+    fn new(seed: u64) -> DeckRng {
+        DeckRng { state: seed }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    // Uniform in 0..bound, rejecting the biased tail of the u64 range.
+    fn below(&mut self, bound: u64) -> u64 {
+        let zone = u64::MAX - (u64::MAX % bound);
+        loop {
+            let r = self.next_u64();
+            if r < zone {
+                return r % bound;
+            }
+        }
+    }
+
+    fn shuffle(&mut self, cards: &mut [Card]) {
+        for i in (1..cards.len()).rev() {
+            let j = self.below(i as u64 + 1) as usize;
+            cards.swap(i, j);
+        }
+    }
+}
+
+/// The cards in a string of codes such as "Ah Kd 7c", for writing tests.
+/// Panics on a code that doesn't parse.
+#[cfg(any(test, feature = "test-util"))]
+pub fn cards(codes: &str) -> Vec<Card> {
+    codes.split_whitespace().map(|code| code.parse().unwrap()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::card::{cards, Card, Deck, Suit, Value};
+
+    #[test]
+    fn test_new_deck_has_52_unique_cards() {
+        let mut deck = Deck::new();
+        let mut seen: Vec<Card> = Vec::new();
+        while let Some(card) = deck.deal() {
+            assert!(!seen.contains(&card));
+            seen.push(card);
+        }
+        assert_eq!(seen.len(), 52);
+    }
+
+    #[test]
+    fn test_short_deck_starts_at_the_six() {
+        let mut deck = Deck::with_lowest(Value::Six);
+        deck.shuffle(9);
+        assert_eq!(deck.remaining(), 36);
+        while let Some(card) = deck.deal() {
+            assert!(card.value.rank() >= 6);
+        }
+        assert_eq!(Deck::with_lowest(Value::Two).remaining(), 52);
+    }
+
+    #[test]
+    fn test_deck_with_joker() {
+        let mut deck = Deck::shuffled_with_joker(3);
+        assert_eq!(deck.remaining(), 53);
+        let mut jokers = 0;
+        while let Some(card) = deck.deal() {
+            jokers += usize::from(card.is_joker());
+        }
+        assert_eq!(jokers, 1);
+
+        let joker: Card = "Jk".parse().unwrap();
+        assert!(joker.is_joker());
+        assert_eq!(joker.to_string(), "Jk");
+        assert!(!"Jh".parse::<Card>().unwrap().is_joker());
+    }
+
+    #[test]
+    fn test_same_seed_same_order() {
+        let mut deck1 = Deck::shuffled(42);
+        let mut deck2 = Deck::shuffled(42);
+        let mut deck3 = Deck::shuffled(43);
+        let hand1: Vec<Card> = (0..10).map(|_| deck1.deal().unwrap()).collect();
+        let hand2: Vec<Card> = (0..10).map(|_| deck2.deal().unwrap()).collect();
+        let hand3: Vec<Card> = (0..10).map(|_| deck3.deal().unwrap()).collect();
+        assert_eq!(hand1, hand2);
+        assert_ne!(hand1, hand3);
+    }
+
+    #[test]
+    fn test_stacked_deck_deals_in_order() {
+        let mut deck = Deck::stacked(cards("Ah 7c Td"));
+        assert_eq!(deck.remaining(), 3);
+        let dealt: Vec<Card> = (0..3).map(|_| deck.deal().unwrap()).collect();
+        assert_eq!(dealt, cards("Ah 7c Td"));
+        assert!(deck.deal().is_none());
+    }
+
+    #[test]
+    fn test_burn_and_reshuffle_discards() {
+        let mut deck = Deck::shuffled(7);
+        assert!(deck.burn());
+        assert_eq!(deck.remaining(), 51);
+        assert_eq!(deck.discarded(), 1);
+
+        for _ in 0..51 {
+            let card = deck.deal().unwrap();
+            deck.discard(card);
+        }
+        assert_eq!(deck.remaining(), 0);
+
+        // the discard pile comes back once the deck is empty
+        assert!(deck.deal().is_some());
+        assert_eq!(deck.remaining(), 51);
+        assert_eq!(deck.discarded(), 0);
+    }
+
+    #[test]
+    fn test_empty_deck_and_discard() {
+        let mut deck = Deck::new();
+        while deck.deal().is_some() {}
+        assert!(deck.deal().is_none());
+        assert!(!deck.burn());
+    }
+
+    #[test]
+    fn test_card_short_form() {
+        let card: Card = "Td".parse().unwrap();
+        assert_eq!(card, Card::new(Suit::Diamonds, Value::Ten));
+        assert_eq!(card.to_string(), "Td");
+        assert_eq!("2c".parse::<Card>().unwrap().to_string(), "2c");
+        assert!("1h".parse::<Card>().is_err());
+        assert!("Ahh".parse::<Card>().is_err());
+        for suit in Suit::ALL.iter() {
+            for value in Value::ALL.iter() {
+                let card = Card::new(suit.clone(), value.clone());
+                assert_eq!(card.to_string().parse::<Card>().unwrap(), card);
+            }
         }
     }
 }
@@ -0,0 +1,542 @@
+use std::sync::OnceLock;
+
+use crate::card::{Card, Suit, Value};
+use crate::hand::HandRank;
+
+/// The primes used to give every multiset of ranks a unique product, from deuce to ace.
+const PRIMES: [u32; 13] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41];
+
+/// Number of distinct five card hand strengths.
+pub const DISTINCT_HANDS: u16 = 7462;
+
+/// The strength of a five card poker hand, from 1 (7-5-4-3-2 offsuit) to
+/// 7462 (a royal flush), with five of a kind above that when cards are wild.
+/// Hands that tie compare equal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HandStrength(u16);
+
+impl HandStrength {
+    pub fn value(&self) -> u16 {
+        self.0
+    }
+
+    /// Five of a kind, only possible with wild cards, beats a royal flush.
+    pub fn five_of_a_kind(value: &Value) -> HandStrength {
+        assert!(*value != Value::Joker, "five jokers are five aces");
+        HandStrength(DISTINCT_HANDS + u16::from(value.rank()) - 1)
+    }
+}
+
+/// A card packed into 32 bits for the evaluator:
+///
+/// ```text
+/// xxxbbbbb bbbbbbbb cdhsrrrr xxpppppp
+/// ```
+///
+/// `b` has one bit set for the rank, `cdhs` one bit for the suit, `r` is the
+/// rank index (deuce = 0) and `p` the rank's prime. A joker has no encoding
+/// and must be given a value first, see [`crate::wild`].
+pub fn encode(card: &Card) -> u32 {
+    assert!(!card.is_joker(), "a joker must be assigned a card before it is evaluated");
+    let rank = u32::from(card.value.rank() - 2);
+    let suit = match card.suit {
+        Suit::Spades => 0x1000,
+        Suit::Hearts => 0x2000,
+        Suit::Diamonds => 0x4000,
+        Suit::Clubs => 0x8000,
+    };
+    (1 << (16 + rank)) | suit | (rank << 8) | PRIMES[rank as usize]
+}
+
+/// Ranks exactly five cards. Panics if `cards` does not hold five cards.
+pub fn evaluate(cards: &[Card]) -> HandStrength {
+    assert_eq!(cards.len(), 5, "a poker hand has five cards");
+    evaluate_encoded([
+        encode(&cards[0]),
+        encode(&cards[1]),
+        encode(&cards[2]),
+        encode(&cards[3]),
+        encode(&cards[4]),
+    ])
+}
+
+/// Ranks five cards already packed with [`encode`].
+pub fn evaluate_encoded(cards: [u32; 5]) -> HandStrength {
+    let tables = tables();
+    let [c1, c2, c3, c4, c5] = cards;
+    let ranks = ((c1 | c2 | c3 | c4 | c5) >> 16) as usize;
+
+    if c1 & c2 & c3 & c4 & c5 & 0xF000 != 0 {
+        return HandStrength(tables.flushes[ranks]);
+    }
+    let unique = tables.unique5[ranks];
+    if unique != 0 {
+        return HandStrength(unique);
+    }
+
+    let product = (c1 & 0xFF) * (c2 & 0xFF) * (c3 & 0xFF) * (c4 & 0xFF) * (c5 & 0xFF);
+    let index = tables.paired
+        .binary_search_by_key(&product, |&(p, _)| p)
+        .expect("every paired hand has an entry");
+    HandStrength(tables.paired[index].1)
+}
+
+/// Finds the strongest five card hand that can be made from `cards`, such as
+/// two hole cards and five on the board. Returns the strength and the indices
+/// of the five cards used, or `None` with fewer than five cards.
+pub fn best_hand(cards: &[Card]) -> Option<(HandStrength, [usize; 5])> {
+    let encoded: Vec<u32> = cards.iter().map(encode).collect();
+    let n = encoded.len();
+    let mut best: Option<(HandStrength, [usize; 5])> = None;
+
+    for a in 0..n {
+        for b in a + 1..n {
+            for c in b + 1..n {
+                for d in c + 1..n {
+                    for e in d + 1..n {
+                        let strength = evaluate_encoded([encoded[a], encoded[b], encoded[c], encoded[d], encoded[e]]);
+                        if best.is_none_or(|(current, _)| strength > current) {
+                            best = Some((strength, [a, b, c, d, e]));
+                        }
+                    }
+                }
+            }
+        }
+    }
+    best
+}
+
+/// Finds the strongest hand using exactly two of the `hole` cards and three
+/// of the `board`, as Omaha requires. Returns the strength with the indices
+/// of the hole and board cards used, or `None` if either is too short.
+pub fn best_omaha_hand(hole: &[Card], board: &[Card]) -> Option<(HandStrength, [usize; 2], [usize; 3])> {
+    let hole: Vec<u32> = hole.iter().map(encode).collect();
+    let board: Vec<u32> = board.iter().map(encode).collect();
+    let mut best: Option<(HandStrength, [usize; 2], [usize; 3])> = None;
+
+    for a in 0..hole.len() {
+        for b in a + 1..hole.len() {
+            for c in 0..board.len() {
+                for d in c + 1..board.len() {
+                    for e in d + 1..board.len() {
+                        let strength = evaluate_encoded([hole[a], hole[b], board[c], board[d], board[e]]);
+                        if best.is_none_or(|(current, _, _)| strength > current) {
+                            best = Some((strength, [a, b], [c, d, e]));
+                        }
+                    }
+                }
+            }
+        }
+    }
+    best
+}
+
+// The standard strength each category starts at and how many strengths it
+// holds, weakest first. The royal flush is the top straight flush.
+const CATEGORIES: [(HandRank, u16, u16); 9] = [
+    (HandRank::HighCard, 1, 1277),
+    (HandRank::OnePair, 1278, 2860),
+    (HandRank::TwoPair, 4138, 858),
+    (HandRank::ThreeOfAKind, 4996, 858),
+    (HandRank::Straight, 5854, 10),
+    (HandRank::Flush, 5864, 1277),
+    (HandRank::FullHouse, 7141, 156),
+    (HandRank::FourOfAKind, 7297, 156),
+    (HandRank::StraightFlush, 7453, 10),
+];
+
+/// How five card hands rank against each other: the order of the hand
+/// categories, and the lowest card in the deck, below which an ace plays to
+/// make the lowest straight.
+///
+/// Strengths from a ranking compare only with strengths from the same
+/// ranking; read their category back with [`HandRanking::category`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HandRanking {
+    lowest: u8,
+    // weakest first
+    order: [HandRank; 9],
+}
+
+impl HandRanking {
+    /// The usual game: a 52 card deck and the usual categories.
+    pub fn standard() -> HandRanking {
+        HandRanking { lowest: 2, order: CATEGORIES.map(|(rank, _, _)| rank) }
+    }
+
+    /// Short deck, six and up: A-6-7-8-9 is the lowest straight and a flush
+    /// beats a full house, being harder to make with fewer cards of each suit.
+    /// Some rooms also rank three of a kind above a straight.
+    pub fn short_deck(trips_beat_straight: bool) -> HandRanking {
+        let (lower, higher) = if trips_beat_straight {
+            (HandRank::Straight, HandRank::ThreeOfAKind)
+        } else {
+            (HandRank::ThreeOfAKind, HandRank::Straight)
+        };
+        HandRanking::new(Value::Six, [
+            HandRank::HighCard,
+            HandRank::OnePair,
+            HandRank::TwoPair,
+            lower,
+            higher,
+            HandRank::FullHouse,
+            HandRank::Flush,
+            HandRank::FourOfAKind,
+            HandRank::StraightFlush,
+        ])
+    }
+
+    /// A ranking with the categories in `order`, weakest first. Panics unless
+    /// `order` holds each category from high card to straight flush once.
+    pub fn new(lowest: Value, order: [HandRank; 9]) -> HandRanking {
+        assert!(lowest.rank() <= 10, "a deck needs cards for a straight");
+        for (rank, _, _) in CATEGORIES {
+            assert!(order.contains(&rank), "the order is missing {}", rank);
+        }
+        HandRanking { lowest: lowest.rank(), order }
+    }
+
+    /// The lowest card in the deck.
+    pub fn lowest(&self) -> Value {
+        Value::ALL[usize::from(self.lowest) - 2].clone()
+    }
+
+    /// Ranks exactly five cards. Panics if `cards` does not hold five cards.
+    pub fn evaluate(&self, cards: &[Card]) -> HandStrength {
+        assert_eq!(cards.len(), 5, "a poker hand has five cards");
+        let natural = match self.ace_low_straight(cards) {
+            // the ace plays as the card below the lowest, which the standard
+            // tables already rank as the straight it makes
+            Some(ace) => {
+                let mut cards = cards.to_vec();
+                cards[ace].value = Value::ALL[usize::from(self.lowest) - 3].clone();
+                evaluate(&cards)
+            }
+            None => evaluate(cards),
+        };
+
+        let category = CATEGORIES.iter().rposition(|&(_, start, _)| natural.0 >= start).unwrap_or(0);
+        let (rank, start, _) = CATEGORIES[category];
+        let mut base = 1;
+        for ordered in self.order {
+            if ordered == rank {
+                break;
+            }
+            base += CATEGORIES.iter().find(|(r, _, _)| *r == ordered).map_or(0, |&(_, _, size)| size);
+        }
+        HandStrength(base + natural.0 - start)
+    }
+
+    /// The category of a strength from this ranking.
+    pub fn category(&self, strength: HandStrength) -> HandRank {
+        if strength.0 > DISTINCT_HANDS {
+            return HandRank::FiveOfAKind;
+        }
+        let mut base = 1;
+        for ordered in self.order {
+            let size = CATEGORIES.iter().find(|(r, _, _)| *r == ordered).map_or(0, |&(_, _, size)| size);
+            if strength.0 < base + size {
+                if ordered == HandRank::StraightFlush && strength.0 == base + size - 1 {
+                    return HandRank::RoyalFlush;
+                }
+                return ordered;
+            }
+            base += size;
+        }
+        HandRank::RoyalFlush
+    }
+
+    /// Finds the strongest five card hand that can be made from `cards`, as
+    /// [`best_hand`] does for the standard ranking.
+    pub fn best_hand(&self, cards: &[Card]) -> Option<(HandStrength, [usize; 5])> {
+        let n = cards.len();
+        let mut best: Option<(HandStrength, [usize; 5])> = None;
+        for a in 0..n {
+            for b in a + 1..n {
+                for c in b + 1..n {
+                    for d in c + 1..n {
+                        for e in d + 1..n {
+                            let five = [a, b, c, d, e].map(|i| cards[i].clone());
+                            let strength = self.evaluate(&five);
+                            if best.is_none_or(|(current, _)| strength > current) {
+                                best = Some((strength, [a, b, c, d, e]));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        best
+    }
+
+    // The index of the ace when the cards are an ace with the four lowest
+    // ranks of a deck that starts above the deuce.
+    fn ace_low_straight(&self, cards: &[Card]) -> Option<usize> {
+        if self.lowest == 2 {
+            return None;
+        }
+        let mut ranks: Vec<u8> = cards.iter().map(|card| card.value.rank()).collect();
+        ranks.sort_unstable();
+        let low = self.lowest;
+        if ranks != [low, low + 1, low + 2, low + 3, 14] {
+            return None;
+        }
+        cards.iter().position(|card| card.value == Value::Ace)
+    }
+}
+
+impl Default for HandRanking {
+    fn default() -> Self {
+        HandRanking::standard()
+    }
+}
+
+struct Tables {
+    // indexed by the 13 bit rank mask of five distinct ranks
+    flushes: Vec<u16>,
+    unique5: Vec<u16>,
+    // (product of primes, strength) sorted by product, for hands with a pair or better
+    paired: Vec<(u32, u16)>,
+}
+
+fn tables() -> &'static Tables {
+    static TABLES: OnceLock<Tables> = OnceLock::new();
+    TABLES.get_or_init(build_tables)
+}
+
+// Rank masks of the ten straights from the wheel up to broadway.
+fn straights() -> Vec<u32> {
+    let mut masks = vec![0b1_0000_0000_1111];
+    masks.extend((0..9).map(|low| 0b1_1111 << low));
+    masks
+}
+
+// Every set of five distinct ranks that is not a straight, weakest first.
+fn no_pair_masks(straights: &[u32]) -> Vec<u32> {
+    let mut masks: Vec<u32> = (0u32..1 << 13)
+        .filter(|mask| mask.count_ones() == 5 && !straights.contains(mask))
+        .collect();
+    // with distinct ranks, comparing the masks compares the highest card first
+    masks.sort_unstable();
+    masks
+}
+
+fn product(ranks: &[usize]) -> u32 {
+    ranks.iter().map(|&r| PRIMES[r]).product()
+}
+
+// Kicker combinations of `count` distinct ranks from those not in `used`, weakest first.
+fn kickers(count: usize, used: &[usize]) -> Vec<Vec<usize>> {
+    let mut combos: Vec<u32> = (0u32..1 << 13)
+        .filter(|mask| mask.count_ones() as usize == count && used.iter().all(|&r| mask & (1 << r) == 0))
+        .collect();
+    combos.sort_unstable();
+    combos.into_iter()
+        .map(|mask| (0..13).filter(|r| mask & (1 << r) != 0).collect())
+        .collect()
+}
+
+fn build_tables() -> Tables {
+    let mut flushes = vec![0u16; 0x1F01];
+    let mut unique5 = vec![0u16; 0x1F01];
+    let mut paired: Vec<(u32, u16)> = Vec::with_capacity(4888);
+    let straights = straights();
+    let no_pair = no_pair_masks(&straights);
+    let mut next = 1u16;
+    let mut assign = || {
+        let strength = next;
+        next += 1;
+        strength
+    };
+
+    // high card
+    for &mask in &no_pair {
+        unique5[mask as usize] = assign();
+    }
+    // one pair
+    for pair in 0..13 {
+        for kicks in kickers(3, &[pair]) {
+            paired.push((product(&[pair, pair, kicks[0], kicks[1], kicks[2]]), assign()));
+        }
+    }
+    // two pair
+    for high in 0..13 {
+        for low in 0..high {
+            for kick in kickers(1, &[high, low]) {
+                paired.push((product(&[high, high, low, low, kick[0]]), assign()));
+            }
+        }
+    }
+    // three of a kind
+    for trips in 0..13 {
+        for kicks in kickers(2, &[trips]) {
+            paired.push((product(&[trips, trips, trips, kicks[0], kicks[1]]), assign()));
+        }
+    }
+    // straight
+    for &mask in &straights {
+        unique5[mask as usize] = assign();
+    }
+    // flush
+    for &mask in &no_pair {
+        flushes[mask as usize] = assign();
+    }
+    // full house
+    for trips in 0..13 {
+        for pair in (0..13).filter(|&p| p != trips) {
+            paired.push((product(&[trips, trips, trips, pair, pair]), assign()));
+        }
+    }
+    // four of a kind
+    for quads in 0..13 {
+        for kick in (0..13).filter(|&k| k != quads) {
+            paired.push((product(&[quads, quads, quads, quads, kick]), assign()));
+        }
+    }
+    // straight flush
+    for &mask in &straights {
+        flushes[mask as usize] = assign();
+    }
+    debug_assert_eq!(next - 1, DISTINCT_HANDS);
+
+    paired.sort_unstable();
+    Tables { flushes, unique5, paired }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::card::cards;
+    use crate::evaluator::{best_hand, best_omaha_hand, evaluate, HandRanking, DISTINCT_HANDS};
+    use crate::hand::HandRank;
+
+    #[test]
+    fn test_extremes() {
+        assert_eq!(evaluate(&cards("7h 5d 4c 3s 2h")).value(), 1);
+        assert_eq!(evaluate(&cards("Ah Kh Qh Jh Th")).value(), DISTINCT_HANDS);
+    }
+
+    #[test]
+    fn test_category_order() {
+        let ordered = [
+            "Ah Kd Qc Js 9h",
+            "2h 2d 3c 4s 5h",
+            "3h 3d 2c 2s 4h",
+            "2h 2d 2c 3s 4h",
+            "Ah 2d 3c 4s 5h",
+            "7h 2h 3h 4h 5h",
+            "2h 2d 2c 3s 3h",
+            "2h 2d 2c 2s 3h",
+            "Ah 2h 3h 4h 5h",
+        ];
+        for pair in ordered.windows(2) {
+            assert!(evaluate(&cards(pair[0])) < evaluate(&cards(pair[1])), "{} < {}", pair[0], pair[1]);
+        }
+    }
+
+    #[test]
+    fn test_kickers_and_ties() {
+        assert!(evaluate(&cards("Kh Kd 9c 5s 3h")) > evaluate(&cards("Kc Ks 9h 5d 2h")));
+        assert!(evaluate(&cards("Ah Ad 2c 2s 3h")) > evaluate(&cards("Kh Kd Qc Qs Ah")));
+        assert_eq!(evaluate(&cards("Th Td 8c 8s 4h")), evaluate(&cards("Tc Ts 8h 8d 4s")));
+    }
+
+    #[test]
+    fn test_wheel_is_lowest_straight() {
+        assert!(evaluate(&cards("Ah 2d 3c 4s 5h")) < evaluate(&cards("2h 3d 4c 5s 6h")));
+        assert!(evaluate(&cards("Ah 2h 3h 4h 5h")) < evaluate(&cards("2d 3d 4d 5d 6d")));
+    }
+
+    #[test]
+    fn test_best_five_of_seven() {
+        let hand = cards("Td 9h Ah Kh 7d Qh Jh");
+        assert!(best_hand(&hand[..4]).is_none());
+
+        // the heart flush beats the ace-high straight made with the ten
+        let (strength, used) = best_hand(&hand).unwrap();
+        assert_eq!(used, [1, 2, 3, 5, 6]);
+        assert_eq!(strength, evaluate(&cards("9h Ah Kh Qh Jh")));
+
+        let (strength, _) = best_hand(&cards("Th 9h Ah Kh 7d Qh Jh")).unwrap();
+        assert_eq!(strength.value(), DISTINCT_HANDS);
+    }
+
+    #[test]
+    fn test_omaha_uses_exactly_two_hole_cards() {
+        // four hearts on the board are no flush with only one heart in hand
+        let (strength, hole, board) = best_omaha_hand(&cards("Ah Kc Qd Js"), &cards("2h 5h 8h 9h Tc")).unwrap();
+        assert!(strength < evaluate(&cards("Ah 2h 5h 8h 9h")));
+        assert_eq!(strength, evaluate(&cards("Qd Js 8h 9h Tc")));
+        assert_eq!(hole, [2, 3]);
+        assert_eq!(board, [2, 3, 4]);
+
+        // nor does a board full house play without two matching hole cards
+        let (strength, _, _) = best_omaha_hand(&cards("As Ad Kc 2d"), &cards("7h 7d 7c 3h 3s")).unwrap();
+        assert_eq!(strength, evaluate(&cards("As Ad 7h 7d 7c")));
+
+        assert!(best_omaha_hand(&cards("As Ad"), &cards("7h 7d")).is_none());
+    }
+
+    #[test]
+    fn test_standard_ranking_matches_the_evaluator() {
+        let standard = HandRanking::standard();
+        for hand in ["7h 5d 4c 3s 2h", "Ah 2d 3c 4s 5h", "2h 2d 2c 3s 3h", "Ah Kh Qh Jh Th"] {
+            let strength = standard.evaluate(&cards(hand));
+            assert_eq!(strength, evaluate(&cards(hand)));
+            assert_eq!(standard.category(strength), HandRank::of(strength));
+        }
+    }
+
+    #[test]
+    fn test_short_deck_ace_plays_below_the_six() {
+        let short = HandRanking::short_deck(false);
+        let low_straight = short.evaluate(&cards("Ah 6d 7c 8s 9h"));
+        assert_eq!(short.category(low_straight), HandRank::Straight);
+        assert!(low_straight < short.evaluate(&cards("6h 7d 8c 9s Th")));
+        assert!(low_straight > short.evaluate(&cards("Ah Ad Ac Ks Qh")));
+
+        let steel = short.evaluate(&cards("Ah 6h 7h 8h 9h"));
+        assert_eq!(short.category(steel), HandRank::StraightFlush);
+        assert!(steel < short.evaluate(&cards("6h 7h 8h 9h Th")));
+        assert_eq!(short.category(short.evaluate(&cards("Ah Kh Qh Jh Th"))), HandRank::RoyalFlush);
+
+        // the wheel is no straight when the deck starts at the six
+        assert_eq!(HandRanking::standard().category(HandRanking::standard().evaluate(&cards("Ah 6d 7c 8s 9h"))), HandRank::HighCard);
+    }
+
+    #[test]
+    fn test_short_deck_category_order() {
+        let flush = cards("Ah 9h 7h 6h Jh");
+        let full_house = cards("Kh Kd Kc 7s 7h");
+        let straight = cards("9h Td Jc Qs Kh");
+        let trips = cards("Qh Qd Qc 7s 6h");
+
+        let short = HandRanking::short_deck(false);
+        assert!(short.evaluate(&flush) > short.evaluate(&full_house));
+        assert!(short.evaluate(&full_house) < short.evaluate(&cards("6h 6d 6c 6s 7h")));
+        assert!(short.evaluate(&straight) > short.evaluate(&trips));
+        assert_eq!(short.category(short.evaluate(&flush)), HandRank::Flush);
+        assert_eq!(short.category(short.evaluate(&full_house)), HandRank::FullHouse);
+
+        let trips_high = HandRanking::short_deck(true);
+        assert!(trips_high.evaluate(&trips) > trips_high.evaluate(&straight));
+        assert!(trips_high.evaluate(&cards("6h 6d 6c 7s 8h")) > trips_high.evaluate(&cards("Ah Kd Qc Js Th")));
+        assert_eq!(trips_high.category(trips_high.evaluate(&trips)), HandRank::ThreeOfAKind);
+
+        // the standard order is unchanged
+        let standard = HandRanking::standard();
+        assert!(standard.evaluate(&full_house) > standard.evaluate(&flush));
+    }
+
+    #[test]
+    fn test_short_deck_best_hand_prefers_the_flush() {
+        let hand = cards("Kh Kd Kc 9h 9s Qh 6h 7h");
+        let short = HandRanking::short_deck(false);
+        let (strength, used) = short.best_hand(&hand).unwrap();
+        assert_eq!(short.category(strength), HandRank::Flush);
+        assert_eq!(used, [0, 3, 5, 6, 7]);
+
+        let standard = HandRanking::standard();
+        let (strength, _) = standard.best_hand(&hand).unwrap();
+        assert_eq!(standard.category(strength), HandRank::FullHouse);
+    }
+}
@@ -1,7 +1,11 @@
 use crate::player::Player;
+use crate::variant::Variant;
 
+/// One hand played at a table, with the variant it was dealt as.
+#[derive(Clone)]
 pub struct Game {
     id: u32,
+    variant: Variant,
     players: Vec<Player>,
     winning_players: Vec<Player>,
     total_chips: u32,
@@ -14,15 +18,24 @@ pub struct GameSession {
 }
 
 impl Game {
-    pub fn new(id: u32, players: Vec<Player>) -> Game {
+    pub fn new(id: u32, variant: Variant, players: Vec<Player>) -> Game {
         Game {
             id,
+            variant,
             players,
             winning_players: Vec::new(),
             total_chips: 0,
         }
     }
 
+    pub fn get_id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn get_variant(&self) -> Variant {
+        self.variant
+    }
+
     pub fn add_player(&mut self, player: Player) {
         self.players.push(player);
     }
@@ -61,13 +74,12 @@ impl GameSession {
         }
     }
 
-    pub fn start_game(&mut self, players: Vec<Player>) {
-        let game = Game {
-            id: self.games.len() as u32,
-            players,
-            winning_players: Vec::new(),
-            total_chips: 0,
-        };
+    pub fn get_id(&self) -> u32 {
+        self.game_session_id
+    }
+
+    pub fn start_game(&mut self, variant: Variant, players: Vec<Player>) {
+        let game = Game::new(self.games.len() as u32, variant, players);
         self.current_game = Some(game);
     }
 
@@ -81,6 +93,10 @@ impl GameSession {
         self.current_game.as_ref()
     }
 
+    pub fn get_current_game_mut(&mut self) -> Option<&mut Game> {
+        self.current_game.as_mut()
+    }
+
     pub fn get_games(&self) -> &Vec<Game> {
         &self.games
     }
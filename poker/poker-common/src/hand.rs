@@ -0,0 +1,362 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use crate::card::{Card, Value};
+use crate::evaluator::{self, HandRanking, HandStrength};
+use crate::wild::{self, WildCards};
+
+/// The category of a five card poker hand, weakest first.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum HandRank {
+    HighCard,
+    OnePair,
+    TwoPair,
+    ThreeOfAKind,
+    Straight,
+    Flush,
+    FullHouse,
+    FourOfAKind,
+    StraightFlush,
+    RoyalFlush,
+    /// Only possible with wild cards.
+    FiveOfAKind,
+}
+
+impl HandRank {
+    /// The category a hand strength falls in. Strengths are numbered from
+    /// the weakest high card hand up, so each category is a contiguous range.
+    pub fn of(strength: HandStrength) -> HandRank {
+        match strength.value() {
+            0..=1277 => HandRank::HighCard,
+            1278..=4137 => HandRank::OnePair,
+            4138..=4995 => HandRank::TwoPair,
+            4996..=5853 => HandRank::ThreeOfAKind,
+            5854..=5863 => HandRank::Straight,
+            5864..=7140 => HandRank::Flush,
+            7141..=7296 => HandRank::FullHouse,
+            7297..=7452 => HandRank::FourOfAKind,
+            7453..=7461 => HandRank::StraightFlush,
+            7462 => HandRank::RoyalFlush,
+            _ => HandRank::FiveOfAKind,
+        }
+    }
+
+    // How many of the five cards form the combination, the rest are kickers.
+    fn made_cards(&self) -> usize {
+        match self {
+            HandRank::HighCard => 1,
+            HandRank::OnePair => 2,
+            HandRank::ThreeOfAKind => 3,
+            HandRank::TwoPair | HandRank::FourOfAKind => 4,
+            _ => 5,
+        }
+    }
+}
+
+impl fmt::Display for HandRank {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            HandRank::HighCard => "High Card",
+            HandRank::OnePair => "One Pair",
+            HandRank::TwoPair => "Two Pair",
+            HandRank::ThreeOfAKind => "Three of a Kind",
+            HandRank::Straight => "Straight",
+            HandRank::Flush => "Flush",
+            HandRank::FullHouse => "Full House",
+            HandRank::FourOfAKind => "Four of a Kind",
+            HandRank::StraightFlush => "Straight Flush",
+            HandRank::RoyalFlush => "Royal Flush",
+            HandRank::FiveOfAKind => "Five of a Kind",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// The best five card hand a player holds, with the cards that make it.
+///
+/// Evaluations compare by hand strength alone, so two hands that split a pot
+/// are equal even if their suits differ.
+#[derive(Clone, Debug)]
+pub struct HandEvaluation {
+    rank: HandRank,
+    strength: HandStrength,
+    cards: Vec<Card>,
+}
+
+impl HandEvaluation {
+    /// Evaluates the best five card hand out of `cards`, or `None` if there
+    /// are fewer than five.
+    pub fn new(cards: &[Card]) -> Option<HandEvaluation> {
+        let (strength, used) = evaluator::best_hand(cards)?;
+        let cards: Vec<Card> = used.iter().map(|&i| cards[i].clone()).collect();
+        Some(HandEvaluation::from_five(strength, HandRank::of(strength), cards))
+    }
+
+    /// Evaluates the best five card hand out of `cards` under `ranking`, such
+    /// as short deck. Compare it only with evaluations under the same ranking.
+    pub fn ranked(cards: &[Card], ranking: &HandRanking) -> Option<HandEvaluation> {
+        let (strength, used) = ranking.best_hand(cards)?;
+        let cards: Vec<Card> = used.iter().map(|&i| cards[i].clone()).collect();
+        Some(HandEvaluation::from_five(strength, ranking.category(strength), cards))
+    }
+
+    /// Evaluates the best Omaha hand: exactly two of the `hole` cards with
+    /// exactly three from the `board`.
+    pub fn omaha(hole: &[Card], board: &[Card]) -> Option<HandEvaluation> {
+        let (strength, from_hole, from_board) = evaluator::best_omaha_hand(hole, board)?;
+        let cards: Vec<Card> = from_hole.iter().map(|&i| hole[i].clone())
+            .chain(from_board.iter().map(|&i| board[i].clone()))
+            .collect();
+        Some(HandEvaluation::from_five(strength, HandRank::of(strength), cards))
+    }
+
+    /// Evaluates the best five card hand out of `cards` with wild cards. The
+    /// cards of the evaluation are the ones the hand plays as, so a wild card
+    /// shows as the card it stands for.
+    pub fn wild(cards: &[Card], wild: &WildCards) -> Option<HandEvaluation> {
+        let (strength, played) = wild::best_hand(cards, wild)?;
+        Some(HandEvaluation::from_five(strength, HandRank::of(strength), played))
+    }
+
+    fn from_five(strength: HandStrength, rank: HandRank, mut cards: Vec<Card>) -> HandEvaluation {
+
+        // bigger groups first, then higher values, so the cards read the way
+        // the hand is named: K K K 7 7, J J 4 4 9
+        let count = |value: &Value| cards.iter().filter(|c| c.value == *value).count();
+        let mut keys: Vec<(usize, u8)> = cards.iter().map(|c| (count(&c.value), c.value.rank())).collect();
+        let mut order: Vec<usize> = (0..cards.len()).collect();
+        order.sort_by(|&a, &b| keys[b].cmp(&keys[a]));
+        cards = order.iter().map(|&i| cards[i].clone()).collect();
+        keys = order.iter().map(|&i| keys[i]).collect();
+
+        // in a wheel the ace plays low: 5 4 3 2 A, or 9 8 7 6 A in short deck
+        let is_straight = matches!(rank, HandRank::Straight | HandRank::StraightFlush);
+        if is_straight && keys[0].1 == 14 && keys[1].1 != 13 {
+            cards.rotate_left(1);
+        }
+
+        HandEvaluation { rank, strength, cards }
+    }
+
+    pub fn rank(&self) -> HandRank {
+        self.rank
+    }
+
+    pub fn strength(&self) -> HandStrength {
+        self.strength
+    }
+
+    /// The five cards that make the hand, most significant first.
+    pub fn cards(&self) -> &[Card] {
+        &self.cards
+    }
+
+    /// The cards that only break ties, such as the three side cards with one pair.
+    pub fn kickers(&self) -> &[Card] {
+        &self.cards[self.rank.made_cards()..]
+    }
+}
+
+impl PartialEq for HandEvaluation {
+    fn eq(&self, other: &Self) -> bool {
+        self.strength == other.strength
+    }
+}
+
+impl Eq for HandEvaluation {}
+
+impl PartialOrd for HandEvaluation {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for HandEvaluation {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.strength.cmp(&other.strength)
+    }
+}
+
+impl fmt::Display for HandEvaluation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let first = &self.cards[0].value;
+        match self.rank {
+            HandRank::HighCard => write!(f, "{}, {}", self.rank, first),
+            HandRank::OnePair | HandRank::ThreeOfAKind | HandRank::FourOfAKind | HandRank::FiveOfAKind => {
+                write!(f, "{}, {}", self.rank, first.plural())
+            }
+            HandRank::TwoPair => write!(f, "{}, {} and {}", self.rank, first.plural(), self.cards[2].value.plural()),
+            HandRank::FullHouse => write!(f, "{}, {} over {}", self.rank, first.plural(), self.cards[3].value.plural()),
+            HandRank::Straight | HandRank::Flush | HandRank::StraightFlush => {
+                write!(f, "{}, {} high", self.rank, first)
+            }
+            HandRank::RoyalFlush => write!(f, "{}", self.rank),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::card::{cards, Card, Deck, Value};
+    use crate::evaluator::{self, HandRanking, DISTINCT_HANDS};
+    use crate::hand::{HandEvaluation, HandRank};
+    use crate::wild::{Joker, WildCards};
+
+    fn evaluate(hand: &str) -> HandEvaluation {
+        HandEvaluation::new(&cards(hand)).unwrap()
+    }
+
+    fn codes(cards: &[Card]) -> String {
+        cards.iter().map(|c| c.to_string()).collect::<Vec<_>>().join(" ")
+    }
+
+    #[test]
+    fn test_rank_and_display() {
+        let cases = [
+            ("Kh 9d 7c 4s 2h", HandRank::HighCard, "High Card, King"),
+            ("9h 9d Ac 4s 2h", HandRank::OnePair, "One Pair, Nines"),
+            ("4h Jd Jc 4s 9h", HandRank::TwoPair, "Two Pair, Jacks and Fours"),
+            ("Qh Qd Qc 4s 2h", HandRank::ThreeOfAKind, "Three of a Kind, Queens"),
+            ("6h 7d 8c 9s Th", HandRank::Straight, "Straight, Ten high"),
+            ("Ah 9h 7h 4h 2h", HandRank::Flush, "Flush, Ace high"),
+            ("7h Kd Kc 7s Kh", HandRank::FullHouse, "Full House, Kings over Sevens"),
+            ("Ah Ad Ac As 2h", HandRank::FourOfAKind, "Four of a Kind, Aces"),
+            ("5s 6s 7s 8s 9s", HandRank::StraightFlush, "Straight Flush, Nine high"),
+            ("Td Jd Qd Kd Ad", HandRank::RoyalFlush, "Royal Flush"),
+        ];
+        for (hand, rank, text) in cases.iter() {
+            let evaluation = evaluate(hand);
+            assert_eq!(evaluation.rank(), *rank, "{}", hand);
+            assert_eq!(evaluation.to_string(), *text);
+        }
+    }
+
+    #[test]
+    fn test_cards_and_kickers() {
+        let full_house = evaluate("7h Kd Kc 7s Kh");
+        assert_eq!(codes(full_house.cards()), "Kd Kc Kh 7h 7s");
+        assert!(full_house.kickers().is_empty());
+
+        let two_pair = evaluate("4h Jd Jc 4s 9h");
+        assert_eq!(codes(two_pair.cards()), "Jd Jc 4h 4s 9h");
+        assert_eq!(codes(two_pair.kickers()), "9h");
+
+        let pair = evaluate("2h 9d Ac 4s 9h");
+        assert_eq!(codes(pair.kickers()), "Ac 4s 2h");
+    }
+
+    #[test]
+    fn test_best_five_of_seven() {
+        let evaluation = evaluate("2c 9h Ah Kh 7d Qh Jh");
+        assert_eq!(evaluation.rank(), HandRank::Flush);
+        assert_eq!(codes(evaluation.cards()), "Ah Kh Qh Jh 9h");
+        assert!(HandEvaluation::new(&evaluation.cards()[..4]).is_none());
+    }
+
+    #[test]
+    fn test_omaha_hand() {
+        let hole = cards("Ah Kc Qd Js");
+        let board = cards("2h 5h 8h 9h Tc");
+        let evaluation = HandEvaluation::omaha(&hole, &board).unwrap();
+        assert_eq!(evaluation.to_string(), "Straight, Queen high");
+        assert_eq!(codes(evaluation.cards()), "Qd Js Tc 9h 8h");
+    }
+
+    #[test]
+    fn test_wild_hand_shows_the_cards_it_plays_as() {
+        let hand = cards("Kh Kd 2c 9s Jk");
+        let wild = WildCards::none().with_rank(Value::Two).with_joker(Joker::Wild);
+        let evaluation = HandEvaluation::wild(&hand, &wild).unwrap();
+        assert_eq!(evaluation.rank(), HandRank::FourOfAKind);
+        assert_eq!(evaluation.to_string(), "Four of a Kind, Kings");
+        assert!(!evaluation.cards().iter().any(|card| card.is_joker()));
+
+        let hand = cards("Kh Kd 2c Ks Jk");
+        let evaluation = HandEvaluation::wild(&hand, &wild).unwrap();
+        assert_eq!(evaluation.to_string(), "Five of a Kind, Kings");
+        assert!(evaluation > evaluate("Td Jd Qd Kd Ad"));
+    }
+
+    #[test]
+    fn test_short_deck_hand() {
+        let short = HandRanking::short_deck(true);
+        let hand = cards("9h Ad 7c 8s 6h Kd");
+        let evaluation = HandEvaluation::ranked(&hand, &short).unwrap();
+        assert_eq!(evaluation.rank(), HandRank::Straight);
+        assert_eq!(evaluation.to_string(), "Straight, Nine high");
+        assert_eq!(codes(evaluation.cards()), "9h 8s 7c 6h Ad");
+    }
+
+    #[test]
+    fn test_ordering() {
+        assert!(evaluate("Ah Ad Ac As 2h") > evaluate("7h Kd Kc 7s Kh"));
+        assert!(evaluate("9h 9d Ac 4s 2h") > evaluate("9c 9s Kc 4d 2d"));
+        assert_eq!(evaluate("9h 9d Ac 4s 2h"), evaluate("9c 9s Ad 4d 2d"));
+    }
+
+    #[test]
+    fn test_wheel_plays_five_high() {
+        let wheel = evaluate("Ah 2d 3c 4s 5h");
+        assert_eq!(wheel.rank(), HandRank::Straight);
+        assert_eq!(wheel.to_string(), "Straight, Five high");
+        assert_eq!(codes(wheel.cards()), "5h 4s 3c 2d Ah");
+        assert!(wheel < evaluate("2h 3d 4c 5s 6h"));
+        assert!(wheel > evaluate("Ah Ad Ac Ks Qh"));
+
+        let steel_wheel = evaluate("Ah 2h 3h 4h 5h");
+        assert_eq!(steel_wheel.rank(), HandRank::StraightFlush);
+        assert_eq!(steel_wheel.to_string(), "Straight Flush, Five high");
+        assert!(steel_wheel < evaluate("2d 3d 4d 5d 6d"));
+
+        // an ace with a five is not a straight without the 2, 3 and 4
+        assert_eq!(evaluate("Ah 5d 3c 4s 6h").rank(), HandRank::HighCard);
+        assert_eq!(evaluate("Qh Kd Ac 2s 3h").rank(), HandRank::HighCard);
+    }
+
+    // Every one of the 2,598,960 five card hands, checked against the known
+    // number of hands and distinct strengths in each category.
+    #[test]
+    fn test_exhaustive_category_counts() {
+        let mut deck = Deck::new();
+        let cards: Vec<u32> = std::iter::from_fn(|| deck.deal()).map(|card| evaluator::encode(&card)).collect();
+        assert_eq!(cards.len(), 52);
+
+        let mut counts = [0u32; 10];
+        let mut distinct: Vec<HashSet<u16>> = vec![HashSet::new(); 10];
+        for a in 0..52 {
+            for b in a + 1..52 {
+                for c in b + 1..52 {
+                    for d in c + 1..52 {
+                        for e in d + 1..52 {
+                            let strength = evaluator::evaluate_encoded([cards[a], cards[b], cards[c], cards[d], cards[e]]);
+                            let rank = HandRank::of(strength) as usize;
+                            counts[rank] += 1;
+                            distinct[rank].insert(strength.value());
+                        }
+                    }
+                }
+            }
+        }
+
+        let expected = [
+            (HandRank::HighCard, 1_302_540, 1277),
+            (HandRank::OnePair, 1_098_240, 2860),
+            (HandRank::TwoPair, 123_552, 858),
+            (HandRank::ThreeOfAKind, 54_912, 858),
+            (HandRank::Straight, 10_200, 10),
+            (HandRank::Flush, 5_108, 1277),
+            (HandRank::FullHouse, 3_744, 156),
+            (HandRank::FourOfAKind, 624, 156),
+            (HandRank::StraightFlush, 36, 9),
+            (HandRank::RoyalFlush, 4, 1),
+        ];
+        for (rank, hands, classes) in expected.iter() {
+            assert_eq!(counts[*rank as usize], *hands, "{}", rank);
+            assert_eq!(distinct[*rank as usize].len(), *classes, "{}", rank);
+        }
+        assert_eq!(counts.iter().sum::<u32>(), 2_598_960);
+        assert_eq!(distinct.iter().map(|set| set.len()).sum::<usize>(), DISTINCT_HANDS as usize);
+    }
+}
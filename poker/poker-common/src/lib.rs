@@ -1,7 +1,25 @@
+pub mod action;
+pub mod badugi;
 pub mod card;
+pub mod evaluator;
 pub mod game;
+pub mod hand;
+pub mod low;
 pub mod player;
+pub mod protocol;
+pub mod stakes;
+pub mod variant;
+pub mod view;
+pub mod wild;
 
-pub use card::{Card, Suit, Value};
+pub use action::PlayerAction;
+pub use badugi::BadugiHand;
+pub use card::{Card, Deck, Suit, Value};
 pub use game::{Game, GameSession};
+pub use hand::{HandEvaluation, HandRank};
+pub use low::{LowHand, LowballHand, LowballRanking};
 pub use player::Player;
+pub use stakes::{BettingStructure, Stakes};
+pub use variant::Variant;
+pub use view::{LegalAction, Round, SeatView, TableView, Viewer};
+pub use wild::{Joker, WildCards};
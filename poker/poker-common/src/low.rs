@@ -0,0 +1,328 @@
+use std::fmt;
+
+use crate::card::Card;
+
+/// A low hand that qualifies for the low half of a hi-lo pot: five different
+/// ranks, all eight or lower, with aces low. Straights and flushes do not
+/// count against it.
+///
+/// Lower hands are better, so the best low compares smallest: 5-4-3-2-A
+/// beats 6-4-3-2-A, which beats 8-7-6-5-4.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LowHand([u8; 5]);
+
+impl LowHand {
+    /// The five ranks, highest first, with an ace as 1.
+    pub fn ranks(&self) -> [u8; 5] {
+        self.0
+    }
+}
+
+impl fmt::Display for LowHand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let ranks: Vec<&str> = self.0.iter().map(|&rank| rank_symbol(rank)).collect();
+        write!(f, "{}", ranks.join("-"))
+    }
+}
+
+fn rank_symbol(rank: u8) -> &'static str {
+    ["A", "2", "3", "4", "5", "6", "7", "8", "9", "T", "J", "Q", "K", "A"][usize::from(rank) - 1]
+}
+
+/// How a lowball game ranks hands. Either way the best hand compares
+/// smallest, so hands from the same ranking can be compared directly.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LowballRanking {
+    /// Aces are low and straights and flushes do not count: 5-4-3-2-A is the
+    /// nuts. Played in Razz and ace-to-five triple draw.
+    AceToFive,
+    /// Aces are high and straights and flushes count against the hand:
+    /// 7-5-4-3-2 of mixed suits is the nuts. Played in deuce-to-seven draw.
+    DeuceToSeven,
+}
+
+impl LowballRanking {
+    /// Ranks up to five cards. Fewer than five cards compare the same way,
+    /// which ranks the up cards showing in Razz; a straight or flush needs
+    /// all five.
+    pub fn rank(&self, cards: &[Card]) -> LowballHand {
+        assert!(cards.len() <= 5, "a lowball hand has at most five cards");
+        let ace_low = *self == LowballRanking::AceToFive;
+        let mut groups: Vec<(u8, u8)> = Vec::new();
+        for card in cards {
+            let rank = if ace_low { card.value.low_rank() } else { card.value.rank() };
+            match groups.iter_mut().find(|(_, r)| *r == rank) {
+                Some(group) => group.0 += 1,
+                None => groups.push((1, rank)),
+            }
+        }
+        groups.sort_unstable_by(|a, b| b.cmp(a));
+
+        let mut ranks = [0; 5];
+        let mut next = 0;
+        for &(size, rank) in &groups {
+            for _ in 0..size {
+                ranks[next] = rank;
+                next += 1;
+            }
+        }
+
+        let sizes: Vec<u8> = groups.iter().map(|&(size, _)| size).collect();
+        let mut category = match sizes.as_slice() {
+            [4, ..] => Category::Quads,
+            [3, 2] => Category::FullHouse,
+            [3, ..] => Category::Trips,
+            [2, 2, ..] => Category::TwoPair,
+            [2, ..] => Category::Pair,
+            _ => Category::NoPair,
+        };
+        if !ace_low && cards.len() == 5 && category == Category::NoPair {
+            // aces only play high, so A-2-3-4-5 is no straight at deuce-to-seven
+            let straight = ranks[0] - ranks[4] == 4;
+            let flush = cards.iter().all(|card| card.suit == cards[0].suit);
+            category = match (straight, flush) {
+                (true, true) => Category::StraightFlush,
+                (false, true) => Category::Flush,
+                (true, false) => Category::Straight,
+                (false, false) => Category::NoPair,
+            };
+        }
+        LowballHand { category, ranks }
+    }
+
+    /// The best hand that can be made from any five of `cards`, or `None`
+    /// with fewer than five.
+    pub fn best(&self, cards: &[Card]) -> Option<LowballHand> {
+        let n = cards.len();
+        let mut best: Option<LowballHand> = None;
+        for a in 0..n {
+            for b in a + 1..n {
+                for c in b + 1..n {
+                    for d in c + 1..n {
+                        for e in d + 1..n {
+                            let five = [&cards[a], &cards[b], &cards[c], &cards[d], &cards[e]].map(|card| card.clone());
+                            let hand = self.rank(&five);
+                            if best.is_none_or(|current| hand < current) {
+                                best = Some(hand);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        best
+    }
+}
+
+// Lowball categories from best to worst. Straights and flushes only ever
+// appear at deuce-to-seven.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum Category {
+    NoPair,
+    Pair,
+    TwoPair,
+    Trips,
+    Straight,
+    Flush,
+    FullHouse,
+    Quads,
+    StraightFlush,
+}
+
+/// A hand ranked for lowball by a [`LowballRanking`]: pairs, and at
+/// deuce-to-seven straights and flushes, make a hand worse. As with
+/// [`LowHand`], the best hand compares smallest.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LowballHand {
+    category: Category,
+    // grouped as the hand reads, highest first within a group
+    ranks: [u8; 5],
+}
+
+impl LowballHand {
+    /// The ranks, grouped as the hand reads: 8-8-5-3-2 for a pair of eights.
+    pub fn ranks(&self) -> Vec<u8> {
+        self.ranks.iter().copied().filter(|&rank| rank > 0).collect()
+    }
+
+    /// Whether the hand has no pair, straight or flush against it.
+    pub fn is_no_pair(&self) -> bool {
+        self.category == Category::NoPair
+    }
+}
+
+impl fmt::Display for LowballHand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let ranks: Vec<&str> = self.ranks().iter().map(|&rank| rank_symbol(rank)).collect();
+        write!(f, "{}", ranks.join("-"))
+    }
+}
+
+/// Ranks up to five cards for ace-to-five lowball.
+pub fn ace_to_five(cards: &[Card]) -> LowballHand {
+    LowballRanking::AceToFive.rank(cards)
+}
+
+/// The best ace-to-five low that can be made from any five of `cards`, or
+/// `None` with fewer than five.
+pub fn best_ace_to_five(cards: &[Card]) -> Option<LowballHand> {
+    LowballRanking::AceToFive.best(cards)
+}
+
+/// Ranks five cards for deuce-to-seven lowball.
+pub fn deuce_to_seven(cards: &[Card]) -> LowballHand {
+    LowballRanking::DeuceToSeven.rank(cards)
+}
+
+/// The best eight-or-better low that can be made from any five of `cards`.
+pub fn best_low(cards: &[Card]) -> Option<LowHand> {
+    let mut ranks: Vec<u8> = cards.iter().map(|c| c.value.low_rank()).filter(|&rank| rank <= 8).collect();
+    ranks.sort_unstable();
+    ranks.dedup();
+    if ranks.len() < 5 {
+        return None;
+    }
+    Some(LowHand([ranks[4], ranks[3], ranks[2], ranks[1], ranks[0]]))
+}
+
+/// The best eight-or-better low using exactly two of the `hole` cards and
+/// three of the `board`, as Omaha Hi-Lo requires.
+pub fn best_omaha_low(hole: &[Card], board: &[Card]) -> Option<LowHand> {
+    let mut best: Option<LowHand> = None;
+    for a in 0..hole.len() {
+        for b in a + 1..hole.len() {
+            for c in 0..board.len() {
+                for d in c + 1..board.len() {
+                    for e in d + 1..board.len() {
+                        let five = [&hole[a], &hole[b], &board[c], &board[d], &board[e]].map(|card| card.clone());
+                        if let Some(low) = best_low(&five) {
+                            if best.is_none_or(|current| low < current) {
+                                best = Some(low);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::card::cards;
+    use crate::low::{ace_to_five, best_ace_to_five, best_low, best_omaha_low, deuce_to_seven, LowballRanking};
+
+    #[test]
+    fn test_qualifying_lows() {
+        let wheel = best_low(&cards("Ah 2h 3h 4h 5h")).unwrap();
+        assert_eq!(wheel.to_string(), "5-4-3-2-A");
+        assert!(wheel < best_low(&cards("6d 4c 3s 2h Ad")).unwrap());
+        assert!(best_low(&cards("8d 7c 6s 5h 4d")).unwrap() > best_low(&cards("8d 7c 6s 4h 3d")).unwrap());
+
+        // a nine, a pair or fewer than five low cards does not qualify
+        assert!(best_low(&cards("9d 4c 3s 2h Ad")).is_none());
+        assert!(best_low(&cards("4d 4c 3s 2h Ad")).is_none());
+        assert!(best_low(&cards("Kd 7c 3s 2h Ad Ac Kh")).is_none());
+    }
+
+    #[test]
+    fn test_best_low_of_seven() {
+        let low = best_low(&cards("Kd 7c 3s 2h Ad 6c 7h")).unwrap();
+        assert_eq!(low.ranks(), [7, 6, 3, 2, 1]);
+    }
+
+    #[test]
+    fn test_omaha_low_uses_exactly_two_hole_cards() {
+        // the board holds three low cards, the hand must supply the other two
+        let low = best_omaha_low(&cards("Ah 2d Ks Kd"), &cards("3c 5h 8d Qc Jh")).unwrap();
+        assert_eq!(low.to_string(), "8-5-3-2-A");
+
+        // a single low card in the hand cannot make a low, however good the board
+        assert!(best_omaha_low(&cards("Ah Kd Ks Qd"), &cards("2c 3h 4d 5c Jh")).is_none());
+
+        // nor can two low cards that pair the board
+        assert!(best_omaha_low(&cards("Ah 2d Ks Kd"), &cards("Ac 2h 7d Qc Jh")).is_none());
+    }
+
+    #[test]
+    fn test_ace_to_five_ignores_straights_and_flushes() {
+        let wheel = ace_to_five(&cards("Ah 2h 3h 4h 5h"));
+        assert_eq!(wheel.to_string(), "5-4-3-2-A");
+        assert!(wheel < ace_to_five(&cards("6d 4c 3s 2h Ad")));
+        assert!(ace_to_five(&cards("Kd Qc Js 9h 8d")).is_no_pair());
+    }
+
+    #[test]
+    fn test_ace_to_five_pairs_rank_below_any_no_pair() {
+        let king_high = ace_to_five(&cards("Kd Qc Js 9h 8d"));
+        let pair = ace_to_five(&cards("2d 2c 3s 4h 5d"));
+        assert!(king_high < pair);
+        assert_eq!(pair.to_string(), "2-2-5-4-3");
+        // the lower pair is better, then the lower kickers
+        assert!(ace_to_five(&cards("5d 5c Ks Qh Jd")) < ace_to_five(&cards("6d 6c 3s 2h Ad")));
+        assert!(ace_to_five(&cards("Kd Kc 2s 2h Qd")) > ace_to_five(&cards("Kd Kc Qs Jh 9d")));
+        assert!(ace_to_five(&cards("Kd Kc 2s 2h Qd")) < ace_to_five(&cards("2d 2c 2s 3h 4d")));
+    }
+
+    #[test]
+    fn test_ace_to_five_partial_hands() {
+        // up cards in Razz: the lowest board showing acts first
+        assert!(ace_to_five(&cards("Ah 4d")) < ace_to_five(&cards("2h 5d")));
+        assert!(ace_to_five(&cards("Kh Qd Jc")) < ace_to_five(&cards("2h 2d 3c")));
+    }
+
+    #[test]
+    fn test_best_ace_to_five_of_seven() {
+        let low = best_ace_to_five(&cards("Kd 7c 3s 2h Ad 6c 7h")).unwrap();
+        assert_eq!(low.to_string(), "7-6-3-2-A");
+        // forced to play a pair when there are not five different ranks
+        let paired = best_ace_to_five(&cards("Ad Ac 2s 2h 3d 3c 4h")).unwrap();
+        assert_eq!(paired.to_string(), "A-A-4-3-2");
+        assert!(best_ace_to_five(&cards("Ad 2c 3s 4h")).is_none());
+    }
+
+    #[test]
+    fn test_deuce_to_seven_nuts() {
+        let nuts = deuce_to_seven(&cards("7d 5c 4s 3h 2d"));
+        assert_eq!(nuts.to_string(), "7-5-4-3-2");
+        assert!(nuts.is_no_pair());
+        assert!(nuts < deuce_to_seven(&cards("7d 6c 4s 3h 2d")));
+        assert!(deuce_to_seven(&cards("7d 6c 5s 4h 2d")) < deuce_to_seven(&cards("8d 5c 4s 3h 2d")));
+    }
+
+    #[test]
+    fn test_deuce_to_seven_aces_are_high() {
+        // A-2-3-4-5 is no straight, just ace high, worse than any king high
+        let ace_high = deuce_to_seven(&cards("Ad 2c 3s 4h 5d"));
+        assert!(ace_high.is_no_pair());
+        assert_eq!(ace_high.to_string(), "A-5-4-3-2");
+        assert!(deuce_to_seven(&cards("Kd Qc Js Th 8d")) < ace_high);
+        // a pair of aces is the worst pair
+        assert!(deuce_to_seven(&cards("Kd Kc 4s 3h 2d")) < deuce_to_seven(&cards("Ad Ac 4s 3h 2d")));
+    }
+
+    #[test]
+    fn test_deuce_to_seven_straights_and_flushes_count_against() {
+        let ace_high = deuce_to_seven(&cards("Ad Kc Qs Jh 9d"));
+        let straight = deuce_to_seven(&cards("6d 5c 4s 3h 2d"));
+        let flush = deuce_to_seven(&cards("7h 5h 4h 3h 2h"));
+        assert!(!straight.is_no_pair());
+        assert!(!flush.is_no_pair());
+        assert!(ace_high < straight);
+        assert!(deuce_to_seven(&cards("Ad Ac As Kh Qd")) < straight);
+        assert!(straight < flush);
+        assert!(flush < deuce_to_seven(&cards("2d 2c 2s 3h 3d")));
+        assert!(deuce_to_seven(&cards("Ad Ac As Ah 2d")) < deuce_to_seven(&cards("6h 5h 4h 3h 2h")));
+    }
+
+    #[test]
+    fn test_rankings_disagree_on_the_wheel() {
+        let wheel = cards("Ah 2d 3c 4s 5h");
+        let seven = cards("7h 5d 4c 3s 2h");
+        assert!(LowballRanking::AceToFive.rank(&wheel) < LowballRanking::AceToFive.rank(&seven));
+        assert!(LowballRanking::DeuceToSeven.rank(&seven) < LowballRanking::DeuceToSeven.rank(&wheel));
+        let best = LowballRanking::DeuceToSeven.best(&cards("Ah 2d 3c 4s 5h 7c 9d")).unwrap();
+        assert_eq!(best.to_string(), "7-5-4-3-2");
+    }
+}
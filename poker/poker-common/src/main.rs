@@ -5,3 +5,4 @@ fn main() {
 pub mod player;
 pub mod game;
 pub mod card;
+pub mod variant;
@@ -1,17 +1,23 @@
 use crate::game::Game;
 use crate::card::{Card, Suit, Value};
 
+#[derive(Clone)]
 pub struct Player {
     name: String,
     hand: Vec<Card>,
+    // whether each card in `hand` was dealt face up, for stud games
+    face_up: Vec<bool>,
     id: u32,
     total_chips: u32,
     current_bet: u32,
     is_active: bool,
+    // dealt out of every hand until they sit back in
+    sitting_out: bool,
     games_played: Vec<Game>,
     player_stats: Stats,
 }
 
+#[derive(Clone)]
 pub struct Stats {
     games_played: u32,
     games_won: u32,
@@ -25,6 +31,7 @@ impl Player {
         Player {
             name,
             hand: Vec::new(),
+            face_up: Vec::new(),
             id,
             total_chips,
             current_bet: 0,
@@ -37,6 +44,7 @@ impl Player {
                 total_chips_won: 0,
             },
             is_active: false,
+            sitting_out: false,
         }
     }
 
@@ -45,6 +53,10 @@ impl Player {
         &self.name
     }
 
+    pub fn get_id(&self) -> u32 {
+        self.id
+    }
+
     pub fn get_hand(&self) -> &Vec<Card> {
         &self.hand
     }
@@ -53,10 +65,18 @@ impl Player {
         self.total_chips
     }
 
+    pub fn get_current_bet(&self) -> u32 {
+        self.current_bet
+    }
+
     pub fn is_active(&self) -> bool {
         self.is_active
     }
 
+    pub fn is_sitting_out(&self) -> bool {
+        self.sitting_out
+    }
+
     pub fn get_stats(&self) -> &Stats {
         &self.player_stats
     }
@@ -70,29 +90,75 @@ impl Player {
         self.total_chips += chips;
     }
 
-    pub fn remove_chips(&mut self, chips: u32) {
+    /// Takes chips from the player's stack. Returns false, leaving the stack
+    /// untouched, if the player does not have that many chips.
+    pub fn remove_chips(&mut self, chips: u32) -> bool {
+        if chips > self.total_chips {
+            return false;
+        }
         self.total_chips -= chips;
+        true
+    }
+
+    /// Moves chips from the player's stack into their bet for the current round.
+    pub fn place_bet(&mut self, chips: u32) -> bool {
+        if !self.remove_chips(chips) {
+            return false;
+        }
+        self.current_bet += chips;
+        true
+    }
+
+    pub fn is_all_in(&self) -> bool {
+        self.is_active && self.total_chips == 0
+    }
+
+    pub fn set_current_bet(&mut self, bet: u32) {
+        self.current_bet = bet;
     }
 
     pub fn set_active(&mut self, active: bool) {
         self.is_active = active;
     }
 
+    pub fn set_sitting_out(&mut self, sitting_out: bool) {
+        self.sitting_out = sitting_out;
+    }
+
+    /// Deals the player a card face down, seen only by them.
     pub fn add_card(&mut self, card: Card) {
         self.hand.push(card);
+        self.face_up.push(false);
+    }
+
+    /// Deals the player a card face up, for everyone at the table to see.
+    pub fn add_face_up_card(&mut self, card: Card) {
+        self.hand.push(card);
+        self.face_up.push(true);
+    }
+
+    pub fn is_face_up(&self, index: usize) -> bool {
+        self.face_up.get(index).copied().unwrap_or(false)
+    }
+
+    /// The player's face up cards, in the order they were dealt.
+    pub fn get_up_cards(&self) -> Vec<Card> {
+        self.hand.iter().zip(&self.face_up).filter(|(_, &up)| up).map(|(card, _)| card.clone()).collect()
     }
 
     pub fn clear_hand(&mut self) {
         self.hand.clear();
+        self.face_up.clear();
     }
 
     pub fn remove_card(&mut self, suit: Suit, value: Value) -> bool{
         let index = self.hand.iter().position(|card| card.suit == suit && card.value == value);
         if let Some(index) = index {
             self.hand.remove(index);
+            self.face_up.remove(index);
             return true;
         }
-        return false;
+        false
     }
 
     pub fn game_won(&mut self, chips: u32) {
@@ -123,18 +189,51 @@ impl Player {
 
 #[cfg(test)]
 mod tests {
-    use super::*;
-    use crate::game::Game;
+    use crate::card::{Card, Suit, Value};
     use crate::player::Player;
-    use crate::player::Stats;
 
     #[test]
     fn test_player_new() {
         let player = Player::new("John".to_owned(), 1, 1000);
         assert_eq!(player.get_name(), "John");
         assert_eq!(player.get_total_chips(), 1000);
-        assert_eq!(player.is_active(), false);
+        assert!(!player.is_active());
     }
-}
 
+    #[test]
+    fn test_remove_chips_cannot_underflow() {
+        let mut player = Player::new("John".to_owned(), 1, 100);
+        assert!(!player.remove_chips(101));
+        assert_eq!(player.get_total_chips(), 100);
+        assert!(player.remove_chips(100));
+        assert_eq!(player.get_total_chips(), 0);
+    }
+
+    #[test]
+    fn test_place_bet() {
+        let mut player = Player::new("John".to_owned(), 1, 100);
+        assert!(player.place_bet(30));
+        assert!(player.place_bet(20));
+        assert_eq!(player.get_current_bet(), 50);
+        assert_eq!(player.get_total_chips(), 50);
+        assert!(!player.place_bet(51));
+        assert_eq!(player.get_current_bet(), 50);
+    }
 
+    #[test]
+    fn test_face_up_cards() {
+        let mut player = Player::new("John".to_owned(), 1, 100);
+        player.add_card(Card::new(Suit::Hearts, Value::Ace));
+        player.add_face_up_card(Card::new(Suit::Clubs, Value::Two));
+        player.add_face_up_card(Card::new(Suit::Spades, Value::Nine));
+        assert!(!player.is_face_up(0));
+        assert!(player.is_face_up(1));
+        assert_eq!(player.get_up_cards(), vec![Card::new(Suit::Clubs, Value::Two), Card::new(Suit::Spades, Value::Nine)]);
+
+        // the remaining cards keep their own state when one is removed
+        assert!(player.remove_card(Suit::Hearts, Value::Ace));
+        assert!(player.is_face_up(0));
+        player.clear_hand();
+        assert!(player.get_up_cards().is_empty());
+    }
+}
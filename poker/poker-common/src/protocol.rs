@@ -0,0 +1,1048 @@
+use std::fmt;
+use std::io::{self, Read, Write};
+
+use crate::action::PlayerAction;
+use crate::card::{Card, Suit, Value};
+use crate::stakes::{BettingStructure, Stakes};
+use crate::variant::Variant;
+use crate::view::{LegalAction, Round, SeatView, TableView, Viewer};
+
+/// Bumped whenever a message is added or changes shape. Client and server
+/// exchange it in `Hello` and `Welcome` before anything else.
+pub const PROTOCOL_VERSION: u16 = 8;
+
+/// The largest frame either side will accept, so a bad length prefix can't
+/// make the reader allocate without limit.
+pub const MAX_FRAME_LEN: usize = 64 * 1024;
+
+/// Everything a client can send to the server.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ClientMessage {
+    /// The first message on a connection, with the client's protocol version.
+    Hello { version: u16 },
+    Username(String),
+    Password(String),
+    /// Logs back in with the token from an earlier `LoggedIn`, as after a
+    /// dropped connection, instead of a username and password.
+    Resume { token: String },
+    ListTables,
+    /// Opens a new table in the lobby. `trips_beat_straight` is only read
+    /// by short deck tables.
+    CreateTable { variant: Variant, stakes: Stakes, structure: BettingStructure, max_seats: u32, trips_beat_straight: bool },
+    /// Takes a seat at a table, bringing `buy_in` chips from the account.
+    JoinTable { table: u32, buy_in: u32 },
+    /// Gives up the seat and cashes out, but keeps watching the table.
+    StandUp { table: u32 },
+    /// Watches a table without taking a seat.
+    Watch { table: u32 },
+    /// Stands up if seated and stops watching.
+    LeaveTable { table: u32 },
+    /// Keeps the seat but sits out of the hands from the next one on.
+    SitOut { table: u32 },
+    /// Comes back from sitting out, by choice or after timing out.
+    SitIn { table: u32 },
+    Action { table: u32, action: PlayerAction },
+    Draw { table: u32, discards: Vec<Card> },
+    Chat { table: u32, text: String },
+    Quit,
+}
+
+/// Everything the server can send to a client.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ServerMessage {
+    /// The reply to `Hello`, with the server's protocol version.
+    Welcome { version: u16 },
+    /// The username is free; the password sent next registers it.
+    NewUser,
+    /// The username is taken; the password sent next logs in to it.
+    ReturningUser,
+    /// Login succeeded. The token resumes this session from a new connection.
+    LoggedIn { chips: u32, token: String },
+    LoginFailed(String),
+    Tables(Vec<TableSummary>),
+    TableCreated { table: u32 },
+    /// Pushed to everyone logged in whenever a table opens or changes.
+    TableUpdated(TableSummary),
+    /// Pushed to everyone logged in when a table closes after standing empty.
+    TableClosed { table: u32 },
+    Seated { table: u32, seat: u32 },
+    /// Stood up from `table`, with the chips returned to the account.
+    StoodUp { table: u32, chips: u32 },
+    Watching { table: u32 },
+    Left { table: u32 },
+    /// The table as the receiving player sees it, sent whenever it changes.
+    TableState { table: u32, view: TableView },
+    Chat { table: u32, from: String, text: String },
+    /// The action clock is running out on the receiving player.
+    ClockWarning { table: u32, seconds_left: u32 },
+    /// How a hand ended, sent before the table resets for the next one.
+    /// `shown` is empty when everyone else folded.
+    HandResult { table: u32, awards: Vec<Award>, shown: Vec<ShownHand> },
+    Error(String),
+    Goodbye,
+}
+
+/// One line of the lobby's table list.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TableSummary {
+    pub id: u32,
+    pub variant: Variant,
+    pub stakes: Stakes,
+    pub players: u32,
+    pub max_seats: u32,
+    /// The average pot over the table's recent hands, zero before the first.
+    pub average_pot: u32,
+}
+
+/// Chips won by one seat at the end of a hand, over every pot it won.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Award {
+    pub seat: u32,
+    pub name: String,
+    pub chips: u32,
+}
+
+/// A hand turned face up at the showdown.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ShownHand {
+    pub seat: u32,
+    pub name: String,
+    pub cards: Vec<Card>,
+}
+
+#[derive(Debug)]
+pub enum ProtocolError {
+    Io(io::Error),
+    /// The length prefix is larger than `MAX_FRAME_LEN`.
+    FrameTooLarge(usize),
+    /// The frame ended before the message did.
+    Truncated,
+    /// The message was complete with bytes left over in the frame.
+    TrailingBytes(usize),
+    UnknownTag { field: &'static str, tag: u8 },
+    InvalidUtf8,
+}
+
+impl fmt::Display for ProtocolError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ProtocolError::Io(err) => write!(f, "connection error: {}", err),
+            ProtocolError::FrameTooLarge(len) => write!(f, "frame of {} bytes is over the {} byte limit", len, MAX_FRAME_LEN),
+            ProtocolError::Truncated => write!(f, "message ends early"),
+            ProtocolError::TrailingBytes(count) => write!(f, "{} bytes after the end of the message", count),
+            ProtocolError::UnknownTag { field, tag } => write!(f, "unknown {} tag {}", field, tag),
+            ProtocolError::InvalidUtf8 => write!(f, "text is not valid UTF-8"),
+        }
+    }
+}
+
+impl std::error::Error for ProtocolError {}
+
+impl From<io::Error> for ProtocolError {
+    fn from(err: io::Error) -> Self {
+        ProtocolError::Io(err)
+    }
+}
+
+/// A message that can be sent in a frame.
+pub trait Message: Sized {
+    fn encode(&self) -> Vec<u8>;
+
+    /// Decodes a whole frame, which must hold exactly one message.
+    fn decode(bytes: &[u8]) -> Result<Self, ProtocolError>;
+}
+
+/// Writes one frame: the payload's length as four big-endian bytes, then
+/// the payload.
+pub fn write_frame<W: Write>(writer: &mut W, payload: &[u8]) -> Result<(), ProtocolError> {
+    if payload.len() > MAX_FRAME_LEN {
+        return Err(ProtocolError::FrameTooLarge(payload.len()));
+    }
+    let mut frame = Vec::with_capacity(4 + payload.len());
+    frame.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+    frame.extend_from_slice(payload);
+    writer.write_all(&frame)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Reads one frame, however the bytes were split or merged on the way, and
+/// returns its payload. Returns `None` if the connection closed cleanly
+/// between frames.
+pub fn read_frame<R: Read>(reader: &mut R) -> Result<Option<Vec<u8>>, ProtocolError> {
+    let mut prefix = [0; 4];
+    let mut filled = 0;
+    while filled < prefix.len() {
+        match reader.read(&mut prefix[filled..]) {
+            Ok(0) if filled == 0 => return Ok(None),
+            Ok(0) => return Err(ProtocolError::Io(io::ErrorKind::UnexpectedEof.into())),
+            Ok(n) => filled += n,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err.into()),
+        }
+    }
+    let len = u32::from_be_bytes(prefix) as usize;
+    if len > MAX_FRAME_LEN {
+        return Err(ProtocolError::FrameTooLarge(len));
+    }
+    let mut payload = vec![0; len];
+    reader.read_exact(&mut payload)?;
+    Ok(Some(payload))
+}
+
+/// Encodes `message` and writes it as one frame.
+pub fn send<W: Write, M: Message>(writer: &mut W, message: &M) -> Result<(), ProtocolError> {
+    write_frame(writer, &message.encode())
+}
+
+/// Reads the next frame and decodes it, or `None` if the connection closed.
+pub fn receive<R: Read, M: Message>(reader: &mut R) -> Result<Option<M>, ProtocolError> {
+    match read_frame(reader)? {
+        Some(payload) => M::decode(&payload).map(Some),
+        None => Ok(None),
+    }
+}
+
+impl Message for ClientMessage {
+    fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.write(&mut out);
+        out
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Self, ProtocolError> {
+        decode_all(bytes)
+    }
+}
+
+impl Message for ServerMessage {
+    fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.write(&mut out);
+        out
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Self, ProtocolError> {
+        decode_all(bytes)
+    }
+}
+
+fn decode_all<T: Wire>(bytes: &[u8]) -> Result<T, ProtocolError> {
+    let mut input = Input { bytes, pos: 0 };
+    let value = T::read(&mut input)?;
+    match bytes.len() - input.pos {
+        0 => Ok(value),
+        left => Err(ProtocolError::TrailingBytes(left)),
+    }
+}
+
+// The bytes of a frame still to be decoded.
+struct Input<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Input<'_> {
+    fn take(&mut self, count: usize) -> Result<&[u8], ProtocolError> {
+        let end = self.pos.checked_add(count).filter(|&end| end <= self.bytes.len()).ok_or(ProtocolError::Truncated)?;
+        let taken = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(taken)
+    }
+
+    fn tag(&mut self) -> Result<u8, ProtocolError> {
+        u8::read(self)
+    }
+}
+
+// How each type is laid out in a frame. Integers are big-endian, text and
+// lists are prefixed with their length, and enums with a one byte tag.
+trait Wire: Sized {
+    fn write(&self, out: &mut Vec<u8>);
+    fn read(input: &mut Input) -> Result<Self, ProtocolError>;
+}
+
+impl Wire for u8 {
+    fn write(&self, out: &mut Vec<u8>) {
+        out.push(*self);
+    }
+
+    fn read(input: &mut Input) -> Result<Self, ProtocolError> {
+        Ok(input.take(1)?[0])
+    }
+}
+
+impl Wire for u16 {
+    fn write(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.to_be_bytes());
+    }
+
+    fn read(input: &mut Input) -> Result<Self, ProtocolError> {
+        let bytes = input.take(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+}
+
+impl Wire for u32 {
+    fn write(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.to_be_bytes());
+    }
+
+    fn read(input: &mut Input) -> Result<Self, ProtocolError> {
+        let bytes = input.take(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+}
+
+// Seats and counts go over the wire as u32 whatever the platform's usize.
+impl Wire for usize {
+    fn write(&self, out: &mut Vec<u8>) {
+        (*self as u32).write(out);
+    }
+
+    fn read(input: &mut Input) -> Result<Self, ProtocolError> {
+        Ok(u32::read(input)? as usize)
+    }
+}
+
+impl Wire for bool {
+    fn write(&self, out: &mut Vec<u8>) {
+        u8::from(*self).write(out);
+    }
+
+    fn read(input: &mut Input) -> Result<Self, ProtocolError> {
+        match input.tag()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            tag => Err(ProtocolError::UnknownTag { field: "bool", tag }),
+        }
+    }
+}
+
+impl Wire for String {
+    fn write(&self, out: &mut Vec<u8>) {
+        self.len().write(out);
+        out.extend_from_slice(self.as_bytes());
+    }
+
+    fn read(input: &mut Input) -> Result<Self, ProtocolError> {
+        let len = usize::read(input)?;
+        let bytes = input.take(len)?;
+        String::from_utf8(bytes.to_vec()).map_err(|_| ProtocolError::InvalidUtf8)
+    }
+}
+
+impl<T: Wire> Wire for Option<T> {
+    fn write(&self, out: &mut Vec<u8>) {
+        match self {
+            None => out.push(0),
+            Some(value) => {
+                out.push(1);
+                value.write(out);
+            }
+        }
+    }
+
+    fn read(input: &mut Input) -> Result<Self, ProtocolError> {
+        match input.tag()? {
+            0 => Ok(None),
+            1 => Ok(Some(T::read(input)?)),
+            tag => Err(ProtocolError::UnknownTag { field: "option", tag }),
+        }
+    }
+}
+
+impl<T: Wire> Wire for Vec<T> {
+    fn write(&self, out: &mut Vec<u8>) {
+        self.len().write(out);
+        for item in self {
+            item.write(out);
+        }
+    }
+
+    fn read(input: &mut Input) -> Result<Self, ProtocolError> {
+        let len = usize::read(input)?;
+        // every item takes at least a byte, so a bad count fails here
+        // rather than in the allocation
+        if len > input.bytes.len() - input.pos {
+            return Err(ProtocolError::Truncated);
+        }
+        (0..len).map(|_| T::read(input)).collect()
+    }
+}
+
+// Enums without data are sent as their index in the type's `ALL` list.
+fn write_index<T: PartialEq>(all: &[T], value: &T, out: &mut Vec<u8>) {
+    let index = all.iter().position(|item| item == value).expect("every value is listed in ALL");
+    out.push(index as u8);
+}
+
+fn read_index<T: Clone>(all: &[T], field: &'static str, input: &mut Input) -> Result<T, ProtocolError> {
+    let tag = input.tag()?;
+    all.get(usize::from(tag)).cloned().ok_or(ProtocolError::UnknownTag { field, tag })
+}
+
+impl Wire for Variant {
+    fn write(&self, out: &mut Vec<u8>) {
+        write_index(&Variant::ALL, self, out);
+    }
+
+    fn read(input: &mut Input) -> Result<Self, ProtocolError> {
+        read_index(&Variant::ALL, "variant", input)
+    }
+}
+
+impl Wire for Round {
+    fn write(&self, out: &mut Vec<u8>) {
+        write_index(&Round::ALL, self, out);
+    }
+
+    fn read(input: &mut Input) -> Result<Self, ProtocolError> {
+        read_index(&Round::ALL, "round", input)
+    }
+}
+
+// A card is its suit and its rank, with the joker as rank 15.
+impl Wire for Card {
+    fn write(&self, out: &mut Vec<u8>) {
+        write_index(&Suit::ALL, &self.suit, out);
+        out.push(self.value.rank());
+    }
+
+    fn read(input: &mut Input) -> Result<Self, ProtocolError> {
+        let suit = read_index(&Suit::ALL, "suit", input)?;
+        let value = match input.tag()? {
+            15 => Value::Joker,
+            rank @ 2..=14 => Value::ALL[usize::from(rank) - 2].clone(),
+            tag => return Err(ProtocolError::UnknownTag { field: "card value", tag }),
+        };
+        Ok(Card::new(suit, value))
+    }
+}
+
+impl Wire for PlayerAction {
+    fn write(&self, out: &mut Vec<u8>) {
+        match self {
+            PlayerAction::Fold => out.push(0),
+            PlayerAction::Check => out.push(1),
+            PlayerAction::Call => out.push(2),
+            PlayerAction::Bet(amount) => {
+                out.push(3);
+                amount.write(out);
+            }
+            PlayerAction::Raise(amount) => {
+                out.push(4);
+                amount.write(out);
+            }
+            PlayerAction::AllIn => out.push(5),
+        }
+    }
+
+    fn read(input: &mut Input) -> Result<Self, ProtocolError> {
+        Ok(match input.tag()? {
+            0 => PlayerAction::Fold,
+            1 => PlayerAction::Check,
+            2 => PlayerAction::Call,
+            3 => PlayerAction::Bet(u32::read(input)?),
+            4 => PlayerAction::Raise(u32::read(input)?),
+            5 => PlayerAction::AllIn,
+            tag => return Err(ProtocolError::UnknownTag { field: "action", tag }),
+        })
+    }
+}
+
+impl Wire for LegalAction {
+    fn write(&self, out: &mut Vec<u8>) {
+        match self {
+            LegalAction::Fold => out.push(0),
+            LegalAction::Check => out.push(1),
+            LegalAction::Call(amount) => {
+                out.push(2);
+                amount.write(out);
+            }
+            LegalAction::Bet { min, max } => {
+                out.push(3);
+                min.write(out);
+                max.write(out);
+            }
+            LegalAction::Raise { min, max } => {
+                out.push(4);
+                min.write(out);
+                max.write(out);
+            }
+        }
+    }
+
+    fn read(input: &mut Input) -> Result<Self, ProtocolError> {
+        Ok(match input.tag()? {
+            0 => LegalAction::Fold,
+            1 => LegalAction::Check,
+            2 => LegalAction::Call(u32::read(input)?),
+            3 => LegalAction::Bet { min: u32::read(input)?, max: u32::read(input)? },
+            4 => LegalAction::Raise { min: u32::read(input)?, max: u32::read(input)? },
+            tag => return Err(ProtocolError::UnknownTag { field: "legal action", tag }),
+        })
+    }
+}
+
+impl Wire for SeatView {
+    fn write(&self, out: &mut Vec<u8>) {
+        self.name.write(out);
+        self.chips.write(out);
+        self.current_bet.write(out);
+        self.in_hand.write(out);
+        self.sitting_out.write(out);
+        self.cards.write(out);
+        self.up_cards.write(out);
+        self.hand.write(out);
+    }
+
+    fn read(input: &mut Input) -> Result<Self, ProtocolError> {
+        Ok(SeatView {
+            name: String::read(input)?,
+            chips: u32::read(input)?,
+            current_bet: u32::read(input)?,
+            in_hand: bool::read(input)?,
+            sitting_out: bool::read(input)?,
+            cards: usize::read(input)?,
+            up_cards: Vec::read(input)?,
+            hand: Vec::read(input)?,
+        })
+    }
+}
+
+impl Wire for Viewer {
+    fn write(&self, out: &mut Vec<u8>) {
+        match self {
+            Viewer::Seat(seat) => {
+                out.push(0);
+                seat.write(out);
+            }
+            Viewer::Spectator => out.push(1),
+            Viewer::Admin => out.push(2),
+        }
+    }
+
+    fn read(input: &mut Input) -> Result<Self, ProtocolError> {
+        Ok(match input.tag()? {
+            0 => Viewer::Seat(usize::read(input)?),
+            1 => Viewer::Spectator,
+            2 => Viewer::Admin,
+            tag => return Err(ProtocolError::UnknownTag { field: "viewer", tag }),
+        })
+    }
+}
+
+impl Wire for TableView {
+    fn write(&self, out: &mut Vec<u8>) {
+        self.variant.write(out);
+        self.round.write(out);
+        self.button.write(out);
+        self.to_act.write(out);
+        self.pot.write(out);
+        self.current_bet.write(out);
+        self.board.write(out);
+        self.seats.write(out);
+        self.viewer.write(out);
+        self.legal_actions.write(out);
+    }
+
+    fn read(input: &mut Input) -> Result<Self, ProtocolError> {
+        Ok(TableView {
+            variant: Variant::read(input)?,
+            round: Round::read(input)?,
+            button: usize::read(input)?,
+            to_act: Option::read(input)?,
+            pot: u32::read(input)?,
+            current_bet: u32::read(input)?,
+            board: Vec::read(input)?,
+            seats: Vec::read(input)?,
+            viewer: Viewer::read(input)?,
+            legal_actions: Vec::read(input)?,
+        })
+    }
+}
+
+impl Wire for Stakes {
+    fn write(&self, out: &mut Vec<u8>) {
+        self.ante.write(out);
+        self.small_blind.write(out);
+        self.big_blind.write(out);
+        self.bring_in.write(out);
+    }
+
+    fn read(input: &mut Input) -> Result<Self, ProtocolError> {
+        Ok(Stakes {
+            ante: u32::read(input)?,
+            small_blind: u32::read(input)?,
+            big_blind: u32::read(input)?,
+            bring_in: u32::read(input)?,
+        })
+    }
+}
+
+impl Wire for BettingStructure {
+    fn write(&self, out: &mut Vec<u8>) {
+        match self {
+            BettingStructure::NoLimit => out.push(0),
+            BettingStructure::PotLimit => out.push(1),
+            BettingStructure::FixedLimit { small_bet, big_bet, max_raises } => {
+                out.push(2);
+                small_bet.write(out);
+                big_bet.write(out);
+                max_raises.write(out);
+            }
+            BettingStructure::SpreadLimit { min, max } => {
+                out.push(3);
+                min.write(out);
+                max.write(out);
+            }
+        }
+    }
+
+    fn read(input: &mut Input) -> Result<Self, ProtocolError> {
+        Ok(match input.tag()? {
+            0 => BettingStructure::NoLimit,
+            1 => BettingStructure::PotLimit,
+            2 => BettingStructure::FixedLimit {
+                small_bet: u32::read(input)?,
+                big_bet: u32::read(input)?,
+                max_raises: u32::read(input)?,
+            },
+            3 => BettingStructure::SpreadLimit { min: u32::read(input)?, max: u32::read(input)? },
+            tag => return Err(ProtocolError::UnknownTag { field: "betting structure", tag }),
+        })
+    }
+}
+
+impl Wire for TableSummary {
+    fn write(&self, out: &mut Vec<u8>) {
+        self.id.write(out);
+        self.variant.write(out);
+        self.stakes.write(out);
+        self.players.write(out);
+        self.max_seats.write(out);
+        self.average_pot.write(out);
+    }
+
+    fn read(input: &mut Input) -> Result<Self, ProtocolError> {
+        Ok(TableSummary {
+            id: u32::read(input)?,
+            variant: Variant::read(input)?,
+            stakes: Stakes::read(input)?,
+            players: u32::read(input)?,
+            max_seats: u32::read(input)?,
+            average_pot: u32::read(input)?,
+        })
+    }
+}
+
+impl Wire for Award {
+    fn write(&self, out: &mut Vec<u8>) {
+        self.seat.write(out);
+        self.name.write(out);
+        self.chips.write(out);
+    }
+
+    fn read(input: &mut Input) -> Result<Self, ProtocolError> {
+        Ok(Award { seat: u32::read(input)?, name: String::read(input)?, chips: u32::read(input)? })
+    }
+}
+
+impl Wire for ShownHand {
+    fn write(&self, out: &mut Vec<u8>) {
+        self.seat.write(out);
+        self.name.write(out);
+        self.cards.write(out);
+    }
+
+    fn read(input: &mut Input) -> Result<Self, ProtocolError> {
+        Ok(ShownHand { seat: u32::read(input)?, name: String::read(input)?, cards: Vec::read(input)? })
+    }
+}
+
+impl Wire for ClientMessage {
+    fn write(&self, out: &mut Vec<u8>) {
+        match self {
+            ClientMessage::Hello { version } => {
+                out.push(0);
+                version.write(out);
+            }
+            ClientMessage::Username(name) => {
+                out.push(1);
+                name.write(out);
+            }
+            ClientMessage::Password(password) => {
+                out.push(2);
+                password.write(out);
+            }
+            ClientMessage::ListTables => out.push(3),
+            ClientMessage::JoinTable { table, buy_in } => {
+                out.push(4);
+                table.write(out);
+                buy_in.write(out);
+            }
+            ClientMessage::LeaveTable { table } => {
+                out.push(5);
+                table.write(out);
+            }
+            ClientMessage::Action { table, action } => {
+                out.push(6);
+                table.write(out);
+                action.write(out);
+            }
+            ClientMessage::Draw { table, discards } => {
+                out.push(7);
+                table.write(out);
+                discards.write(out);
+            }
+            ClientMessage::Chat { table, text } => {
+                out.push(8);
+                table.write(out);
+                text.write(out);
+            }
+            ClientMessage::Quit => out.push(9),
+            ClientMessage::Resume { token } => {
+                out.push(10);
+                token.write(out);
+            }
+            ClientMessage::CreateTable { variant, stakes, structure, max_seats, trips_beat_straight } => {
+                out.push(11);
+                variant.write(out);
+                stakes.write(out);
+                structure.write(out);
+                max_seats.write(out);
+                trips_beat_straight.write(out);
+            }
+            ClientMessage::StandUp { table } => {
+                out.push(12);
+                table.write(out);
+            }
+            ClientMessage::Watch { table } => {
+                out.push(13);
+                table.write(out);
+            }
+            ClientMessage::SitOut { table } => {
+                out.push(14);
+                table.write(out);
+            }
+            ClientMessage::SitIn { table } => {
+                out.push(15);
+                table.write(out);
+            }
+        }
+    }
+
+    fn read(input: &mut Input) -> Result<Self, ProtocolError> {
+        Ok(match input.tag()? {
+            0 => ClientMessage::Hello { version: u16::read(input)? },
+            1 => ClientMessage::Username(String::read(input)?),
+            2 => ClientMessage::Password(String::read(input)?),
+            3 => ClientMessage::ListTables,
+            4 => ClientMessage::JoinTable { table: u32::read(input)?, buy_in: u32::read(input)? },
+            5 => ClientMessage::LeaveTable { table: u32::read(input)? },
+            6 => ClientMessage::Action { table: u32::read(input)?, action: PlayerAction::read(input)? },
+            7 => ClientMessage::Draw { table: u32::read(input)?, discards: Vec::read(input)? },
+            8 => ClientMessage::Chat { table: u32::read(input)?, text: String::read(input)? },
+            9 => ClientMessage::Quit,
+            10 => ClientMessage::Resume { token: String::read(input)? },
+            11 => ClientMessage::CreateTable {
+                variant: Variant::read(input)?,
+                stakes: Stakes::read(input)?,
+                structure: BettingStructure::read(input)?,
+                max_seats: u32::read(input)?,
+                trips_beat_straight: bool::read(input)?,
+            },
+            12 => ClientMessage::StandUp { table: u32::read(input)? },
+            13 => ClientMessage::Watch { table: u32::read(input)? },
+            14 => ClientMessage::SitOut { table: u32::read(input)? },
+            15 => ClientMessage::SitIn { table: u32::read(input)? },
+            tag => return Err(ProtocolError::UnknownTag { field: "client message", tag }),
+        })
+    }
+}
+
+impl Wire for ServerMessage {
+    fn write(&self, out: &mut Vec<u8>) {
+        match self {
+            ServerMessage::Welcome { version } => {
+                out.push(0);
+                version.write(out);
+            }
+            ServerMessage::NewUser => out.push(1),
+            ServerMessage::ReturningUser => out.push(2),
+            ServerMessage::LoggedIn { chips, token } => {
+                out.push(3);
+                chips.write(out);
+                token.write(out);
+            }
+            ServerMessage::LoginFailed(reason) => {
+                out.push(4);
+                reason.write(out);
+            }
+            ServerMessage::Tables(tables) => {
+                out.push(5);
+                tables.write(out);
+            }
+            ServerMessage::Seated { table, seat } => {
+                out.push(6);
+                table.write(out);
+                seat.write(out);
+            }
+            ServerMessage::Left { table } => {
+                out.push(7);
+                table.write(out);
+            }
+            ServerMessage::TableState { table, view } => {
+                out.push(8);
+                table.write(out);
+                view.write(out);
+            }
+            ServerMessage::Chat { table, from, text } => {
+                out.push(9);
+                table.write(out);
+                from.write(out);
+                text.write(out);
+            }
+            ServerMessage::Error(message) => {
+                out.push(10);
+                message.write(out);
+            }
+            ServerMessage::Goodbye => out.push(11),
+            ServerMessage::TableCreated { table } => {
+                out.push(12);
+                table.write(out);
+            }
+            ServerMessage::TableUpdated(summary) => {
+                out.push(13);
+                summary.write(out);
+            }
+            ServerMessage::StoodUp { table, chips } => {
+                out.push(14);
+                table.write(out);
+                chips.write(out);
+            }
+            ServerMessage::Watching { table } => {
+                out.push(15);
+                table.write(out);
+            }
+            ServerMessage::ClockWarning { table, seconds_left } => {
+                out.push(16);
+                table.write(out);
+                seconds_left.write(out);
+            }
+            ServerMessage::HandResult { table, awards, shown } => {
+                out.push(17);
+                table.write(out);
+                awards.write(out);
+                shown.write(out);
+            }
+            ServerMessage::TableClosed { table } => {
+                out.push(18);
+                table.write(out);
+            }
+        }
+    }
+
+    fn read(input: &mut Input) -> Result<Self, ProtocolError> {
+        Ok(match input.tag()? {
+            0 => ServerMessage::Welcome { version: u16::read(input)? },
+            1 => ServerMessage::NewUser,
+            2 => ServerMessage::ReturningUser,
+            3 => ServerMessage::LoggedIn { chips: u32::read(input)?, token: String::read(input)? },
+            4 => ServerMessage::LoginFailed(String::read(input)?),
+            5 => ServerMessage::Tables(Vec::read(input)?),
+            6 => ServerMessage::Seated { table: u32::read(input)?, seat: u32::read(input)? },
+            7 => ServerMessage::Left { table: u32::read(input)? },
+            8 => ServerMessage::TableState { table: u32::read(input)?, view: TableView::read(input)? },
+            9 => ServerMessage::Chat { table: u32::read(input)?, from: String::read(input)?, text: String::read(input)? },
+            10 => ServerMessage::Error(String::read(input)?),
+            11 => ServerMessage::Goodbye,
+            12 => ServerMessage::TableCreated { table: u32::read(input)? },
+            13 => ServerMessage::TableUpdated(TableSummary::read(input)?),
+            14 => ServerMessage::StoodUp { table: u32::read(input)?, chips: u32::read(input)? },
+            15 => ServerMessage::Watching { table: u32::read(input)? },
+            16 => ServerMessage::ClockWarning { table: u32::read(input)?, seconds_left: u32::read(input)? },
+            17 => ServerMessage::HandResult { table: u32::read(input)?, awards: Vec::read(input)?, shown: Vec::read(input)? },
+            18 => ServerMessage::TableClosed { table: u32::read(input)? },
+            tag => return Err(ProtocolError::UnknownTag { field: "server message", tag }),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{self, Read};
+
+    use crate::action::PlayerAction;
+    use crate::card::cards;
+    use crate::protocol::{read_frame, receive, send, Award, ClientMessage, Message, ProtocolError, ServerMessage, ShownHand, TableSummary, PROTOCOL_VERSION};
+    use crate::stakes::{BettingStructure, Stakes};
+    use crate::variant::Variant;
+    use crate::view::{LegalAction, Round, SeatView, TableView, Viewer};
+
+    // Hands out at most one byte per read, like a slow TCP stream.
+    struct Trickle<'a>(&'a [u8]);
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.0.is_empty() || buf.is_empty() {
+                return Ok(0);
+            }
+            buf[0] = self.0[0];
+            self.0 = &self.0[1..];
+            Ok(1)
+        }
+    }
+
+    fn view() -> TableView {
+        TableView {
+            variant: Variant::SevenCardStud,
+            round: Round::FourthStreet,
+            button: 2,
+            to_act: Some(1),
+            pot: 45,
+            current_bet: 10,
+            board: Vec::new(),
+            seats: vec![SeatView {
+                name: "Jane".to_owned(),
+                chips: 955,
+                current_bet: 10,
+                in_hand: true,
+                sitting_out: false,
+                cards: 4,
+                up_cards: cards("Kd 7c"),
+                hand: cards("Ah 2s Kd 7c"),
+            }],
+            viewer: Viewer::Seat(0),
+            legal_actions: vec![LegalAction::Fold, LegalAction::Call(10), LegalAction::Raise { min: 20, max: 20 }],
+        }
+    }
+
+    fn summary() -> TableSummary {
+        TableSummary { id: 1, variant: Variant::OmahaHiLo, stakes: Stakes::default(), players: 3, max_seats: 10, average_pot: 84 }
+    }
+
+    fn client_messages() -> Vec<ClientMessage> {
+        vec![
+            ClientMessage::Hello { version: PROTOCOL_VERSION },
+            ClientMessage::Username("jane".to_owned()),
+            ClientMessage::Password("pässwörd".to_owned()),
+            ClientMessage::Resume { token: "0123abcd".to_owned() },
+            ClientMessage::ListTables,
+            ClientMessage::CreateTable {
+                variant: Variant::Razz,
+                stakes: Stakes { ante: 1, small_blind: 0, big_blind: 0, bring_in: 3 },
+                structure: BettingStructure::FixedLimit { small_bet: 10, big_bet: 20, max_raises: 3 },
+                max_seats: 7,
+                trips_beat_straight: true,
+            },
+            ClientMessage::CreateTable {
+                variant: Variant::TexasHoldem,
+                stakes: Stakes::default(),
+                structure: BettingStructure::SpreadLimit { min: 10, max: 50 },
+                max_seats: 10,
+                trips_beat_straight: false,
+            },
+            ClientMessage::JoinTable { table: 3, buy_in: 500 },
+            ClientMessage::StandUp { table: 3 },
+            ClientMessage::Watch { table: 3 },
+            ClientMessage::LeaveTable { table: 3 },
+            ClientMessage::SitOut { table: 3 },
+            ClientMessage::SitIn { table: 3 },
+            ClientMessage::Action { table: 3, action: PlayerAction::Raise(60) },
+            ClientMessage::Draw { table: 3, discards: cards("2c Jk") },
+            ClientMessage::Chat { table: 3, text: "nice hand".to_owned() },
+            ClientMessage::Quit,
+        ]
+    }
+
+    fn server_messages() -> Vec<ServerMessage> {
+        vec![
+            ServerMessage::Welcome { version: PROTOCOL_VERSION },
+            ServerMessage::NewUser,
+            ServerMessage::ReturningUser,
+            ServerMessage::LoggedIn { chips: 1000, token: "0123abcd".to_owned() },
+            ServerMessage::LoginFailed("wrong password".to_owned()),
+            ServerMessage::Tables(vec![summary()]),
+            ServerMessage::TableCreated { table: 1 },
+            ServerMessage::TableUpdated(summary()),
+            ServerMessage::TableClosed { table: 1 },
+            ServerMessage::Seated { table: 1, seat: 4 },
+            ServerMessage::StoodUp { table: 1, chips: 650 },
+            ServerMessage::Watching { table: 1 },
+            ServerMessage::Left { table: 1 },
+            ServerMessage::TableState { table: 1, view: view() },
+            ServerMessage::TableState { table: 2, view: TableView { viewer: Viewer::Admin, legal_actions: Vec::new(), ..view() } },
+            ServerMessage::Chat { table: 1, from: "jane".to_owned(), text: "gl".to_owned() },
+            ServerMessage::ClockWarning { table: 1, seconds_left: 5 },
+            ServerMessage::HandResult {
+                table: 1,
+                awards: vec![Award { seat: 2, name: "jane".to_owned(), chips: 45 }],
+                shown: vec![
+                    ShownHand { seat: 0, name: "john".to_owned(), cards: cards("Ah Kd") },
+                    ShownHand { seat: 2, name: "jane".to_owned(), cards: cards("7c 7d") },
+                ],
+            },
+            ServerMessage::HandResult { table: 1, awards: vec![Award { seat: 0, name: "john".to_owned(), chips: 15 }], shown: Vec::new() },
+            ServerMessage::Error("no such table".to_owned()),
+            ServerMessage::Goodbye,
+        ]
+    }
+
+    #[test]
+    fn test_every_message_round_trips() {
+        for message in client_messages() {
+            assert_eq!(ClientMessage::decode(&message.encode()).unwrap(), message);
+        }
+        for message in server_messages() {
+            assert_eq!(ServerMessage::decode(&message.encode()).unwrap(), message);
+        }
+    }
+
+    #[test]
+    fn test_frames_split_across_reads() {
+        let mut stream = Vec::new();
+        for message in client_messages() {
+            send(&mut stream, &message).unwrap();
+        }
+        let mut reader = Trickle(&stream);
+        for message in client_messages() {
+            assert_eq!(receive::<_, ClientMessage>(&mut reader).unwrap(), Some(message));
+        }
+        assert!(receive::<_, ClientMessage>(&mut reader).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_frames_merged_in_one_read() {
+        let mut stream = Vec::new();
+        for message in server_messages() {
+            send(&mut stream, &message).unwrap();
+        }
+        // every frame arrives in a single buffer
+        let mut reader = stream.as_slice();
+        for message in server_messages() {
+            assert_eq!(receive::<_, ServerMessage>(&mut reader).unwrap(), Some(message));
+        }
+        assert!(receive::<_, ServerMessage>(&mut reader).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_bad_frames_are_rejected() {
+        // the connection drops halfway through a frame
+        let mut stream = Vec::new();
+        send(&mut stream, &ClientMessage::Username("jane".to_owned())).unwrap();
+        let mut reader = &stream[..stream.len() - 1];
+        assert!(matches!(receive::<_, ClientMessage>(&mut reader), Err(ProtocolError::Io(_))));
+
+        let mut reader: &[u8] = &[0xff, 0xff, 0xff, 0xff];
+        assert!(matches!(read_frame(&mut reader), Err(ProtocolError::FrameTooLarge(_))));
+
+        assert!(matches!(ClientMessage::decode(&[42]), Err(ProtocolError::UnknownTag { tag: 42, .. })));
+        assert!(matches!(ClientMessage::decode(&[1, 0, 0, 0, 9, b'a']), Err(ProtocolError::Truncated)));
+        assert!(matches!(ClientMessage::decode(&[9, 0]), Err(ProtocolError::TrailingBytes(1))));
+        assert!(matches!(ClientMessage::decode(&[1, 0, 0, 0, 1, 0xff]), Err(ProtocolError::InvalidUtf8)));
+        // a list can't claim more items than the frame has bytes
+        assert!(matches!(ClientMessage::decode(&[7, 0, 0, 0, 1, 0xff, 0xff, 0xff, 0xff]), Err(ProtocolError::Truncated)));
+    }
+}
@@ -0,0 +1,30 @@
+/// Forced bets posted at the start of every hand.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Stakes {
+    pub ante: u32,
+    pub small_blind: u32,
+    pub big_blind: u32,
+    /// What the lowest (or in Razz highest) door card must open for in stud games.
+    pub bring_in: u32,
+}
+
+impl Default for Stakes {
+    fn default() -> Self {
+        Stakes { ante: 0, small_blind: 5, big_blind: 10, bring_in: 2 }
+    }
+}
+
+/// How much a player may bet or raise.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BettingStructure {
+    /// Any amount from a minimum raise up to the whole stack.
+    NoLimit,
+    /// Up to the size of the pot after calling.
+    PotLimit,
+    /// Bets and raises are exactly `small_bet` on the early streets and
+    /// `big_bet` on the late ones, with at most `max_raises` raises after the
+    /// opening bet in each round.
+    FixedLimit { small_bet: u32, big_bet: u32, max_raises: u32 },
+    /// Any amount between `min` and `max`, but at least the previous raise.
+    SpreadLimit { min: u32, max: u32 },
+}
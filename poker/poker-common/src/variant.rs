@@ -0,0 +1,73 @@
+use std::fmt;
+
+/// Every poker variant the server can deal, shared with clients so both
+/// sides can name the game being played.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Variant {
+    FiveCardDraw,
+    DeuceToSevenTripleDraw,
+    AceToFiveTripleDraw,
+    Badugi,
+    TexasHoldem,
+    PotLimitOmaha,
+    OmahaHiLo,
+    ShortDeck,
+    SevenCardStud,
+    StudHiLo,
+    Razz,
+}
+
+impl Variant {
+    pub const ALL: [Variant; 11] = [
+        Variant::FiveCardDraw,
+        Variant::DeuceToSevenTripleDraw,
+        Variant::AceToFiveTripleDraw,
+        Variant::Badugi,
+        Variant::TexasHoldem,
+        Variant::PotLimitOmaha,
+        Variant::OmahaHiLo,
+        Variant::ShortDeck,
+        Variant::SevenCardStud,
+        Variant::StudHiLo,
+        Variant::Razz,
+    ];
+
+    /// Whether the betting is opened by a bring-in rather than blinds.
+    pub fn has_bring_in(&self) -> bool {
+        matches!(self, Variant::SevenCardStud | Variant::StudHiLo | Variant::Razz)
+    }
+
+    /// The most players a table can seat without running out of cards.
+    /// Stud stops at seven, since eight players to seventh street would need
+    /// 56 cards.
+    pub fn max_players(&self) -> usize {
+        match self {
+            Variant::TexasHoldem | Variant::PotLimitOmaha | Variant::OmahaHiLo => 10,
+            Variant::SevenCardStud | Variant::StudHiLo | Variant::Razz => 7,
+            Variant::FiveCardDraw
+            | Variant::DeuceToSevenTripleDraw
+            | Variant::AceToFiveTripleDraw
+            | Variant::Badugi
+            | Variant::ShortDeck => 6,
+        }
+    }
+}
+
+impl fmt::Display for Variant {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Variant::FiveCardDraw => "Five Card Draw",
+            Variant::DeuceToSevenTripleDraw => "2-7 Triple Draw",
+            Variant::AceToFiveTripleDraw => "A-5 Triple Draw",
+            Variant::Badugi => "Badugi",
+            Variant::TexasHoldem => "Texas Hold'em",
+            Variant::PotLimitOmaha => "Pot-Limit Omaha",
+            Variant::OmahaHiLo => "Omaha Hi-Lo",
+            Variant::ShortDeck => "Short Deck Hold'em",
+            Variant::SevenCardStud => "Seven Card Stud",
+            Variant::StudHiLo => "Stud Hi-Lo",
+            Variant::Razz => "Razz",
+        };
+        write!(f, "{}", name)
+    }
+}
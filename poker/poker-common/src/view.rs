@@ -0,0 +1,240 @@
+use crate::card::Card;
+use crate::player::Player;
+use crate::variant::Variant;
+
+/// The phases of a hand, in the order they are played. Each variant uses
+/// only the phases it needs: draw games alternate betting and drawing, Hold'em
+/// bets preflop and on the flop, turn and river, and stud bets on each
+/// street from third to seventh.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Round {
+    /// No hand in progress; players can join and a new hand can be started.
+    Waiting,
+    FirstBetting,
+    Draw,
+    SecondBetting,
+    ThirdBetting,
+    FourthBetting,
+    Preflop,
+    Flop,
+    Turn,
+    River,
+    ThirdStreet,
+    FourthStreet,
+    FifthStreet,
+    SixthStreet,
+    SeventhStreet,
+    Showdown,
+    /// The pot has been awarded and the table is waiting to be reset.
+    Complete,
+}
+
+impl Round {
+    pub const ALL: [Round; 17] = [
+        Round::Waiting,
+        Round::FirstBetting,
+        Round::Draw,
+        Round::SecondBetting,
+        Round::ThirdBetting,
+        Round::FourthBetting,
+        Round::Preflop,
+        Round::Flop,
+        Round::Turn,
+        Round::River,
+        Round::ThirdStreet,
+        Round::FourthStreet,
+        Round::FifthStreet,
+        Round::SixthStreet,
+        Round::SeventhStreet,
+        Round::Showdown,
+        Round::Complete,
+    ];
+}
+
+/// A betting action the player to act may take, with the amounts allowed.
+/// Going all-in is a bet or raise to the maximum, or a call for less.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LegalAction {
+    Fold,
+    Check,
+    Call(u32),
+    Bet { min: u32, max: u32 },
+    Raise { min: u32, max: u32 },
+}
+
+/// Who a view of the table is for, which decides whose hidden cards it shows.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Viewer {
+    /// The player in this seat, who sees their own cards.
+    Seat(usize),
+    /// Someone watching, who sees only what is face up.
+    Spectator,
+    /// Sees every hand, for looking into a table from the server side.
+    Admin,
+}
+
+impl Viewer {
+    /// Whether the cards dealt face down to `seat` are shown to this viewer.
+    pub fn can_see(&self, seat: usize) -> bool {
+        match self {
+            Viewer::Seat(own) => *own == seat,
+            Viewer::Spectator => false,
+            Viewer::Admin => true,
+        }
+    }
+}
+
+/// What one viewer can see about one seat.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SeatView {
+    pub name: String,
+    pub chips: u32,
+    pub current_bet: u32,
+    pub in_hand: bool,
+    pub sitting_out: bool,
+    pub cards: usize,
+    /// The cards dealt face up in stud games.
+    pub up_cards: Vec<Card>,
+    /// Every card in the hand, face down ones included. Empty unless the
+    /// viewer may see them.
+    pub hand: Vec<Card>,
+}
+
+impl SeatView {
+    /// The player in `seat` as `viewer` sees them. This is the one place a
+    /// hand is copied into a view, so hidden cards can't leak from anywhere
+    /// else.
+    pub fn new(player: &Player, seat: usize, viewer: Viewer) -> SeatView {
+        SeatView {
+            name: player.get_name().clone(),
+            chips: player.get_total_chips(),
+            current_bet: player.get_current_bet(),
+            in_hand: player.is_active(),
+            sitting_out: player.is_sitting_out(),
+            cards: player.get_hand().len(),
+            up_cards: player.get_up_cards(),
+            hand: if viewer.can_see(seat) { player.get_hand().clone() } else { Vec::new() },
+        }
+    }
+}
+
+/// The table as one viewer sees it: the public state, plus only the cards
+/// that viewer is allowed to see.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TableView {
+    pub variant: Variant,
+    pub round: Round,
+    pub button: usize,
+    pub to_act: Option<usize>,
+    pub pot: u32,
+    pub current_bet: u32,
+    pub board: Vec<Card>,
+    pub seats: Vec<SeatView>,
+    pub viewer: Viewer,
+    /// What the viewer may do now; always empty for anyone but a seat.
+    pub legal_actions: Vec<LegalAction>,
+}
+
+impl TableView {
+    /// The seats of `players` as `viewer` sees them.
+    pub fn seats(players: &[Player], viewer: Viewer) -> Vec<SeatView> {
+        players.iter().enumerate().map(|(seat, player)| SeatView::new(player, seat, viewer)).collect()
+    }
+
+    /// The viewer's own cards, or nothing for anyone not seated.
+    pub fn hand(&self) -> &[Card] {
+        match self.viewer {
+            Viewer::Seat(seat) => self.seats.get(seat).map_or(&[], |view| &view.hand),
+            _ => &[],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::card::{cards, Card};
+    use crate::player::Player;
+    use crate::protocol::{Message, ServerMessage};
+    use crate::variant::Variant;
+    use crate::view::{Round, TableView, Viewer};
+
+    // A stud table on fourth street: two down cards and two up for each.
+    fn players() -> Vec<Player> {
+        let hands = [("jane", "Ah 2s", "Kd 7c"), ("john", "Qs Qh", "3d 9c"), ("jack", "5h 6h", "Jc Tc")];
+        hands.iter().enumerate().map(|(id, (name, down, up))| {
+            let mut player = Player::new(name.to_string(), id as u32, 1000);
+            cards(down).into_iter().for_each(|card| player.add_card(card));
+            cards(up).into_iter().for_each(|card| player.add_face_up_card(card));
+            player
+        }).collect()
+    }
+
+    // Sends the view over the wire and back, and returns every card it holds.
+    fn cards_sent(players: &[Player], viewer: Viewer) -> Vec<Card> {
+        let view = TableView {
+            variant: Variant::SevenCardStud,
+            round: Round::FourthStreet,
+            button: 0,
+            to_act: Some(1),
+            pot: 30,
+            current_bet: 0,
+            board: Vec::new(),
+            seats: TableView::seats(players, viewer),
+            viewer,
+            legal_actions: Vec::new(),
+        };
+        let message = ServerMessage::TableState { table: 1, view };
+        match ServerMessage::decode(&message.encode()).unwrap() {
+            ServerMessage::TableState { view, .. } => {
+                view.seats.iter().flat_map(|seat| seat.up_cards.iter().chain(&seat.hand)).cloned().collect()
+            }
+            message => panic!("unexpected message: {:?}", message),
+        }
+    }
+
+    #[test]
+    fn test_a_seat_sees_only_its_own_down_cards() {
+        let players = players();
+        for seat in 0..players.len() {
+            let sent = cards_sent(&players, Viewer::Seat(seat));
+            for (other, player) in players.iter().enumerate() {
+                for (i, card) in player.get_hand().iter().enumerate() {
+                    let visible = other == seat || player.is_face_up(i);
+                    assert_eq!(sent.contains(card), visible, "seat {} and {} of seat {}", seat, card, other);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_spectators_and_admins() {
+        let players = players();
+        let sent = cards_sent(&players, Viewer::Spectator);
+        assert_eq!(sent.len(), 6);
+        assert!(players.iter().all(|player| player.get_up_cards().iter().all(|card| sent.contains(card))));
+
+        let sent = cards_sent(&players, Viewer::Admin);
+        assert!(players.iter().flat_map(|player| player.get_hand()).all(|card| sent.contains(card)));
+    }
+
+    #[test]
+    fn test_own_hand() {
+        let players = players();
+        let view = |viewer| TableView {
+            variant: Variant::SevenCardStud,
+            round: Round::FourthStreet,
+            button: 0,
+            to_act: None,
+            pot: 0,
+            current_bet: 0,
+            board: Vec::new(),
+            seats: TableView::seats(&players, viewer),
+            viewer,
+            legal_actions: Vec::new(),
+        };
+        assert_eq!(view(Viewer::Seat(1)).hand(), cards("Qs Qh 3d 9c"));
+        assert!(view(Viewer::Seat(5)).hand().is_empty());
+        assert!(view(Viewer::Spectator).hand().is_empty());
+        assert!(view(Viewer::Admin).hand().is_empty());
+    }
+}
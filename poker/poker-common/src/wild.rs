@@ -0,0 +1,263 @@
+use crate::card::{Card, Suit, Value};
+use crate::evaluator::{self, HandStrength};
+use crate::hand::HandRank;
+
+/// How the joker plays when the deck has one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Joker {
+    /// The joker can be any card.
+    Wild,
+    /// The bug: the joker is an ace, or any card that completes a straight
+    /// or a flush.
+    Bug,
+}
+
+/// Which cards are wild at a table: any number of natural ranks, such as
+/// deuces, and optionally a joker in the deck.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WildCards {
+    // one bit per rank, deuce in bit 2 and ace in bit 14
+    ranks: u16,
+    joker: Option<Joker>,
+}
+
+impl WildCards {
+    /// No wild cards and no joker, the usual game.
+    pub fn none() -> WildCards {
+        WildCards::default()
+    }
+
+    /// Makes every card of `value` wild, as in deuces wild.
+    pub fn with_rank(mut self, value: Value) -> WildCards {
+        assert!(value != Value::Joker, "the joker is added with `with_joker`");
+        self.ranks |= 1 << value.rank();
+        self
+    }
+
+    /// Adds a joker to the deck that plays as `joker`.
+    pub fn with_joker(mut self, joker: Joker) -> WildCards {
+        self.joker = Some(joker);
+        self
+    }
+
+    pub fn joker(&self) -> Option<Joker> {
+        self.joker
+    }
+
+    pub fn is_none(&self) -> bool {
+        self.ranks == 0 && self.joker.is_none()
+    }
+
+    /// Whether `card` can stand for any card. A joker is fully wild unless
+    /// it is the bug.
+    pub fn is_wild(&self, card: &Card) -> bool {
+        if card.is_joker() {
+            return self.joker != Some(Joker::Bug);
+        }
+        self.ranks & (1 << card.value.rank()) != 0
+    }
+
+    fn is_bug(&self, card: &Card) -> bool {
+        card.is_joker() && self.joker == Some(Joker::Bug)
+    }
+}
+
+/// Ranks exactly five cards, giving each wild card whatever value makes the
+/// best hand. Returns the strength with the five cards as they play, wild
+/// cards replaced. Without wild cards this is [`evaluator::evaluate`].
+pub fn evaluate(cards: &[Card], wild: &WildCards) -> (HandStrength, Vec<Card>) {
+    assert_eq!(cards.len(), 5, "a poker hand has five cards");
+    let mut naturals: Vec<Card> = Vec::new();
+    let mut wilds = 0;
+    let mut bug = false;
+    for card in cards {
+        if wild.is_bug(card) {
+            bug = true;
+        } else if wild.is_wild(card) {
+            wilds += 1;
+        } else {
+            naturals.push(card.clone());
+        }
+    }
+    if wilds == 0 && !bug {
+        return (evaluator::evaluate(cards), cards.to_vec());
+    }
+
+    // five of a kind beats everything, so take it whenever it is there
+    let value = naturals.first().map_or(Value::Ace, |card| card.value.clone());
+    if naturals.iter().all(|card| card.value == value) && (!bug || value == Value::Ace) {
+        let mut played = naturals.clone();
+        played.extend(Suit::ALL.iter().cycle().take(5 - naturals.len()).map(|suit| Card::new(suit.clone(), value.clone())));
+        return (HandStrength::five_of_a_kind(&value), played);
+    }
+
+    // otherwise every wild card is tried as each card not already held
+    let candidates: Vec<Card> = Suit::ALL.iter()
+        .flat_map(|suit| Value::ALL.iter().map(move |value| Card::new(suit.clone(), value.clone())))
+        .filter(|card| !naturals.contains(card))
+        .collect();
+    let mut best: Option<(HandStrength, Vec<Card>)> = None;
+    let mut played = naturals;
+    assign(&candidates, 0, wilds, bug, &mut played, &mut best);
+    best.expect("a wild card can always be assigned")
+}
+
+// Gives the next wild card each remaining candidate in turn, from `start` on
+// so the same set is never tried twice, and the bug last.
+fn assign(
+    candidates: &[Card],
+    start: usize,
+    wilds: usize,
+    bug: bool,
+    played: &mut Vec<Card>,
+    best: &mut Option<(HandStrength, Vec<Card>)>,
+) {
+    if wilds > 0 {
+        for i in start..candidates.len() {
+            played.push(candidates[i].clone());
+            assign(candidates, i + 1, wilds - 1, bug, played, best);
+            played.pop();
+        }
+        return;
+    }
+    if bug {
+        for card in candidates {
+            if played.contains(card) {
+                continue;
+            }
+            played.push(card.clone());
+            let strength = evaluator::evaluate(played);
+            // the bug is only an ace, unless it finishes a straight or flush
+            let rank = HandRank::of(strength);
+            let allowed = card.value == Value::Ace
+                || matches!(rank, HandRank::Straight | HandRank::Flush | HandRank::StraightFlush | HandRank::RoyalFlush);
+            if allowed && best.as_ref().is_none_or(|(current, _)| strength > *current) {
+                *best = Some((strength, played.clone()));
+            }
+            played.pop();
+        }
+        return;
+    }
+    let strength = evaluator::evaluate(played);
+    if best.as_ref().is_none_or(|(current, _)| strength > *current) {
+        *best = Some((strength, played.clone()));
+    }
+}
+
+/// Finds the strongest five card hand that can be made from `cards` with
+/// wild cards. Returns the strength with the five cards as they play, or
+/// `None` with fewer than five cards.
+pub fn best_hand(cards: &[Card], wild: &WildCards) -> Option<(HandStrength, Vec<Card>)> {
+    let n = cards.len();
+    let mut best: Option<(HandStrength, Vec<Card>)> = None;
+    for a in 0..n {
+        for b in a + 1..n {
+            for c in b + 1..n {
+                for d in c + 1..n {
+                    for e in d + 1..n {
+                        let five = [&cards[a], &cards[b], &cards[c], &cards[d], &cards[e]].map(|card| card.clone());
+                        let (strength, played) = evaluate(&five, wild);
+                        if best.as_ref().is_none_or(|(current, _)| strength > *current) {
+                            best = Some((strength, played));
+                        }
+                    }
+                }
+            }
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::card::{cards, Value};
+    use crate::evaluator::{self, HandStrength, DISTINCT_HANDS};
+    use crate::hand::HandRank;
+    use crate::wild::{best_hand, evaluate, Joker, WildCards};
+
+    fn deuces() -> WildCards {
+        WildCards::none().with_rank(Value::Two)
+    }
+
+    #[test]
+    fn test_no_wild_cards_is_the_plain_evaluator() {
+        let hand = cards("Kh 9d 7c 4s 2h");
+        assert_eq!(evaluate(&hand, &WildCards::none()).0, evaluator::evaluate(&hand));
+        // deuces are natural unless the table makes them wild
+        assert!(WildCards::none().is_none());
+        assert!(!deuces().is_none());
+        assert!(deuces().is_wild(&cards("2c")[0]));
+        assert!(!deuces().is_wild(&cards("3c")[0]));
+    }
+
+    #[test]
+    fn test_five_of_a_kind_beats_a_royal_flush() {
+        let (strength, played) = evaluate(&cards("Kh Kd Kc Ks 2h"), &deuces());
+        assert_eq!(strength, HandStrength::five_of_a_kind(&Value::King));
+        assert!(played.iter().all(|card| card.value == Value::King));
+        assert!(strength.value() > DISTINCT_HANDS);
+        assert_eq!(HandRank::of(strength), HandRank::FiveOfAKind);
+
+        let (aces, _) = evaluate(&cards("Ah Ad 2c 2s Jk"), &deuces().with_joker(Joker::Wild));
+        assert!(aces > strength);
+        // all wild plays as five aces
+        assert_eq!(evaluate(&cards("2h 2d 2c 2s Jk"), &deuces().with_joker(Joker::Wild)).0, aces);
+    }
+
+    #[test]
+    fn test_wild_cards_make_the_best_hand() {
+        // a deuce completes the royal flush rather than making trips
+        let (strength, played) = evaluate(&cards("Ah Kh Qh Th 2s"), &deuces());
+        assert_eq!(strength.value(), DISTINCT_HANDS);
+        assert!(played.contains(&cards("Jh")[0]));
+
+        // two deuces turn a pair into quads, not a full house
+        let (strength, _) = evaluate(&cards("9h 9d 2c 2s 4h"), &deuces());
+        assert_eq!(strength, evaluator::evaluate(&cards("9h 9d 9c 9s 4h")));
+
+        // the deuce counts as the card it stands for, so a gutshot fills
+        let (strength, _) = evaluate(&cards("8h 7d 5c 4s 2d"), &deuces());
+        assert_eq!(strength, evaluator::evaluate(&cards("8h 7d 6c 5c 4s")));
+    }
+
+    #[test]
+    fn test_bug_plays_as_an_ace() {
+        let bug = WildCards::none().with_joker(Joker::Bug);
+        // with a pair of kings the bug is an ace kicker, not a third king
+        let (strength, played) = evaluate(&cards("Kh Kd 9c 5s Jk"), &bug);
+        assert_eq!(strength, evaluator::evaluate(&cards("Kh Kd Ac 9c 5s")));
+        assert!(played.iter().any(|card| card.value == Value::Ace));
+
+        // a pair of aces becomes trips
+        let (strength, _) = evaluate(&cards("Ah Ad 9c 5s Jk"), &bug);
+        assert_eq!(HandRank::of(strength), HandRank::ThreeOfAKind);
+
+        // four aces and the bug are five aces
+        let (strength, _) = evaluate(&cards("Ah Ad Ac As Jk"), &bug);
+        assert_eq!(strength, HandStrength::five_of_a_kind(&Value::Ace));
+        // but four kings and the bug are only quads with an ace
+        let (strength, _) = evaluate(&cards("Kh Kd Kc Ks Jk"), &bug);
+        assert_eq!(strength, evaluator::evaluate(&cards("Kh Kd Kc Ks Ah")));
+    }
+
+    #[test]
+    fn test_bug_completes_straights_and_flushes() {
+        let bug = WildCards::none().with_joker(Joker::Bug);
+        let (strength, _) = evaluate(&cards("9h 8d 7c 6s Jk"), &bug);
+        assert_eq!(strength, evaluator::evaluate(&cards("Th 9h 8d 7c 6s")));
+
+        let (strength, _) = evaluate(&cards("Kh 9h 7h 4h Jk"), &bug);
+        assert_eq!(strength, evaluator::evaluate(&cards("Ah Kh 9h 7h 4h")));
+
+        let (strength, _) = evaluate(&cards("9h 8h 7h 6h Jk"), &bug);
+        assert_eq!(strength, evaluator::evaluate(&cards("Th 9h 8h 7h 6h")));
+    }
+
+    #[test]
+    fn test_best_wild_hand_of_seven() {
+        let (strength, played) = best_hand(&cards("2c 9h Ah Kh 7d Qh 4s"), &deuces()).unwrap();
+        assert_eq!(played.len(), 5);
+        assert_eq!(HandRank::of(strength), HandRank::Flush);
+        assert!(best_hand(&cards("2c 9h Ah Kh"), &deuces()).is_none());
+    }
+}
@@ -0,0 +1,450 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use sha2::Sha256;
+
+/// Chips credited to a new account.
+pub const STARTING_CHIPS: u32 = 1000;
+pub const MIN_PASSWORD_LEN: usize = 8;
+pub const MAX_USERNAME_LEN: usize = 32;
+/// Failed logins in a row before the account is locked.
+pub const MAX_FAILED_LOGINS: u32 = 5;
+/// How long a locked account refuses logins, in seconds.
+pub const LOCKOUT_SECS: u64 = 15 * 60;
+/// PBKDF2-HMAC-SHA256 iterations for new passwords.
+pub const DEFAULT_ROUNDS: u32 = 600_000;
+
+const SALT_LEN: usize = 16;
+const HASH_LEN: usize = 32;
+
+#[derive(Debug)]
+pub enum AccountError {
+    /// Usernames are 1 to 32 letters, digits, `_` or `-`.
+    InvalidUsername,
+    PasswordTooShort,
+    UsernameTaken,
+    NoSuchUser,
+    WrongPassword { attempts_left: u32 },
+    /// Too many failed logins; try again at this Unix time.
+    LockedOut { until: u64 },
+    NotEnoughChips { chips: u32, needed: u32 },
+    /// The accounts file has a line that can't be read.
+    Corrupt { line: usize },
+    Io(io::Error),
+}
+
+impl fmt::Display for AccountError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AccountError::InvalidUsername => {
+                write!(f, "usernames are 1 to {} letters, digits, '_' or '-'", MAX_USERNAME_LEN)
+            }
+            AccountError::PasswordTooShort => write!(f, "passwords need at least {} characters", MIN_PASSWORD_LEN),
+            AccountError::UsernameTaken => write!(f, "that username is taken"),
+            AccountError::NoSuchUser => write!(f, "no account with that username"),
+            AccountError::WrongPassword { attempts_left } => {
+                write!(f, "wrong password, {} attempts left before the account is locked", attempts_left)
+            }
+            AccountError::LockedOut { until } => write!(f, "account locked after too many failed logins until {}", until),
+            AccountError::NotEnoughChips { chips, needed } => write!(f, "needs {} chips but the account has {}", needed, chips),
+            AccountError::Corrupt { line } => write!(f, "accounts file is corrupt at line {}", line),
+            AccountError::Io(err) => write!(f, "accounts file error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for AccountError {}
+
+impl From<io::Error> for AccountError {
+    fn from(err: io::Error) -> Self {
+        AccountError::Io(err)
+    }
+}
+
+// One registered player. Only the salted hash of the password is kept.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Account {
+    rounds: u32,
+    salt: [u8; SALT_LEN],
+    hash: [u8; HASH_LEN],
+    chips: u32,
+    failed_logins: u32,
+    locked_until: u64,
+}
+
+/// How to hash a password for one account. Hashing is slow on purpose, so
+/// it is done from this, without the store borrowed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Salt {
+    salt: [u8; SALT_LEN],
+    rounds: u32,
+}
+
+impl Salt {
+    pub fn hash(&self, password: &str) -> HashedPassword {
+        HashedPassword { salt: *self, hash: hash_password(password, &self.salt, self.rounds) }
+    }
+}
+
+/// A password hashed with a [`Salt`], ready to register or log in with.
+#[derive(Clone, Debug)]
+pub struct HashedPassword {
+    salt: Salt,
+    hash: [u8; HASH_LEN],
+}
+
+/// Registered players and their chip balances, kept in a plain text file
+/// with one account per line. Every change is written through to the file
+/// before the call returns, so a restart loses nothing.
+pub struct AccountStore {
+    path: PathBuf,
+    accounts: HashMap<String, Account>,
+    rounds: u32,
+}
+
+impl AccountStore {
+    /// Loads the accounts at `path`, starting empty if the file doesn't exist yet.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<AccountStore, AccountError> {
+        let path = path.as_ref().to_path_buf();
+        let accounts = match fs::read_to_string(&path) {
+            Ok(contents) => parse_accounts(&contents)?,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => HashMap::new(),
+            Err(err) => return Err(err.into()),
+        };
+        Ok(AccountStore { path, accounts, rounds: DEFAULT_ROUNDS })
+    }
+
+    /// Hashes new passwords with this many rounds instead of the default.
+    /// Existing accounts keep the rounds they were hashed with.
+    pub fn with_rounds(mut self, rounds: u32) -> AccountStore {
+        self.rounds = rounds;
+        self
+    }
+
+    pub fn exists(&self, username: &str) -> bool {
+        self.accounts.contains_key(username)
+    }
+
+    pub fn get_chips(&self, username: &str) -> Option<u32> {
+        self.accounts.get(username).map(|account| account.chips)
+    }
+
+    /// Creates an account with `STARTING_CHIPS` and returns its balance.
+    /// This hashes the password with the store borrowed; a shared store should
+    /// use [`check_new`](AccountStore::check_new) and
+    /// [`register_hashed`](AccountStore::register_hashed) instead.
+    pub fn register(&mut self, username: &str, password: &str) -> Result<u32, AccountError> {
+        let salt = self.check_new(username, password)?;
+        self.register_hashed(username, &salt.hash(password))
+    }
+
+    /// Checks that `username` can be registered with `password`, and returns
+    /// the salt to hash the password with.
+    pub fn check_new(&self, username: &str, password: &str) -> Result<Salt, AccountError> {
+        if !is_valid_username(username) {
+            return Err(AccountError::InvalidUsername);
+        }
+        if password.chars().count() < MIN_PASSWORD_LEN {
+            return Err(AccountError::PasswordTooShort);
+        }
+        if self.exists(username) {
+            return Err(AccountError::UsernameTaken);
+        }
+        let mut salt = [0; SALT_LEN];
+        getrandom::getrandom(&mut salt).map_err(io::Error::from)?;
+        Ok(Salt { salt, rounds: self.rounds })
+    }
+
+    /// Creates an account with a password hashed from
+    /// [`check_new`](AccountStore::check_new)'s salt.
+    pub fn register_hashed(&mut self, username: &str, password: &HashedPassword) -> Result<u32, AccountError> {
+        // someone may have taken the name while the password was hashed
+        if self.exists(username) {
+            return Err(AccountError::UsernameTaken);
+        }
+        let account = Account {
+            rounds: password.salt.rounds,
+            salt: password.salt.salt,
+            hash: password.hash,
+            chips: STARTING_CHIPS,
+            failed_logins: 0,
+            locked_until: 0,
+        };
+        self.accounts.insert(username.to_owned(), account);
+        self.save()?;
+        Ok(STARTING_CHIPS)
+    }
+
+    /// Checks the password and returns the account's chip balance.
+    pub fn login(&mut self, username: &str, password: &str) -> Result<u32, AccountError> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |since| since.as_secs());
+        self.login_at(username, password, now)
+    }
+
+    /// [`login`](AccountStore::login) at `now` seconds since the Unix epoch.
+    /// After `MAX_FAILED_LOGINS` wrong passwords in a row the account refuses
+    /// every login, right or wrong, for `LOCKOUT_SECS`.
+    pub fn login_at(&mut self, username: &str, password: &str, now: u64) -> Result<u32, AccountError> {
+        let salt = self.login_salt(username, now)?;
+        self.login_hashed(username, &salt.hash(password), now)
+    }
+
+    /// The salt the account's password was hashed with, unless the account
+    /// is locked out at `now`.
+    pub fn login_salt(&self, username: &str, now: u64) -> Result<Salt, AccountError> {
+        let account = self.accounts.get(username).ok_or(AccountError::NoSuchUser)?;
+        if now < account.locked_until {
+            return Err(AccountError::LockedOut { until: account.locked_until });
+        }
+        Ok(Salt { salt: account.salt, rounds: account.rounds })
+    }
+
+    /// Logs in with a password hashed from [`login_salt`](AccountStore::login_salt),
+    /// counting a wrong one towards the lockout.
+    pub fn login_hashed(&mut self, username: &str, password: &HashedPassword, now: u64) -> Result<u32, AccountError> {
+        let account = self.accounts.get_mut(username).ok_or(AccountError::NoSuchUser)?;
+        if now < account.locked_until {
+            return Err(AccountError::LockedOut { until: account.locked_until });
+        }
+
+        let salt = Salt { salt: account.salt, rounds: account.rounds };
+        if password.salt == salt && constant_time_eq(&password.hash, &account.hash) {
+            account.failed_logins = 0;
+            let chips = account.chips;
+            self.save()?;
+            return Ok(chips);
+        }
+
+        account.failed_logins += 1;
+        let result = if account.failed_logins >= MAX_FAILED_LOGINS {
+            account.failed_logins = 0;
+            account.locked_until = now + LOCKOUT_SECS;
+            AccountError::LockedOut { until: account.locked_until }
+        } else {
+            AccountError::WrongPassword { attempts_left: MAX_FAILED_LOGINS - account.failed_logins }
+        };
+        self.save()?;
+        Err(result)
+    }
+
+    /// Adds chips to the account, as when a player leaves a table.
+    pub fn deposit(&mut self, username: &str, chips: u32) -> Result<u32, AccountError> {
+        let account = self.accounts.get_mut(username).ok_or(AccountError::NoSuchUser)?;
+        account.chips = account.chips.saturating_add(chips);
+        let balance = account.chips;
+        self.save()?;
+        Ok(balance)
+    }
+
+    /// Takes chips from the account, as for a buy-in, and returns what is left.
+    pub fn withdraw(&mut self, username: &str, chips: u32) -> Result<u32, AccountError> {
+        let account = self.accounts.get_mut(username).ok_or(AccountError::NoSuchUser)?;
+        if account.chips < chips {
+            return Err(AccountError::NotEnoughChips { chips: account.chips, needed: chips });
+        }
+        account.chips -= chips;
+        let balance = account.chips;
+        self.save()?;
+        Ok(balance)
+    }
+
+    // Writes every account to a temporary file and moves it over the old
+    // one, so a crash mid-write can't leave half a file behind.
+    fn save(&self) -> Result<(), AccountError> {
+        let mut names: Vec<&String> = self.accounts.keys().collect();
+        names.sort();
+        let mut contents = String::new();
+        for name in names {
+            let account = &self.accounts[name];
+            contents.push_str(&format!(
+                "{} {} {} {} {} {} {}\n",
+                name,
+                account.rounds,
+                to_hex(&account.salt),
+                to_hex(&account.hash),
+                account.chips,
+                account.failed_logins,
+                account.locked_until,
+            ));
+        }
+        let temp = self.path.with_extension("tmp");
+        fs::write(&temp, contents)?;
+        fs::rename(&temp, &self.path)?;
+        Ok(())
+    }
+}
+
+fn parse_accounts(contents: &str) -> Result<HashMap<String, Account>, AccountError> {
+    let mut accounts = HashMap::new();
+    for (i, line) in contents.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+        let corrupt = AccountError::Corrupt { line: i + 1 };
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let [name, rounds, salt, hash, chips, failed_logins, locked_until] = fields[..] else {
+            return Err(corrupt);
+        };
+        let account = (|| {
+            Some(Account {
+                rounds: rounds.parse().ok()?,
+                salt: from_hex(salt)?.try_into().ok()?,
+                hash: from_hex(hash)?.try_into().ok()?,
+                chips: chips.parse().ok()?,
+                failed_logins: failed_logins.parse().ok()?,
+                locked_until: locked_until.parse().ok()?,
+            })
+        })();
+        accounts.insert(name.to_owned(), account.ok_or(corrupt)?);
+    }
+    Ok(accounts)
+}
+
+/// Whether `username` could be registered: 1 to 32 letters, digits, `_` or `-`.
+pub fn is_valid_username(username: &str) -> bool {
+    (1..=MAX_USERNAME_LEN).contains(&username.len())
+        && username.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+fn hash_password(password: &str, salt: &[u8], rounds: u32) -> [u8; HASH_LEN] {
+    let mut hash = [0; HASH_LEN];
+    pbkdf2::pbkdf2_hmac::<Sha256>(password.as_bytes(), salt, rounds, &mut hash);
+    hash
+}
+
+// Compares every byte whatever the first difference, so the time taken
+// says nothing about how much of the hash matched.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len()).step_by(2).map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+
+    use crate::accounts::{AccountError, AccountStore, LOCKOUT_SECS, MAX_FAILED_LOGINS, STARTING_CHIPS};
+
+    // A fresh accounts file for one test, hashed with few rounds to keep the
+    // tests fast.
+    fn store(name: &str) -> (AccountStore, PathBuf) {
+        let path = std::env::temp_dir().join(format!("poker-accounts-{}-{}.db", name, std::process::id()));
+        let _ = fs::remove_file(&path);
+        (AccountStore::open(&path).unwrap().with_rounds(100), path)
+    }
+
+    #[test]
+    fn test_register_and_login() {
+        let (mut accounts, path) = store("login");
+        assert!(!accounts.exists("jane"));
+        assert_eq!(accounts.register("jane", "correct horse").unwrap(), STARTING_CHIPS);
+        assert!(accounts.exists("jane"));
+        assert!(matches!(accounts.register("jane", "battery staple"), Err(AccountError::UsernameTaken)));
+
+        assert_eq!(accounts.login_at("jane", "correct horse", 0).unwrap(), STARTING_CHIPS);
+        assert!(matches!(accounts.login_at("jane", "wrong horse", 0), Err(AccountError::WrongPassword { .. })));
+        assert!(matches!(accounts.login_at("john", "correct horse", 0), Err(AccountError::NoSuchUser)));
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_hashing_apart_from_the_store() {
+        let (mut accounts, path) = store("hashed");
+        let jane = accounts.check_new("jane", "correct horse").unwrap();
+        let other = accounts.check_new("jane", "battery staple").unwrap();
+        assert_eq!(accounts.register_hashed("jane", &jane.hash("correct horse")).unwrap(), STARTING_CHIPS);
+        // the name went while the second password was being hashed
+        assert!(matches!(accounts.register_hashed("jane", &other.hash("battery staple")), Err(AccountError::UsernameTaken)));
+
+        let salt = accounts.login_salt("jane", 0).unwrap();
+        assert_eq!(accounts.login_hashed("jane", &salt.hash("correct horse"), 0).unwrap(), STARTING_CHIPS);
+        // a hash made with the wrong salt never matches
+        assert!(matches!(accounts.login_hashed("jane", &other.hash("correct horse"), 0), Err(AccountError::WrongPassword { .. })));
+        assert!(matches!(accounts.login_salt("john", 0), Err(AccountError::NoSuchUser)));
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_registration_rules() {
+        let (mut accounts, path) = store("rules");
+        assert!(matches!(accounts.register("", "long enough"), Err(AccountError::InvalidUsername)));
+        assert!(matches!(accounts.register("two words", "long enough"), Err(AccountError::InvalidUsername)));
+        assert!(matches!(accounts.register("jane", "short"), Err(AccountError::PasswordTooShort)));
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_passwords_are_salted() {
+        let (mut accounts, path) = store("salted");
+        accounts.register("jane", "same password").unwrap();
+        accounts.register("john", "same password").unwrap();
+        let contents = fs::read_to_string(&path).unwrap();
+        assert!(!contents.contains("same password"));
+        let hashes: Vec<&str> = contents.lines().map(|line| line.split(' ').nth(3).unwrap()).collect();
+        assert_ne!(hashes[0], hashes[1]);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_lockout_after_failed_logins() {
+        let (mut accounts, path) = store("lockout");
+        accounts.register("jane", "correct horse").unwrap();
+        for attempt in 1..MAX_FAILED_LOGINS {
+            let err = accounts.login_at("jane", "guess", 100).unwrap_err();
+            assert!(matches!(err, AccountError::WrongPassword { attempts_left } if attempts_left == MAX_FAILED_LOGINS - attempt));
+        }
+        let until = 100 + LOCKOUT_SECS;
+        assert!(matches!(accounts.login_at("jane", "guess", 100), Err(AccountError::LockedOut { until: u }) if u == until));
+
+        // even the right password is refused until the lockout ends
+        assert!(matches!(accounts.login_at("jane", "correct horse", until - 1), Err(AccountError::LockedOut { .. })));
+        assert_eq!(accounts.login_at("jane", "correct horse", until).unwrap(), STARTING_CHIPS);
+
+        // a good login resets the count
+        accounts.login_at("jane", "guess", until).unwrap_err();
+        accounts.login_at("jane", "correct horse", until).unwrap();
+        for _ in 1..MAX_FAILED_LOGINS {
+            assert!(matches!(accounts.login_at("jane", "guess", until), Err(AccountError::WrongPassword { .. })));
+        }
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_accounts_persist() {
+        let (mut accounts, path) = store("persist");
+        accounts.register("jane", "correct horse").unwrap();
+        assert_eq!(accounts.withdraw("jane", 400).unwrap(), 600);
+        assert!(matches!(accounts.withdraw("jane", 601), Err(AccountError::NotEnoughChips { chips: 600, needed: 601 })));
+        assert_eq!(accounts.deposit("jane", 250).unwrap(), 850);
+        accounts.login_at("jane", "guess", 0).unwrap_err();
+        drop(accounts);
+
+        let mut reopened = AccountStore::open(&path).unwrap();
+        assert_eq!(reopened.get_chips("jane"), Some(850));
+        // the failed login was saved too
+        let err = reopened.login_at("jane", "guess", 0).unwrap_err();
+        assert!(matches!(err, AccountError::WrongPassword { attempts_left } if attempts_left == MAX_FAILED_LOGINS - 2));
+        assert_eq!(reopened.login_at("jane", "correct horse", 0).unwrap(), 850);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_corrupt_file_is_reported() {
+        let (_, path) = store("corrupt");
+        fs::write(&path, "jane 100 00 zz 1000 0 0\n").unwrap();
+        assert!(matches!(AccountStore::open(&path), Err(AccountError::Corrupt { line: 1 })));
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,1204 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender, SyncSender};
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use poker_common::action::PlayerAction;
+use poker_common::card::Card;
+use poker_common::player::Player;
+use poker_common::protocol::{Award, ServerMessage, ShownHand, TableSummary};
+
+use crate::accounts::AccountStore;
+use crate::clock::{Clock, ClockConfig, SystemClock, TimeBank};
+use crate::dealer::{Dealer, DealerError, LegalAction, Round, TableConfig, Viewer};
+use crate::lobby::{deliver, LobbyError, Seating, Subscribers, AVERAGE_POT_HANDS};
+use crate::variant::{DealerFactory, Variant};
+
+/// How long a table waits after a hand before dealing the next one, so
+/// players can sit down or stand up in between.
+pub const HAND_DELAY: Duration = Duration::from_secs(3);
+
+/// What a connection can ask of a table. Requests that can fail carry a
+/// channel for the answer; betting, drawing and chat report their errors
+/// as table events instead.
+pub enum TableCommand {
+    Sit { username: String, buy_in: u32, events: SyncSender<ServerMessage>, reply: Sender<Result<usize, LobbyError>> },
+    Stand { username: String, reply: Sender<Result<u32, LobbyError>> },
+    Watch { username: String, events: SyncSender<ServerMessage>, reply: Sender<Result<(), LobbyError>> },
+    Leave { username: String, reply: Sender<Result<Option<u32>, LobbyError>> },
+    Action { username: String, action: PlayerAction },
+    Draw { username: String, discards: Vec<Card> },
+    Chat { username: String, text: String },
+    SitOut { username: String, sitting_out: bool },
+    /// The player's connection dropped; their seat is kept for a while.
+    Disconnect { username: String, reply: Sender<Result<(), LobbyError>> },
+    /// Back from a dropped connection, to the seat that was kept.
+    Reconnect { username: String, events: SyncSender<ServerMessage>, reply: Sender<Result<usize, LobbyError>> },
+}
+
+/// A connection's way to a running table. Cloning it is cheap. The table
+/// closes itself once it has stood empty for a while, and from then on
+/// every request through a handle fails with `TableClosed`.
+#[derive(Clone)]
+pub struct TableHandle {
+    id: u32,
+    commands: Sender<TableCommand>,
+    summary: Arc<Mutex<TableSummary>>,
+    open: Arc<AtomicBool>,
+}
+
+impl TableHandle {
+    pub fn get_id(&self) -> u32 {
+        self.id
+    }
+
+    /// False once the table has closed, after standing empty.
+    pub fn is_open(&self) -> bool {
+        self.open.load(Ordering::SeqCst)
+    }
+
+    pub fn summary(&self) -> TableSummary {
+        self.summary.lock().unwrap().clone()
+    }
+
+    /// Seats `username` with `buy_in` chips and returns the seat. Table
+    /// events for them go to `events` from then on, starting with `Seated`
+    /// ahead of the table's state.
+    pub fn sit(&self, username: &str, buy_in: u32, events: SyncSender<ServerMessage>) -> Result<usize, LobbyError> {
+        self.request(|reply| TableCommand::Sit { username: username.to_owned(), buy_in, events, reply })
+    }
+
+    /// Gives up the seat and returns the player's chips. They stay at the
+    /// table as a spectator.
+    pub fn stand(&self, username: &str) -> Result<u32, LobbyError> {
+        self.request(|reply| TableCommand::Stand { username: username.to_owned(), reply })
+    }
+
+    /// Sends the table's events to `events`, starting with `Watching` ahead
+    /// of the table's state.
+    pub fn watch(&self, username: &str, events: SyncSender<ServerMessage>) -> Result<(), LobbyError> {
+        self.request(|reply| TableCommand::Watch { username: username.to_owned(), events, reply })
+    }
+
+    /// Stands up if seated and stops watching. Returns the chips of a player
+    /// who was seated.
+    pub fn leave(&self, username: &str) -> Result<Option<u32>, LobbyError> {
+        self.request(|reply| TableCommand::Leave { username: username.to_owned(), reply })
+    }
+
+    pub fn act(&self, username: &str, action: PlayerAction) -> Result<(), LobbyError> {
+        self.send(TableCommand::Action { username: username.to_owned(), action })
+    }
+
+    pub fn draw(&self, username: &str, discards: Vec<Card>) -> Result<(), LobbyError> {
+        self.send(TableCommand::Draw { username: username.to_owned(), discards })
+    }
+
+    pub fn chat(&self, username: &str, text: String) -> Result<(), LobbyError> {
+        self.send(TableCommand::Chat { username: username.to_owned(), text })
+    }
+
+    pub fn sit_out(&self, username: &str, sitting_out: bool) -> Result<(), LobbyError> {
+        self.send(TableCommand::SitOut { username: username.to_owned(), sitting_out })
+    }
+
+    /// Keeps a seated player's seat while their connection is gone, and
+    /// stops sending them the table. Waits, so that by the time this returns
+    /// the lobby knows where they can reconnect.
+    pub fn disconnect(&self, username: &str) -> Result<(), LobbyError> {
+        self.request(|reply| TableCommand::Disconnect { username: username.to_owned(), reply })
+    }
+
+    /// Takes back the seat kept since the player's connection dropped, and
+    /// sends them `Seated` and then the whole table state.
+    pub fn reconnect(&self, username: &str, events: SyncSender<ServerMessage>) -> Result<usize, LobbyError> {
+        self.request(|reply| TableCommand::Reconnect { username: username.to_owned(), events, reply })
+    }
+
+    fn send(&self, command: TableCommand) -> Result<(), LobbyError> {
+        self.commands.send(command).map_err(|_| LobbyError::TableClosed(self.id))
+    }
+
+    // Sends a command and waits for the table's answer. The table never
+    // blocks while handling one, so this only waits behind other commands.
+    fn request<T>(&self, command: impl FnOnce(Sender<Result<T, LobbyError>>) -> TableCommand) -> Result<T, LobbyError> {
+        let (reply, answer) = mpsc::channel();
+        self.send(command(reply))?;
+        answer.recv().unwrap_or(Err(LobbyError::TableClosed(self.id)))
+    }
+}
+
+// Who the table is waiting on to bet or draw, and since when.
+#[derive(Clone, Copy, Debug)]
+struct Turn {
+    round: Round,
+    seat: usize,
+    started: Duration,
+    // the player's time bank when the turn began
+    bank: Duration,
+    warned: bool,
+}
+
+// A seated player whose connection has gone, since when, and whether the
+// grace period has run out and sat them out.
+#[derive(Clone, Copy, Debug)]
+struct Away {
+    since: Duration,
+    sat_out: bool,
+}
+
+impl Turn {
+    fn deadline(&self, config: &ClockConfig) -> Duration {
+        self.started + config.action_time + self.bank
+    }
+}
+
+/// One table running on its own thread. It owns the dealer outright, takes
+/// commands from connections one at a time and deals hands back to back.
+/// Events go out with `try_send`, so a client that has stopped reading only
+/// misses updates; it can never hold up the hand. Players who don't act in
+/// time are checked or folded by the action clock.
+pub struct TableActor {
+    id: u32,
+    variant: Variant,
+    config: TableConfig,
+    max_seats: usize,
+    dealer: Box<dyn Dealer>,
+    // everyone hearing the table's events, seated or watching
+    viewers: BTreeMap<String, SyncSender<ServerMessage>>,
+    pots: VecDeque<u32>,
+    // the largest the pot has been this hand
+    pot: u32,
+    hand_delay: Duration,
+    clock: Box<dyn Clock>,
+    clock_config: ClockConfig,
+    next_hand: Option<Duration>,
+    turn: Option<Turn>,
+    banks: BTreeMap<String, TimeBank>,
+    // timeouts in a row, cleared whenever the player acts for themselves
+    timeouts: BTreeMap<String, u32>,
+    away: BTreeMap<String, Away>,
+    // since when no one has been seated or watching
+    empty_since: Option<Duration>,
+    summary: Arc<Mutex<TableSummary>>,
+    open: Arc<AtomicBool>,
+    subscribers: Arc<Mutex<Subscribers>>,
+    seating: Arc<Mutex<Seating>>,
+    accounts: Option<Arc<Mutex<AccountStore>>>,
+}
+
+impl TableActor {
+    pub fn new(id: u32, variant: Variant, config: TableConfig, max_seats: usize, subscribers: Arc<Mutex<Subscribers>>) -> TableActor {
+        TableActor {
+            id,
+            variant,
+            config,
+            max_seats,
+            dealer: variant.dealer(config),
+            viewers: BTreeMap::new(),
+            pots: VecDeque::new(),
+            pot: 0,
+            hand_delay: HAND_DELAY,
+            clock: Box::new(SystemClock::new()),
+            clock_config: ClockConfig::default(),
+            next_hand: None,
+            turn: None,
+            banks: BTreeMap::new(),
+            timeouts: BTreeMap::new(),
+            away: BTreeMap::new(),
+            empty_since: None,
+            summary: Arc::new(Mutex::new(TableSummary {
+                id,
+                variant,
+                stakes: config.stakes,
+                players: 0,
+                max_seats: max_seats as u32,
+                average_pot: 0,
+            })),
+            open: Arc::new(AtomicBool::new(true)),
+            subscribers,
+            seating: Arc::default(),
+            accounts: None,
+        }
+    }
+
+    pub fn with_hand_delay(mut self, hand_delay: Duration) -> TableActor {
+        self.hand_delay = hand_delay;
+        self
+    }
+
+    /// Keeps time with `clock` instead of the system clock.
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> TableActor {
+        self.clock = Box::new(clock);
+        self
+    }
+
+    pub fn with_clock_config(mut self, clock_config: ClockConfig) -> TableActor {
+        self.clock_config = clock_config;
+        self
+    }
+
+    /// Shares the lobby's record of who is seated where.
+    pub fn with_seating(mut self, seating: Arc<Mutex<Seating>>) -> TableActor {
+        self.seating = seating;
+        self
+    }
+
+    /// Where the chips of a dropped player go once the table lets their seat go.
+    pub fn with_accounts(mut self, accounts: Arc<Mutex<AccountStore>>) -> TableActor {
+        self.accounts = Some(accounts);
+        self
+    }
+
+    /// Starts the table's thread and returns a handle to it. Fails if the
+    /// system won't start another thread.
+    pub fn spawn(self) -> io::Result<TableHandle> {
+        let (commands, receiver) = mpsc::channel();
+        let handle = TableHandle { id: self.id, commands, summary: Arc::clone(&self.summary), open: Arc::clone(&self.open) };
+        thread::Builder::new().name(format!("table {}", self.id)).spawn(move || self.run(receiver))?;
+        Ok(handle)
+    }
+
+    // Announces the table, then runs it until it has stood empty long
+    // enough to close.
+    fn run(mut self, commands: Receiver<TableCommand>) {
+        self.publish();
+        self.tick();
+        while !self.is_closing() {
+            let command = match self.next_wake() {
+                Some(at) => match commands.recv_timeout(at.saturating_sub(self.clock.now())) {
+                    Ok(command) => command,
+                    Err(RecvTimeoutError::Timeout) => {
+                        self.tick();
+                        continue;
+                    }
+                    Err(RecvTimeoutError::Disconnected) => break,
+                },
+                None => match commands.recv() {
+                    Ok(command) => command,
+                    Err(_) => break,
+                },
+            };
+            self.handle(command);
+            // a steady stream of commands mustn't keep the clock from running
+            self.tick();
+        }
+        self.close();
+    }
+
+    pub fn handle(&mut self, command: TableCommand) {
+        // a requester that gave up waiting doesn't need the answer
+        match command {
+            TableCommand::Sit { username, buy_in, events, reply } => {
+                let _ = reply.send(self.sit(&username, buy_in, events));
+            }
+            TableCommand::Stand { username, reply } => {
+                let _ = reply.send(self.stand(&username));
+            }
+            TableCommand::Watch { username, events, reply } => {
+                let _ = reply.send(self.watch(&username, events));
+            }
+            TableCommand::Leave { username, reply } => {
+                let _ = reply.send(self.leave(&username));
+            }
+            TableCommand::Action { username, action } => {
+                let acted = self.seat(&username).and_then(|seat| self.dealer.apply_action(seat, action).map_err(LobbyError::from));
+                self.played(&username, acted.map(drop));
+            }
+            TableCommand::Draw { username, discards } => {
+                let drawn = self.seat(&username).and_then(|seat| self.dealer.draw(seat, &discards).map_err(LobbyError::from));
+                self.played(&username, drawn);
+            }
+            TableCommand::Chat { username, text } => {
+                let message = ServerMessage::Chat { table: self.id, from: username, text };
+                self.viewers.retain(|_, events| deliver(events, message.clone()));
+            }
+            TableCommand::SitOut { username, sitting_out } => {
+                let sat = self.seat(&username).and_then(|seat| self.dealer.sit_out(seat, sitting_out).map_err(LobbyError::from));
+                self.played(&username, sat);
+            }
+            TableCommand::Disconnect { username, reply } => {
+                let _ = reply.send(self.disconnect(&username));
+            }
+            TableCommand::Reconnect { username, events, reply } => {
+                let _ = reply.send(self.reconnect(&username, events));
+            }
+        }
+    }
+
+    /// Deals the next hand, warns the player to act or acts for them,
+    /// whichever the clock says is due.
+    pub fn tick(&mut self) {
+        let now = self.clock.now();
+        self.check_empty(now);
+        self.check_away(now);
+        if self.next_hand.is_some_and(|at| now >= at) {
+            self.start_hand();
+        }
+        let Some(turn) = self.turn else {
+            return;
+        };
+        let deadline = turn.deadline(&self.clock_config);
+        if now >= deadline {
+            self.time_out(turn);
+        } else if !turn.warned && now + self.clock_config.warning >= deadline {
+            self.warn(&turn, deadline - now);
+        }
+    }
+
+    // When the clock next has something to do.
+    fn next_wake(&self) -> Option<Duration> {
+        let turn = self.turn.map(|turn| {
+            let deadline = turn.deadline(&self.clock_config);
+            if turn.warned { deadline } else { deadline.saturating_sub(self.clock_config.warning) }
+        });
+        // a seat can only be let go of between hands
+        let config = &self.clock_config;
+        let waiting = self.dealer.round() == Round::Waiting;
+        let away = self.away.values().filter_map(|away| match away.sat_out {
+            false => Some(away.since + config.reconnect_grace),
+            true if waiting => Some(away.since + config.reconnect_grace + config.removal),
+            true => None,
+        });
+        let closing = self.empty_since.map(|since| since + config.close_empty);
+        [self.next_hand, turn, closing].into_iter().flatten().chain(away).min()
+    }
+
+    // Starts the wait to close once no one is seated or watching, and calls
+    // it off when someone comes back.
+    fn check_empty(&mut self, now: Duration) {
+        if self.viewers.is_empty() && self.dealer.players().is_empty() {
+            self.empty_since.get_or_insert(now);
+        } else {
+            self.empty_since = None;
+        }
+    }
+
+    /// True once the table has stood empty for long enough to close.
+    pub fn is_closing(&self) -> bool {
+        self.empty_since.is_some_and(|since| self.clock.now() >= since + self.clock_config.close_empty)
+    }
+
+    // Takes the table out of the lobby. Commands still on their way get no
+    // answer, which their senders take as the table having closed.
+    fn close(&mut self) {
+        self.open.store(false, Ordering::SeqCst);
+        self.subscribers.lock().unwrap().publish(ServerMessage::TableClosed { table: self.id });
+    }
+
+    pub fn players(&self) -> &[Player] {
+        self.dealer.players()
+    }
+
+    pub fn round(&self) -> Round {
+        self.dealer.round()
+    }
+
+    /// Those watching without a seat.
+    pub fn spectators(&self) -> Vec<&str> {
+        self.viewers.keys().map(String::as_str).filter(|name| self.seat_of(name).is_none()).collect()
+    }
+
+    pub fn get_next_hand(&self) -> Option<Duration> {
+        self.next_hand
+    }
+
+    pub fn get_timeouts(&self, username: &str) -> u32 {
+        self.timeouts.get(username).copied().unwrap_or(0)
+    }
+
+    fn seat_of(&self, username: &str) -> Option<usize> {
+        self.dealer.players().iter().position(|player| player.get_name() == username)
+    }
+
+    fn seat(&self, username: &str) -> Result<usize, LobbyError> {
+        self.seat_of(username).ok_or(LobbyError::NotSeated(self.id))
+    }
+
+    // Enough to post the forced bets of one hand.
+    fn min_buy_in(&self) -> u32 {
+        let stakes = self.config.stakes;
+        (stakes.ante + stakes.big_blind.max(stakes.bring_in)).max(1)
+    }
+
+    fn sit(&mut self, username: &str, buy_in: u32, events: SyncSender<ServerMessage>) -> Result<usize, LobbyError> {
+        if self.seat_of(username).is_some() {
+            return Err(LobbyError::AlreadySeated(self.id));
+        }
+        if self.dealer.players().len() >= self.max_seats {
+            return Err(LobbyError::TableFull(self.id));
+        }
+        if buy_in < self.min_buy_in() {
+            return Err(LobbyError::BuyInTooSmall { min: self.min_buy_in() });
+        }
+        if self.dealer.round() != Round::Waiting {
+            return Err(LobbyError::HandInProgress(self.id));
+        }
+
+        let id = self.dealer.players().len() as u32;
+        let seat = self.dealer.seat_player(Player::new(username.to_owned(), id, buy_in));
+        self.seating.lock().unwrap().seat(username, self.id);
+        self.join(username, seat, events);
+        Ok(seat)
+    }
+
+    fn stand(&mut self, username: &str) -> Result<u32, LobbyError> {
+        let seat = self.seat(username)?;
+        let player = self.dealer.remove_player(seat).map_err(|err| match err {
+            DealerError::WrongRound { .. } => LobbyError::HandInProgress(self.id),
+            _ => LobbyError::NotSeated(self.id),
+        })?;
+        self.banks.remove(username);
+        self.timeouts.remove(username);
+        self.away.remove(username);
+        self.seating.lock().unwrap().unseat(username);
+        self.changed();
+        Ok(player.get_total_chips())
+    }
+
+    fn disconnect(&mut self, username: &str) -> Result<(), LobbyError> {
+        self.viewers.remove(username);
+        self.seat(username)?;
+        self.away.insert(username.to_owned(), Away { since: self.clock.now(), sat_out: false });
+        // their turn, if it is one, is played for them at once
+        self.settle();
+        self.broadcast();
+        Ok(())
+    }
+
+    fn reconnect(&mut self, username: &str, events: SyncSender<ServerMessage>) -> Result<usize, LobbyError> {
+        let seat = self.seat(username)?;
+        if let Some(away) = self.away.remove(username) {
+            if away.sat_out {
+                self.dealer.sit_out(seat, false)?;
+                self.timeouts.remove(username);
+            }
+        }
+        self.join(username, seat, events);
+        Ok(seat)
+    }
+
+    // The player hears they are seated before any of the table's state, which
+    // only reaches them once they are one of its viewers.
+    fn join(&mut self, username: &str, seat: usize, events: SyncSender<ServerMessage>) {
+        deliver(&events, ServerMessage::Seated { table: self.id, seat: seat as u32 });
+        self.viewers.insert(username.to_owned(), events);
+        self.changed();
+    }
+
+    // Sits out the players gone past the grace period, and between hands lets
+    // go of the seats of those gone longer still, paying their chips back.
+    fn check_away(&mut self, now: Duration) {
+        let config = self.clock_config;
+        let mut changed = false;
+        for (name, away) in self.away.iter_mut() {
+            if !away.sat_out && now >= away.since + config.reconnect_grace {
+                if let Some(seat) = self.dealer.players().iter().position(|player| player.get_name() == name) {
+                    let _ = self.dealer.sit_out(seat, true);
+                }
+                away.sat_out = true;
+                changed = true;
+            }
+        }
+        if self.dealer.round() == Round::Waiting {
+            let gone: Vec<String> = self.away.iter()
+                .filter(|(_, away)| away.sat_out && now >= away.since + config.reconnect_grace + config.removal)
+                .map(|(name, _)| name.clone())
+                .collect();
+            for name in gone {
+                self.remove_away(&name);
+            }
+        }
+        if changed {
+            self.changed();
+        }
+    }
+
+    fn remove_away(&mut self, username: &str) {
+        match self.stand(username) {
+            Ok(chips) => {
+                if let Some(accounts) = &self.accounts {
+                    // the account is known, so this only fails on a write
+                    // error, and there is no one left to tell
+                    let _ = accounts.lock().unwrap().deposit(username, chips);
+                }
+            }
+            // no longer seated, so there is nothing to hold
+            Err(_) => {
+                self.away.remove(username);
+            }
+        }
+    }
+
+    fn watch(&mut self, username: &str, events: SyncSender<ServerMessage>) -> Result<(), LobbyError> {
+        if self.seat_of(username).is_some() {
+            return Err(LobbyError::AlreadySeated(self.id));
+        }
+        let state = self.state_for(username);
+        deliver(&events, ServerMessage::Watching { table: self.id });
+        deliver(&events, state);
+        self.viewers.insert(username.to_owned(), events);
+        Ok(())
+    }
+
+    fn leave(&mut self, username: &str) -> Result<Option<u32>, LobbyError> {
+        let chips = match self.seat_of(username) {
+            Some(_) => Some(self.stand(username)?),
+            None => None,
+        };
+        self.viewers.remove(username);
+        Ok(chips)
+    }
+
+    // After a bet, a draw or sitting out or in: a mistake goes back to
+    // whoever made it, anything else moves the hand along for everyone.
+    fn played(&mut self, username: &str, result: Result<(), LobbyError>) {
+        match result {
+            Ok(()) => {
+                self.charge_clock(username);
+                self.settle();
+                self.broadcast();
+            }
+            Err(err) => {
+                if let Some(events) = self.viewers.get(username) {
+                    deliver(events, ServerMessage::Error(err.to_string()));
+                }
+            }
+        }
+    }
+
+    /// Deals the next hand if the table is waiting on one. Without two
+    /// players with chips the table goes quiet until someone sits down.
+    pub fn start_hand(&mut self) {
+        self.next_hand = None;
+        if self.dealer.round() != Round::Waiting {
+            return;
+        }
+        if self.dealer.start_hand(seed()).is_ok() {
+            self.pot = 0;
+            self.settle();
+            self.broadcast();
+        }
+    }
+
+    // Takes the hand through every step that waits on no one: dealing the
+    // next street once betting closes, the showdown and the clear-up.
+    fn settle(&mut self) {
+        loop {
+            let round = self.dealer.round();
+            if !matches!(round, Round::Waiting | Round::Complete) {
+                self.pot = self.pot.max(self.dealer.view_for(Viewer::Spectator).pot);
+            }
+            let step = match round {
+                Round::Waiting => break,
+                Round::Showdown => self.dealer.showdown().map(drop),
+                Round::Complete => self.finish_hand(),
+                _ => match self.to_act() {
+                    Some(seat) if self.is_absent(seat) => self.act_for(seat),
+                    Some(_) => break,
+                    None => self.dealer.advance().map(drop),
+                },
+            };
+            // the dealer refused to move on, so leave the hand where it is
+            // rather than spin
+            if step.is_err() {
+                break;
+            }
+        }
+        self.schedule();
+        self.update_turn();
+    }
+
+    // Whoever the hand waits on to bet or draw, as everyone at the table sees it.
+    fn to_act(&self) -> Option<usize> {
+        self.dealer.view_for(Viewer::Spectator).to_act
+    }
+
+    // Sitting out or disconnected, so there's no one to wait for.
+    fn is_absent(&self, seat: usize) -> bool {
+        let player = &self.dealer.players()[seat];
+        player.is_sitting_out() || self.away.contains_key(player.get_name())
+    }
+
+    fn default_action(&self, seat: usize) -> PlayerAction {
+        if self.dealer.legal_actions(seat).contains(&LegalAction::Check) {
+            PlayerAction::Check
+        } else {
+            PlayerAction::Fold
+        }
+    }
+
+    // What the clock does for a player: stand pat in a draw, otherwise check
+    // if it's free and fold if not.
+    fn act_for(&mut self, seat: usize) -> Result<(), DealerError> {
+        if self.dealer.round() == Round::Draw {
+            self.dealer.draw(seat, &[])
+        } else {
+            self.dealer.apply_action(seat, self.default_action(seat)).map(drop)
+        }
+    }
+
+    // Starts the clock on whoever the hand now waits for, unless it was
+    // already running for them.
+    fn update_turn(&mut self) {
+        let round = self.dealer.round();
+        let Some(seat) = self.to_act() else {
+            self.turn = None;
+            return;
+        };
+        if self.turn.is_some_and(|turn| turn.round == round && turn.seat == seat) {
+            return;
+        }
+        let now = self.clock.now();
+        let config = &self.clock_config;
+        let name = self.dealer.players()[seat].get_name().clone();
+        let bank = self.banks.entry(name).or_insert_with(|| TimeBank::new(config, now)).remaining(config, now);
+        self.turn = Some(Turn { round, seat, started: now, bank, warned: false });
+    }
+
+    // Someone acted for themselves: any time over the action time comes out
+    // of their bank, and their run of timeouts is over.
+    fn charge_clock(&mut self, username: &str) {
+        self.timeouts.remove(username);
+        let Some(turn) = self.turn else {
+            return;
+        };
+        if Some(turn.seat) == self.seat_of(username) {
+            let over = (self.clock.now() - turn.started).saturating_sub(self.clock_config.action_time);
+            if let Some(bank) = self.banks.get_mut(username) {
+                bank.spend(over);
+            }
+        }
+    }
+
+    fn warn(&mut self, turn: &Turn, left: Duration) {
+        let warning = ServerMessage::ClockWarning { table: self.id, seconds_left: left.as_secs_f64().ceil() as u32 };
+        if let Some(events) = self.viewers.get(self.dealer.players()[turn.seat].get_name()) {
+            deliver(events, warning);
+        }
+        self.turn = Some(Turn { warned: true, ..*turn });
+    }
+
+    // The time is up, so the clock acts for the player. Too many timeouts in
+    // a row sits them out.
+    fn time_out(&mut self, turn: Turn) {
+        let seat = turn.seat;
+        if self.act_for(seat).is_ok() {
+            let name = self.dealer.players()[seat].get_name().clone();
+            if let Some(bank) = self.banks.get_mut(&name) {
+                bank.spend(turn.bank);
+            }
+            let timeouts = self.timeouts.entry(name).or_insert(0);
+            *timeouts += 1;
+            if *timeouts >= self.clock_config.max_timeouts {
+                let _ = self.dealer.sit_out(seat, true);
+            }
+        }
+        self.turn = None;
+        self.settle();
+        self.broadcast();
+    }
+
+    // Tells the table who won and shows the hands still in, if more than
+    // one got this far, then clears the hand away.
+    fn finish_hand(&mut self) -> Result<(), DealerError> {
+        let players = self.dealer.players();
+        let in_hand: Vec<usize> = (0..players.len()).filter(|&seat| players[seat].is_active()).collect();
+        let shown = match in_hand.len() {
+            0 | 1 => Vec::new(),
+            _ => in_hand.iter()
+                .map(|&seat| ShownHand { seat: seat as u32, name: players[seat].get_name().clone(), cards: players[seat].get_hand().clone() })
+                .collect(),
+        };
+        let awards = self.dealer.awards().into_iter()
+            .map(|(seat, chips)| Award { seat: seat as u32, name: players[seat].get_name().clone(), chips })
+            .collect();
+        self.dealer.end_hand()?;
+        let result = ServerMessage::HandResult { table: self.id, awards, shown };
+        self.viewers.retain(|_, events| deliver(events, result.clone()));
+        if self.pots.len() == AVERAGE_POT_HANDS {
+            self.pots.pop_front();
+        }
+        self.pots.push_back(self.pot);
+        self.pot = 0;
+        self.changed();
+        Ok(())
+    }
+
+    fn schedule(&mut self) {
+        let dealt_in = self.dealer.players().iter().filter(|player| player.get_total_chips() > 0 && !player.is_sitting_out());
+        let ready = dealt_in.count() >= 2;
+        if self.next_hand.is_none() && ready && self.dealer.round() == Round::Waiting {
+            self.next_hand = Some(self.clock.now() + self.hand_delay);
+        }
+    }
+
+    // The seating changed or a hand finished: tell the lobby and the table.
+    fn changed(&mut self) {
+        self.publish();
+        self.broadcast();
+        self.schedule();
+    }
+
+    fn publish(&mut self) {
+        let average_pot = match self.pots.len() {
+            0 => 0,
+            hands => (self.pots.iter().map(|&pot| u64::from(pot)).sum::<u64>() / hands as u64) as u32,
+        };
+        let summary = TableSummary {
+            id: self.id,
+            variant: self.variant,
+            stakes: self.config.stakes,
+            players: self.dealer.players().len() as u32,
+            max_seats: self.max_seats as u32,
+            average_pot,
+        };
+        *self.summary.lock().unwrap() = summary.clone();
+        self.subscribers.lock().unwrap().publish(ServerMessage::TableUpdated(summary));
+    }
+
+    // The table as `username` sees it: their own cards if seated, and no
+    // one's if watching.
+    fn state_for(&self, username: &str) -> ServerMessage {
+        let viewer = self.seat_of(username).map_or(Viewer::Spectator, Viewer::Seat);
+        ServerMessage::TableState { table: self.id, view: self.dealer.view_for(viewer) }
+    }
+
+    fn broadcast(&mut self) {
+        let states: Vec<ServerMessage> = self.viewers.keys().map(|name| self.state_for(name)).collect();
+        let mut states = states.into_iter();
+        self.viewers.retain(|_, events| deliver(events, states.next().unwrap()));
+    }
+}
+
+fn seed() -> u64 {
+    let mut bytes = [0; 8];
+    match getrandom::getrandom(&mut bytes) {
+        Ok(()) => u64::from_le_bytes(bytes),
+        Err(_) => SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |since| since.as_nanos() as u64),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc::{self, Receiver, SyncSender};
+    use std::fs;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use poker_common::action::PlayerAction;
+    use poker_common::protocol::ServerMessage;
+
+    use crate::accounts::{AccountStore, STARTING_CHIPS};
+    use crate::actor::{TableActor, TableCommand};
+    use crate::clock::{Clock, ClockConfig, VirtualClock};
+    use crate::dealer::{Round, TableConfig, Viewer};
+    use crate::lobby::{LobbyError, Seating};
+    use crate::variant::Variant;
+
+    fn holdem(max_seats: usize) -> TableActor {
+        TableActor::new(1, Variant::TexasHoldem, TableConfig::default(), max_seats, Arc::default())
+    }
+
+    // A connection for the tests that don't read what the table sends; the
+    // inbox has to be kept, or the table drops the viewer.
+    fn connection() -> (SyncSender<ServerMessage>, Receiver<ServerMessage>) {
+        mpsc::sync_channel(1024)
+    }
+
+    fn clocked(clock: &VirtualClock) -> TableActor {
+        let config = ClockConfig {
+            action_time: Duration::from_secs(20),
+            warning: Duration::from_secs(5),
+            time_bank: Duration::from_secs(10),
+            refill: Duration::from_secs(5),
+            refill_every: Duration::from_secs(60),
+            max_timeouts: 2,
+            reconnect_grace: Duration::from_secs(60),
+            removal: Duration::from_secs(300),
+            close_empty: Duration::from_secs(60),
+        };
+        holdem(6).with_hand_delay(Duration::ZERO).with_clock(clock.clone()).with_clock_config(config)
+    }
+
+    fn to_act(actor: &TableActor) -> Option<String> {
+        actor.to_act().map(|seat| actor.players()[seat].get_name().clone())
+    }
+
+    fn disconnect(actor: &mut TableActor, username: &str) -> Result<(), LobbyError> {
+        let (reply, answer) = mpsc::channel();
+        actor.handle(TableCommand::Disconnect { username: username.to_owned(), reply });
+        answer.recv().unwrap()
+    }
+
+    fn reconnect(actor: &mut TableActor, username: &str, events: SyncSender<ServerMessage>) -> Result<usize, LobbyError> {
+        let (reply, answer) = mpsc::channel();
+        actor.handle(TableCommand::Reconnect { username: username.to_owned(), events, reply });
+        answer.recv().unwrap()
+    }
+
+    // Acts for whoever is to act, the way their connection would.
+    fn act(actor: &mut TableActor, action: PlayerAction) {
+        let seat = (0..actor.players().len()).find(|&seat| !actor.dealer.legal_actions(seat).is_empty()).unwrap();
+        let username = actor.players()[seat].get_name().clone();
+        actor.handle(TableCommand::Action { username, action });
+    }
+
+    #[test]
+    fn test_sit_and_stand() {
+        let mut actor = holdem(2);
+        let (events, _inbox) = connection();
+        assert_eq!(actor.sit("jane", 500, events.clone()), Ok(0));
+        assert_eq!(actor.sit("jane", 500, events.clone()), Err(LobbyError::AlreadySeated(1)));
+        assert_eq!(actor.sit("john", 5, events.clone()), Err(LobbyError::BuyInTooSmall { min: 10 }));
+        assert_eq!(actor.sit("john", 300, events.clone()), Ok(1));
+        assert_eq!(actor.sit("jack", 300, events.clone()), Err(LobbyError::TableFull(1)));
+        assert_eq!(actor.summary.lock().unwrap().players, 2);
+
+        // standing up cashes out and leaves the player watching
+        assert_eq!(actor.stand("jane"), Ok(500));
+        assert_eq!(actor.spectators(), ["jane"]);
+        assert_eq!(actor.players()[0].get_name(), "john");
+        assert_eq!(actor.stand("jane"), Err(LobbyError::NotSeated(1)));
+
+        // sitting back down stops spectating
+        assert_eq!(actor.sit("jane", 200, events.clone()), Ok(1));
+        assert!(actor.spectators().is_empty());
+    }
+
+    #[test]
+    fn test_no_seat_changes_mid_hand() {
+        let mut actor = holdem(3);
+        let (events, _inbox) = connection();
+        actor.sit("jane", 500, events.clone()).unwrap();
+        assert_eq!(actor.get_next_hand(), None);
+        actor.sit("john", 500, events.clone()).unwrap();
+        assert!(actor.get_next_hand().is_some());
+        actor.start_hand();
+        assert_eq!(actor.round(), Round::Preflop);
+        assert_eq!(actor.sit("jack", 500, events.clone()), Err(LobbyError::HandInProgress(1)));
+        assert_eq!(actor.stand("jane"), Err(LobbyError::HandInProgress(1)));
+        // watching is fine at any time
+        assert_eq!(actor.watch("jack", events.clone()), Ok(()));
+    }
+
+    #[test]
+    fn test_watch_and_leave() {
+        let mut actor = holdem(6);
+        let (events, _inbox) = connection();
+        actor.watch("jack", events.clone()).unwrap();
+        actor.watch("jack", events.clone()).unwrap();
+        assert_eq!(actor.spectators().len(), 1);
+        assert_eq!(actor.leave("jack"), Ok(None));
+        assert!(actor.spectators().is_empty());
+
+        actor.sit("jane", 400, events.clone()).unwrap();
+        assert_eq!(actor.watch("jane", events.clone()), Err(LobbyError::AlreadySeated(1)));
+        assert_eq!(actor.leave("jane"), Ok(Some(400)));
+        assert!(actor.spectators().is_empty());
+        assert_eq!(actor.summary.lock().unwrap().players, 0);
+    }
+
+    #[test]
+    fn test_hands_play_out_from_commands() {
+        let mut actor = holdem(6);
+        let (events, _inbox) = connection();
+        let (jane, to_jane): (_, Receiver<ServerMessage>) = mpsc::sync_channel(1024);
+        actor.sit("jane", 500, jane).unwrap();
+        actor.sit("john", 500, events.clone()).unwrap();
+        actor.start_hand();
+        to_jane.try_iter().for_each(drop);
+
+        // acting out of turn only tells the one who tried
+        let waiting = (0..2).find(|&seat| actor.dealer.legal_actions(seat).is_empty()).unwrap();
+        let username = actor.players()[waiting].get_name().clone();
+        actor.handle(TableCommand::Action { username, action: PlayerAction::Check });
+        if waiting == 0 {
+            assert!(matches!(to_jane.try_recv(), Ok(ServerMessage::Error(_))));
+        }
+
+        // folding the blinds ends the hand at once, and no one shows
+        act(&mut actor, PlayerAction::Fold);
+        assert_eq!(actor.round(), Round::Waiting);
+        assert_eq!(actor.summary.lock().unwrap().average_pot, 15);
+        let sent: Vec<ServerMessage> = to_jane.try_iter().collect();
+        let result = sent.iter().find_map(|message| match message {
+            ServerMessage::HandResult { awards, shown, .. } => Some((awards.clone(), shown.clone())),
+            _ => None,
+        });
+        let (awards, shown) = result.expect("no hand result");
+        assert_eq!(awards.iter().map(|award| award.chips).sum::<u32>(), 15);
+        assert!(shown.is_empty());
+        assert!(matches!(sent.last(), Some(ServerMessage::TableState { view, .. }) if view.round == Round::Waiting));
+
+        // the next one is checked down to a showdown
+        actor.start_hand();
+        while actor.round() != Round::Waiting {
+            let seat = (0..2).find(|&seat| !actor.dealer.legal_actions(seat).is_empty()).unwrap();
+            let to_call = actor.dealer.view_for(Viewer::Seat(seat)).current_bet > actor.players()[seat].get_current_bet();
+            act(&mut actor, if to_call { PlayerAction::Call } else { PlayerAction::Check });
+        }
+        assert_eq!(actor.summary.lock().unwrap().average_pot, (15 + 20) / 2);
+        assert_eq!(actor.players().iter().map(|player| player.get_total_chips()).sum::<u32>(), 1000);
+        // both hands are shown, before the table resets
+        let sent: Vec<ServerMessage> = to_jane.try_iter().collect();
+        let result = sent.iter().position(|message| matches!(message, ServerMessage::HandResult { .. })).expect("no hand result");
+        let ServerMessage::HandResult { table: 1, awards, shown } = &sent[result] else { unreachable!() };
+        assert_eq!(awards.iter().map(|award| award.chips).sum::<u32>(), 20);
+        assert_eq!(shown.iter().map(|hand| hand.name.as_str()).collect::<Vec<_>>(), ["jane", "john"]);
+        assert!(shown.iter().all(|hand| hand.cards.len() == 2));
+        assert!(matches!(&sent[result - 1], ServerMessage::TableState { view, .. } if view.round != Round::Waiting));
+    }
+
+    #[test]
+    fn test_gone_clients_stop_hearing_the_table() {
+        let mut actor = holdem(6);
+        let (events, _inbox) = connection();
+        let (jane, to_jane) = mpsc::sync_channel(1);
+        actor.sit("jane", 500, jane).unwrap();
+        let (jack, to_jack) = mpsc::sync_channel(1024);
+        actor.watch("jack", jack).unwrap();
+        drop(to_jack);
+
+        // jane's connection is full, so she misses updates but keeps her seat
+        actor.sit("john", 500, events.clone()).unwrap();
+        actor.handle(TableCommand::Chat { username: "john".to_owned(), text: "hi".to_owned() });
+        assert_eq!(to_jane.try_iter().count(), 1);
+        assert!(actor.viewers.contains_key("jane"));
+        assert!(!actor.viewers.contains_key("jack"));
+    }
+
+    #[test]
+    fn test_the_clock_warns_then_acts() {
+        let clock = VirtualClock::new();
+        let mut actor = clocked(&clock);
+        let (events, _inbox) = connection();
+        let (jane, to_jane) = mpsc::sync_channel(1024);
+        actor.sit("jane", 500, jane).unwrap();
+        actor.sit("john", 500, events.clone()).unwrap();
+        actor.tick();
+        // jane has the button, so she posts the small blind and acts first
+        assert_eq!(to_act(&actor).unwrap(), "jane");
+        to_jane.try_iter().for_each(drop);
+
+        // twenty seconds to act and ten in the bank, with a warning at five to go
+        clock.advance(Duration::from_secs(24));
+        actor.tick();
+        assert!(to_jane.try_recv().is_err());
+        clock.advance(Duration::from_secs(1));
+        actor.tick();
+        assert_eq!(to_jane.try_recv(), Ok(ServerMessage::ClockWarning { table: 1, seconds_left: 5 }));
+        clock.advance(Duration::from_secs(4));
+        actor.tick();
+        assert_eq!(to_act(&actor).unwrap(), "jane");
+
+        // calling isn't free, so the clock folds
+        clock.advance(Duration::from_secs(1));
+        actor.tick();
+        assert_eq!(actor.round(), Round::Waiting);
+        assert_eq!(actor.get_timeouts("jane"), 1);
+        assert_eq!(actor.players()[1].get_total_chips(), 505);
+
+        // next hand she is in the big blind and can check for free, and her
+        // bank is spent
+        actor.tick();
+        act(&mut actor, PlayerAction::Call);
+        assert_eq!(to_act(&actor).unwrap(), "jane");
+        clock.advance(Duration::from_secs(20));
+        actor.tick();
+        assert_eq!(actor.round(), Round::Flop);
+        assert_eq!(actor.get_timeouts("jane"), 2);
+        assert!(actor.players()[0].is_sitting_out());
+    }
+
+    #[test]
+    fn test_repeated_timeouts_sit_the_player_out() {
+        let clock = VirtualClock::new();
+        let mut actor = clocked(&clock);
+        let (events, _inbox) = connection();
+        actor.sit("jane", 500, events.clone()).unwrap();
+        actor.sit("john", 500, events.clone()).unwrap();
+
+        // john plays along at once; jane has walked away
+        let mut hands = 0;
+        while !actor.players()[0].is_sitting_out() {
+            assert!(hands < 10, "jane was never sat out");
+            actor.tick();
+            match to_act(&actor).as_deref() {
+                Some("john") => {
+                    let action = if actor.default_action(1) == PlayerAction::Check { PlayerAction::Check } else { PlayerAction::Call };
+                    act(&mut actor, action);
+                }
+                Some(_) => {
+                    clock.advance(Duration::from_secs(60));
+                    actor.tick();
+                }
+                None => hands += 1,
+            }
+        }
+        assert_eq!(actor.get_timeouts("jane"), 2);
+
+        // whatever is left of the hand is checked or folded for her at once,
+        // and no new hand starts with only john sitting in
+        while actor.round() != Round::Waiting {
+            assert_eq!(to_act(&actor).as_deref(), Some("john"));
+            act(&mut actor, PlayerAction::Check);
+        }
+        assert_eq!(actor.get_next_hand(), None);
+
+        // coming back clears the record and gets the game going again
+        actor.handle(TableCommand::SitOut { username: "jane".to_owned(), sitting_out: false });
+        assert_eq!(actor.get_timeouts("jane"), 0);
+        assert!(actor.get_next_hand().is_some());
+    }
+
+    #[test]
+    fn test_time_bank_is_spent_and_refilled() {
+        let clock = VirtualClock::new();
+        let mut actor = clocked(&clock);
+        let (events, _inbox) = connection();
+        actor.sit("jane", 500, events.clone()).unwrap();
+        actor.sit("john", 500, events.clone()).unwrap();
+        actor.tick();
+
+        // 26 seconds to call is 6 from the bank
+        let first = to_act(&actor).unwrap();
+        clock.advance(Duration::from_secs(26));
+        act(&mut actor, PlayerAction::Call);
+        let config = actor.clock_config;
+        assert_eq!(actor.banks.get_mut(&first).unwrap().remaining(&config, clock.now()), Duration::from_secs(4));
+        assert_eq!(actor.get_timeouts(&first), 0);
+
+        // the next turn only has what is left, until the refill comes round
+        act(&mut actor, PlayerAction::Check);
+        act(&mut actor, PlayerAction::Check);
+        assert_eq!(to_act(&actor).unwrap(), first);
+        assert_eq!(actor.turn.unwrap().bank, Duration::from_secs(4));
+        clock.advance(Duration::from_secs(40));
+        assert_eq!(actor.banks.get_mut(&first).unwrap().remaining(&config, clock.now()), Duration::from_secs(9));
+    }
+
+    #[test]
+    fn test_the_clock_stands_pat_for_a_missing_draw() {
+        let clock = VirtualClock::new();
+        let config = ClockConfig { time_bank: Duration::ZERO, ..ClockConfig::default() };
+        let mut actor = TableActor::new(1, Variant::FiveCardDraw, TableConfig::default(), 6, Arc::default())
+            .with_hand_delay(Duration::ZERO)
+            .with_clock(clock.clone())
+            .with_clock_config(config);
+        let (events, _inbox) = connection();
+        actor.sit("jane", 500, events.clone()).unwrap();
+        actor.sit("john", 500, events.clone()).unwrap();
+        actor.tick();
+        act(&mut actor, PlayerAction::Call);
+        act(&mut actor, PlayerAction::Check);
+        assert_eq!(actor.round(), Round::Draw);
+
+        actor.handle(TableCommand::Draw { username: "john".to_owned(), discards: Vec::new() });
+        assert_eq!(actor.round(), Round::Draw);
+        clock.advance(config.action_time);
+        actor.tick();
+        assert_eq!(actor.round(), Round::SecondBetting);
+        assert_eq!(actor.get_timeouts("jane"), 1);
+        assert_eq!(actor.get_timeouts("john"), 0);
+    }
+
+    #[test]
+    fn test_a_dropped_player_is_played_for_then_removed() {
+        let path = std::env::temp_dir().join(format!("poker-accounts-dropped-{}.db", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let mut store = AccountStore::open(&path).unwrap().with_rounds(100);
+        store.register("jane", "correct horse").unwrap();
+        let accounts = Arc::new(Mutex::new(store));
+        let seating = Arc::new(Mutex::new(Seating::default()));
+        let clock = VirtualClock::new();
+        let mut actor = clocked(&clock).with_seating(Arc::clone(&seating)).with_accounts(Arc::clone(&accounts));
+        let (events, _inbox) = connection();
+        actor.sit("jane", 500, events.clone()).unwrap();
+        actor.sit("john", 500, events.clone()).unwrap();
+
+        // the seat is kept, and her turns are played for her at once
+        assert_eq!(disconnect(&mut actor, "jane"), Ok(()));
+        assert_eq!(disconnect(&mut actor, "jack"), Err(LobbyError::NotSeated(1)));
+        assert_eq!(seating.lock().unwrap().get_table("jane"), Some(1));
+        for _ in 0..2 {
+            actor.tick();
+            while actor.round() != Round::Waiting {
+                assert_eq!(to_act(&actor).as_deref(), Some("john"));
+                let action = if actor.default_action(1) == PlayerAction::Check { PlayerAction::Check } else { PlayerAction::Call };
+                act(&mut actor, action);
+            }
+        }
+        assert_eq!(actor.players().len(), 2);
+
+        // past the grace period she is sat out, so no hand starts
+        clock.advance(Duration::from_secs(60));
+        actor.tick();
+        assert!(actor.players()[0].is_sitting_out());
+        assert_eq!(actor.round(), Round::Waiting);
+        assert_eq!(actor.get_next_hand(), None);
+
+        // and later still the seat goes and the chips go back to her account
+        let chips = actor.players()[0].get_total_chips();
+        clock.advance(Duration::from_secs(300));
+        actor.tick();
+        assert_eq!(actor.players().len(), 1);
+        assert_eq!(accounts.lock().unwrap().get_chips("jane"), Some(STARTING_CHIPS + chips));
+        assert_eq!(seating.lock().unwrap().get_table("jane"), None);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_reconnecting_resyncs_and_sits_back_in() {
+        let seating = Arc::new(Mutex::new(Seating::default()));
+        let clock = VirtualClock::new();
+        let mut actor = clocked(&clock).with_seating(Arc::clone(&seating));
+        let (events, _inbox) = connection();
+        actor.sit("jane", 500, events.clone()).unwrap();
+        actor.sit("john", 500, events.clone()).unwrap();
+        disconnect(&mut actor, "jane").unwrap();
+        clock.advance(Duration::from_secs(60));
+        actor.tick();
+        assert!(actor.players()[0].is_sitting_out());
+
+        let (events, inbox) = connection();
+        assert_eq!(reconnect(&mut actor, "jack", events.clone()), Err(LobbyError::NotSeated(1)));
+        assert_eq!(reconnect(&mut actor, "jane", events), Ok(0));
+        assert!(!actor.players()[0].is_sitting_out());
+        assert_eq!(seating.lock().unwrap().get_table("jane"), Some(1));
+        assert!(actor.get_next_hand().is_some());
+        // the whole table comes straight back
+        assert!(inbox.try_iter().any(|message| matches!(message, ServerMessage::TableState { table: 1, .. })));
+    }
+
+    #[test]
+    fn test_an_empty_table_closes() {
+        // a table no one comes to closes once the wait is up
+        let clock = VirtualClock::new();
+        let mut actor = clocked(&clock);
+        actor.tick();
+        clock.advance(Duration::from_secs(60));
+        actor.tick();
+        assert!(actor.is_closing());
+
+        // anyone watching keeps it open, and the wait starts over when they leave
+        let clock = VirtualClock::new();
+        let mut actor = clocked(&clock);
+        actor.tick();
+        let (events, _inbox) = connection();
+        actor.watch("jane", events).unwrap();
+        clock.advance(Duration::from_secs(90));
+        actor.tick();
+        assert!(!actor.is_closing());
+        actor.leave("jane").unwrap();
+        actor.tick();
+        clock.advance(Duration::from_secs(59));
+        actor.tick();
+        assert!(!actor.is_closing());
+        clock.advance(Duration::from_secs(1));
+        assert!(actor.is_closing());
+    }
+}
@@ -0,0 +1,584 @@
+use std::fmt;
+
+use poker_common::action::PlayerAction;
+use poker_common::player::Player;
+
+pub use poker_common::stakes::BettingStructure;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum BettingError {
+    RoundComplete,
+    NotPlayersTurn { seat: usize, current: usize },
+    PlayerNotInHand(usize),
+    CannotCheck { to_call: u32 },
+    NothingToCall,
+    /// There is already a bet this round, so the player has to raise instead.
+    BetAlreadyOpened,
+    /// Nobody has bet yet, so the player has to bet instead.
+    NoBetToRaise,
+    BetTooSmall { min: u32 },
+    RaiseTooSmall { min: u32 },
+    BetTooLarge { max: u32 },
+    RaiseTooLarge { max: u32 },
+    /// Fixed-limit games allow only so many raises per round.
+    RaiseCapReached,
+    /// An all-in for less than a full raise does not reopen the betting for
+    /// players who have already acted.
+    RaiseNotAllowed,
+    InsufficientChips { stack: u32 },
+}
+
+impl fmt::Display for BettingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BettingError::RoundComplete => write!(f, "the betting round is already complete"),
+            BettingError::NotPlayersTurn { seat, current } => {
+                write!(f, "seat {} acted out of turn, seat {} is to act", seat, current)
+            }
+            BettingError::PlayerNotInHand(seat) => write!(f, "seat {} is not in the hand", seat),
+            BettingError::CannotCheck { to_call } => write!(f, "cannot check, {} to call", to_call),
+            BettingError::NothingToCall => write!(f, "there is nothing to call"),
+            BettingError::BetAlreadyOpened => write!(f, "the betting is already open, raise instead"),
+            BettingError::NoBetToRaise => write!(f, "there is no bet to raise, bet instead"),
+            BettingError::BetTooSmall { min } => write!(f, "the minimum bet is {}", min),
+            BettingError::RaiseTooSmall { min } => write!(f, "the minimum raise is to {}", min),
+            BettingError::BetTooLarge { max } => write!(f, "the maximum bet is {}", max),
+            BettingError::RaiseTooLarge { max } => write!(f, "the maximum raise is to {}", max),
+            BettingError::RaiseCapReached => write!(f, "the betting is capped, call or fold"),
+            BettingError::RaiseNotAllowed => write!(f, "the betting has not been reopened, call or fold"),
+            BettingError::InsufficientChips { stack } => write!(f, "only {} chips left", stack),
+        }
+    }
+}
+
+impl std::error::Error for BettingError {}
+
+/// The bet sizing rules for one round of betting.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Limits {
+    pub structure: BettingStructure,
+    /// Smallest opening bet in no-limit and pot-limit games, usually the big blind.
+    pub min_bet: u32,
+    /// Chips already in the middle when the round opens, blinds included.
+    pub pot: u32,
+    /// Whether fixed-limit games use the big bet on this street.
+    pub big_street: bool,
+}
+
+impl Limits {
+    pub fn no_limit(min_bet: u32) -> Self {
+        Limits { structure: BettingStructure::NoLimit, min_bet, pot: 0, big_street: false }
+    }
+
+    fn opening_increment(&self) -> u32 {
+        match self.structure {
+            BettingStructure::NoLimit | BettingStructure::PotLimit => self.min_bet,
+            BettingStructure::FixedLimit { small_bet, big_bet, .. } => {
+                if self.big_street { big_bet } else { small_bet }
+            }
+            BettingStructure::SpreadLimit { min, .. } => min,
+        }
+    }
+}
+
+/// Tracks a single round of betting: whose turn it is, the bet to match and
+/// the size of the smallest legal raise.
+///
+/// Chips are moved into each player's `current_bet` as they act; the caller is
+/// responsible for adding what `apply` returns to the pot.
+pub struct BettingRound {
+    limits: Limits,
+    pot: u32,
+    current_bet: u32,
+    min_raise: u32,
+    raises: u32,
+    to_act: Option<usize>,
+    needs_action: Vec<bool>,
+    can_raise: Vec<bool>,
+}
+
+impl BettingRound {
+    /// Opens a round where `current_bet` must be matched (the big blind before
+    /// the draw, zero after it), with bet sizes governed by `limits`.
+    pub fn new(players: &[Player], first_to_act: usize, current_bet: u32, limits: Limits) -> Self {
+        let mut round = BettingRound {
+            limits,
+            pot: limits.pot,
+            current_bet,
+            min_raise: limits.opening_increment(),
+            // the blinds count as the opening bet
+            raises: u32::from(current_bet > 0),
+            to_act: None,
+            needs_action: players.iter().map(|p| p.is_active() && p.get_total_chips() > 0).collect(),
+            can_raise: vec![true; players.len()],
+        };
+        round.find_next_to_act(players, first_to_act);
+        round
+    }
+
+    /// Opens a stud round where `bring_in_seat` has been forced in for less
+    /// than a full bet. The others may call the bring-in or complete it to a
+    /// full bet, and the completion counts as the opening bet.
+    pub fn with_bring_in(players: &[Player], bring_in_seat: usize, bring_in: u32, limits: Limits) -> Self {
+        let mut round = BettingRound::new(players, bring_in_seat, bring_in, limits);
+        round.raises = 0;
+        round.min_raise = limits.opening_increment().saturating_sub(bring_in).max(1);
+        // bringing in was their action, they only act again if someone completes
+        round.needs_action[bring_in_seat] = false;
+        round.find_next_to_act(players, bring_in_seat + 1);
+        round
+    }
+
+    pub fn current_bet(&self) -> u32 {
+        self.current_bet
+    }
+
+    pub fn to_act(&self) -> Option<usize> {
+        self.to_act
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.to_act.is_none()
+    }
+
+    pub fn amount_to_call(&self, player: &Player) -> u32 {
+        self.current_bet.saturating_sub(player.get_current_bet())
+    }
+
+    /// The smallest total a bet or raise can be made to, short of going all-in.
+    pub fn min_raise_to(&self) -> u32 {
+        self.current_bet + self.min_raise
+    }
+
+    /// The largest total a bet or raise can be made to under the betting
+    /// structure, before taking the player's stack into account.
+    pub fn max_raise_to(&self, player: &Player) -> u32 {
+        match self.limits.structure {
+            BettingStructure::NoLimit => u32::MAX,
+            // call first, then raise by the size of the pot
+            BettingStructure::PotLimit => self.current_bet + self.pot + self.amount_to_call(player),
+            BettingStructure::FixedLimit { .. } => self.min_raise_to(),
+            BettingStructure::SpreadLimit { max, .. } => self.current_bet + max,
+        }
+    }
+
+    /// The range of totals `seat` may legally bet or raise to right now, or
+    /// `None` if they can only check, call or fold. The lower end is below a
+    /// full raise when the player's stack only allows an all-in for less.
+    pub fn raise_range(&self, players: &[Player], seat: usize) -> Option<(u32, u32)> {
+        let player = players.get(seat)?;
+        let all_in = player.get_current_bet() + player.get_total_chips();
+        if !self.can_raise[seat] || self.cap_reached() || all_in <= self.current_bet {
+            return None;
+        }
+        let max = self.max_raise_to(player).min(all_in);
+        let min = self.min_raise_to().min(max);
+        Some((min, max))
+    }
+
+    fn cap_reached(&self) -> bool {
+        match self.limits.structure {
+            BettingStructure::FixedLimit { max_raises, .. } => self.raises > max_raises,
+            _ => false,
+        }
+    }
+
+    /// Validates and applies an action for `seat`, returning the number of
+    /// chips the player put in.
+    pub fn apply(&mut self, players: &mut [Player], seat: usize, action: PlayerAction) -> Result<u32, BettingError> {
+        let current = self.to_act.ok_or(BettingError::RoundComplete)?;
+        if seat >= players.len() || !players[seat].is_active() {
+            return Err(BettingError::PlayerNotInHand(seat));
+        }
+        if seat != current {
+            return Err(BettingError::NotPlayersTurn { seat, current });
+        }
+
+        let chips = self.validate(&players[seat], seat, action)?;
+        if action == PlayerAction::Fold {
+            players[seat].set_active(false);
+        } else {
+            players[seat].place_bet(chips);
+        }
+
+        self.pot += chips;
+        let total = players[seat].get_current_bet();
+        if total > self.current_bet {
+            let raise = total - self.current_bet;
+            if raise >= self.min_raise {
+                self.raises += 1;
+                // fixed-limit raises are always one bet, even after a completed bring-in
+                self.min_raise = match self.limits.structure {
+                    BettingStructure::FixedLimit { .. } => self.limits.opening_increment(),
+                    _ => raise.max(self.limits.opening_increment()),
+                };
+                self.can_raise.iter_mut().for_each(|r| *r = true);
+            } else {
+                // short all-in: whoever has already acted may only call or fold
+                for other in 0..players.len() {
+                    if !self.needs_action[other] {
+                        self.can_raise[other] = false;
+                    }
+                }
+            }
+            self.current_bet = total;
+            for (other, player) in players.iter().enumerate() {
+                self.needs_action[other] = player.is_active() && player.get_total_chips() > 0;
+            }
+        }
+
+        self.needs_action[seat] = false;
+        self.find_next_to_act(players, seat + 1);
+        Ok(chips)
+    }
+
+    fn validate(&self, player: &Player, seat: usize, action: PlayerAction) -> Result<u32, BettingError> {
+        let to_call = self.amount_to_call(player);
+        let stack = player.get_total_chips();
+
+        match action {
+            PlayerAction::Fold => Ok(0),
+            PlayerAction::Check => {
+                if to_call > 0 {
+                    return Err(BettingError::CannotCheck { to_call });
+                }
+                Ok(0)
+            }
+            PlayerAction::Call => {
+                if to_call == 0 {
+                    return Err(BettingError::NothingToCall);
+                }
+                // calling for less than the full amount puts the player all-in
+                Ok(to_call.min(stack))
+            }
+            PlayerAction::Bet(amount) => {
+                if self.current_bet > 0 {
+                    return Err(BettingError::BetAlreadyOpened);
+                }
+                if amount > stack {
+                    return Err(BettingError::InsufficientChips { stack });
+                }
+                if amount < self.min_raise && amount < stack {
+                    return Err(BettingError::BetTooSmall { min: self.min_raise });
+                }
+                let max = self.max_raise_to(player);
+                if amount > max {
+                    return Err(BettingError::BetTooLarge { max });
+                }
+                Ok(amount)
+            }
+            PlayerAction::Raise(raise_to) => {
+                if self.current_bet == 0 {
+                    return Err(BettingError::NoBetToRaise);
+                }
+                self.check_can_raise(seat)?;
+                let chips = raise_to.saturating_sub(player.get_current_bet());
+                if chips > stack {
+                    return Err(BettingError::InsufficientChips { stack });
+                }
+                if raise_to < self.min_raise_to() && chips < stack {
+                    return Err(BettingError::RaiseTooSmall { min: self.min_raise_to() });
+                }
+                let max = self.max_raise_to(player);
+                if raise_to > max {
+                    return Err(BettingError::RaiseTooLarge { max });
+                }
+                Ok(chips)
+            }
+            PlayerAction::AllIn => {
+                if stack == 0 {
+                    return Err(BettingError::InsufficientChips { stack });
+                }
+                if stack > to_call {
+                    self.check_can_raise(seat)?;
+                    let max = self.max_raise_to(player);
+                    if player.get_current_bet() + stack > max {
+                        return Err(BettingError::RaiseTooLarge { max });
+                    }
+                }
+                Ok(stack)
+            }
+        }
+    }
+
+    fn check_can_raise(&self, seat: usize) -> Result<(), BettingError> {
+        if !self.can_raise[seat] {
+            return Err(BettingError::RaiseNotAllowed);
+        }
+        if self.cap_reached() {
+            return Err(BettingError::RaiseCapReached);
+        }
+        Ok(())
+    }
+
+    fn find_next_to_act(&mut self, players: &[Player], from: usize) {
+        self.to_act = None;
+        let count = players.len();
+        if count == 0 || players.iter().filter(|p| p.is_active()).count() < 2 {
+            return;
+        }
+
+        // with nobody left to bet against, a player who has matched the bet is done
+        let can_bet = players.iter().filter(|p| p.is_active() && p.get_total_chips() > 0).count();
+        if can_bet < 2 {
+            for (seat, player) in players.iter().enumerate() {
+                if player.get_current_bet() >= self.current_bet {
+                    self.needs_action[seat] = false;
+                }
+            }
+        }
+
+        self.to_act = (0..count)
+            .map(|offset| (from + offset) % count)
+            .find(|&seat| self.needs_action[seat] && players[seat].is_active());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use poker_common::action::PlayerAction;
+    use poker_common::player::Player;
+
+    fn setup_players(stacks: &[u32]) -> Vec<Player> {
+        stacks.iter().enumerate().map(|(i, &chips)| {
+            let mut player = Player::new(format!("Player {}", i), i as u32, chips);
+            player.set_active(true);
+            player
+        }).collect()
+    }
+
+    #[test]
+    fn test_check_around_completes_round() {
+        let mut players = setup_players(&[100, 100, 100]);
+        let mut round = BettingRound::new(&players, 1, 0, Limits::no_limit(10));
+        assert_eq!(round.to_act(), Some(1));
+
+        round.apply(&mut players, 1, PlayerAction::Check).unwrap();
+        round.apply(&mut players, 2, PlayerAction::Check).unwrap();
+        assert!(!round.is_complete());
+        round.apply(&mut players, 0, PlayerAction::Check).unwrap();
+        assert!(round.is_complete());
+        assert_eq!(round.apply(&mut players, 1, PlayerAction::Check), Err(BettingError::RoundComplete));
+    }
+
+    #[test]
+    fn test_out_of_turn_and_folded_players() {
+        let mut players = setup_players(&[100, 100, 100]);
+        let mut round = BettingRound::new(&players, 0, 0, Limits::no_limit(10));
+        assert_eq!(round.apply(&mut players, 2, PlayerAction::Check), Err(BettingError::NotPlayersTurn { seat: 2, current: 0 }));
+
+        round.apply(&mut players, 0, PlayerAction::Fold).unwrap();
+        assert!(!players[0].is_active());
+        assert_eq!(round.apply(&mut players, 0, PlayerAction::Check), Err(BettingError::PlayerNotInHand(0)));
+        assert_eq!(round.apply(&mut players, 5, PlayerAction::Check), Err(BettingError::PlayerNotInHand(5)));
+    }
+
+    #[test]
+    fn test_bet_call_raise_validation() {
+        let mut players = setup_players(&[100, 100, 100]);
+        let mut round = BettingRound::new(&players, 0, 0, Limits::no_limit(10));
+
+        assert_eq!(round.apply(&mut players, 0, PlayerAction::Call), Err(BettingError::NothingToCall));
+        assert_eq!(round.apply(&mut players, 0, PlayerAction::Raise(20)), Err(BettingError::NoBetToRaise));
+        assert_eq!(round.apply(&mut players, 0, PlayerAction::Bet(5)), Err(BettingError::BetTooSmall { min: 10 }));
+        assert_eq!(round.apply(&mut players, 0, PlayerAction::Bet(500)), Err(BettingError::InsufficientChips { stack: 100 }));
+        assert_eq!(round.apply(&mut players, 0, PlayerAction::Bet(20)), Ok(20));
+
+        assert_eq!(round.apply(&mut players, 1, PlayerAction::Check), Err(BettingError::CannotCheck { to_call: 20 }));
+        assert_eq!(round.apply(&mut players, 1, PlayerAction::Bet(40)), Err(BettingError::BetAlreadyOpened));
+        assert_eq!(round.apply(&mut players, 1, PlayerAction::Raise(30)), Err(BettingError::RaiseTooSmall { min: 40 }));
+        assert_eq!(round.apply(&mut players, 1, PlayerAction::Raise(50)), Ok(50));
+
+        // the last raise was 30, so the next one has to be to at least 80
+        assert_eq!(round.apply(&mut players, 2, PlayerAction::Raise(70)), Err(BettingError::RaiseTooSmall { min: 80 }));
+        assert_eq!(round.apply(&mut players, 2, PlayerAction::Call), Ok(50));
+        assert_eq!(round.apply(&mut players, 0, PlayerAction::Call), Ok(30));
+        assert!(round.is_complete());
+        assert_eq!(players.iter().map(|p| p.get_current_bet()).sum::<u32>(), 150);
+    }
+
+    #[test]
+    fn test_big_blind_gets_option() {
+        let mut players = setup_players(&[100, 100, 100]);
+        players[1].place_bet(5);
+        players[2].place_bet(10);
+        let mut round = BettingRound::new(&players, 0, 10, Limits::no_limit(10));
+
+        round.apply(&mut players, 0, PlayerAction::Call).unwrap();
+        assert_eq!(round.apply(&mut players, 1, PlayerAction::Call), Ok(5));
+        assert_eq!(round.to_act(), Some(2));
+        round.apply(&mut players, 2, PlayerAction::Check).unwrap();
+        assert!(round.is_complete());
+    }
+
+    #[test]
+    fn test_short_call_puts_player_all_in() {
+        let mut players = setup_players(&[100, 30, 100]);
+        let mut round = BettingRound::new(&players, 0, 0, Limits::no_limit(10));
+        round.apply(&mut players, 0, PlayerAction::Bet(50)).unwrap();
+        assert_eq!(round.apply(&mut players, 1, PlayerAction::Call), Ok(30));
+        assert!(players[1].is_all_in());
+        round.apply(&mut players, 2, PlayerAction::Call).unwrap();
+        assert!(round.is_complete());
+    }
+
+    #[test]
+    fn test_short_all_in_does_not_reopen_betting() {
+        let mut players = setup_players(&[100, 25, 100]);
+        let mut round = BettingRound::new(&players, 0, 0, Limits::no_limit(10));
+        round.apply(&mut players, 0, PlayerAction::Bet(20)).unwrap();
+        // raising by 5 is less than a full raise
+        assert_eq!(round.apply(&mut players, 1, PlayerAction::AllIn), Ok(25));
+        // seat 2 has not acted yet and may still raise
+        assert_eq!(round.apply(&mut players, 2, PlayerAction::Call), Ok(25));
+        // seat 0 already acted, so it can only call the extra 5 or fold
+        assert_eq!(round.apply(&mut players, 0, PlayerAction::Raise(60)), Err(BettingError::RaiseNotAllowed));
+        assert_eq!(round.apply(&mut players, 0, PlayerAction::AllIn), Err(BettingError::RaiseNotAllowed));
+        assert_eq!(round.apply(&mut players, 0, PlayerAction::Call), Ok(5));
+        assert!(round.is_complete());
+    }
+
+    #[test]
+    fn test_everyone_all_in_needs_no_action() {
+        let mut players = setup_players(&[100, 100]);
+        let mut round = BettingRound::new(&players, 0, 0, Limits::no_limit(10));
+        round.apply(&mut players, 0, PlayerAction::AllIn).unwrap();
+        round.apply(&mut players, 1, PlayerAction::Call).unwrap();
+        assert!(round.is_complete());
+
+        // a later round with everyone all-in is over before it starts
+        let round = BettingRound::new(&players, 0, 0, Limits::no_limit(10));
+        assert!(round.is_complete());
+    }
+
+    #[test]
+    fn test_fold_to_one_player_completes_round() {
+        let mut players = setup_players(&[100, 100, 100]);
+        let mut round = BettingRound::new(&players, 0, 0, Limits::no_limit(10));
+        round.apply(&mut players, 0, PlayerAction::Bet(10)).unwrap();
+        round.apply(&mut players, 1, PlayerAction::Fold).unwrap();
+        assert!(!round.is_complete());
+        round.apply(&mut players, 2, PlayerAction::Fold).unwrap();
+        assert!(round.is_complete());
+    }
+
+    fn limits(structure: BettingStructure, pot: u32) -> Limits {
+        Limits { structure, min_bet: 10, pot, big_street: false }
+    }
+
+    #[test]
+    fn test_pot_limit_call_then_raise_the_pot() {
+        let mut players = setup_players(&[1000, 1000, 1000]);
+        players[1].place_bet(5);
+        players[2].place_bet(10);
+        let mut round = BettingRound::new(&players, 0, 10, limits(BettingStructure::PotLimit, 15));
+
+        // call 10 making the pot 25, then raise 25 more: 35
+        assert_eq!(round.raise_range(&players, 0), Some((20, 35)));
+        assert_eq!(round.apply(&mut players, 0, PlayerAction::Raise(40)), Err(BettingError::RaiseTooLarge { max: 35 }));
+        assert_eq!(round.apply(&mut players, 0, PlayerAction::AllIn), Err(BettingError::RaiseTooLarge { max: 35 }));
+        round.apply(&mut players, 0, PlayerAction::Raise(35)).unwrap();
+
+        // the small blind calls 30 into a pot of 50 and raises 80: to 115
+        assert_eq!(round.raise_range(&players, 1), Some((60, 115)));
+        round.apply(&mut players, 1, PlayerAction::Raise(115)).unwrap();
+    }
+
+    #[test]
+    fn test_pot_limit_opening_bet() {
+        let mut players = setup_players(&[1000, 1000]);
+        let mut round = BettingRound::new(&players, 0, 0, limits(BettingStructure::PotLimit, 60));
+        assert_eq!(round.raise_range(&players, 0), Some((10, 60)));
+        assert_eq!(round.apply(&mut players, 0, PlayerAction::Bet(70)), Err(BettingError::BetTooLarge { max: 60 }));
+        round.apply(&mut players, 0, PlayerAction::Bet(60)).unwrap();
+    }
+
+    #[test]
+    fn test_fixed_limit_sizes_and_cap() {
+        let structure = BettingStructure::FixedLimit { small_bet: 10, big_bet: 20, max_raises: 3 };
+        let mut players = setup_players(&[1000, 1000]);
+        let mut round = BettingRound::new(&players, 0, 0, Limits { structure, min_bet: 10, pot: 0, big_street: true });
+
+        assert_eq!(round.apply(&mut players, 0, PlayerAction::Bet(10)), Err(BettingError::BetTooSmall { min: 20 }));
+        assert_eq!(round.apply(&mut players, 0, PlayerAction::Bet(40)), Err(BettingError::BetTooLarge { max: 20 }));
+        round.apply(&mut players, 0, PlayerAction::Bet(20)).unwrap();
+        assert_eq!(round.raise_range(&players, 1), Some((40, 40)));
+
+        // a bet and three raises
+        round.apply(&mut players, 1, PlayerAction::Raise(40)).unwrap();
+        round.apply(&mut players, 0, PlayerAction::Raise(60)).unwrap();
+        round.apply(&mut players, 1, PlayerAction::Raise(80)).unwrap();
+        assert_eq!(round.raise_range(&players, 0), None);
+        assert_eq!(round.apply(&mut players, 0, PlayerAction::Raise(100)), Err(BettingError::RaiseCapReached));
+        round.apply(&mut players, 0, PlayerAction::Call).unwrap();
+        assert!(round.is_complete());
+    }
+
+    #[test]
+    fn test_fixed_limit_blinds_count_as_opening_bet() {
+        let structure = BettingStructure::FixedLimit { small_bet: 10, big_bet: 20, max_raises: 1 };
+        let mut players = setup_players(&[1000, 1000, 1000]);
+        players[1].place_bet(5);
+        players[2].place_bet(10);
+        let mut round = BettingRound::new(&players, 0, 10, limits(structure, 15));
+        assert_eq!(round.raise_range(&players, 0), Some((20, 20)));
+        round.apply(&mut players, 0, PlayerAction::Raise(20)).unwrap();
+        assert_eq!(round.apply(&mut players, 1, PlayerAction::Raise(30)), Err(BettingError::RaiseCapReached));
+    }
+
+    #[test]
+    fn test_spread_limit() {
+        let structure = BettingStructure::SpreadLimit { min: 10, max: 50 };
+        let mut players = setup_players(&[1000, 1000, 1000]);
+        let mut round = BettingRound::new(&players, 0, 0, limits(structure, 0));
+        assert_eq!(round.raise_range(&players, 0), Some((10, 50)));
+        assert_eq!(round.apply(&mut players, 0, PlayerAction::Bet(60)), Err(BettingError::BetTooLarge { max: 50 }));
+        round.apply(&mut players, 0, PlayerAction::Bet(30)).unwrap();
+
+        // raises must match the last raise and stay under the spread maximum
+        assert_eq!(round.raise_range(&players, 1), Some((60, 80)));
+        assert_eq!(round.apply(&mut players, 1, PlayerAction::Raise(50)), Err(BettingError::RaiseTooSmall { min: 60 }));
+        assert_eq!(round.apply(&mut players, 1, PlayerAction::Raise(90)), Err(BettingError::RaiseTooLarge { max: 80 }));
+        round.apply(&mut players, 1, PlayerAction::Raise(80)).unwrap();
+    }
+
+    #[test]
+    fn test_raise_range_limited_by_stack() {
+        let mut players = setup_players(&[1000, 15]);
+        let mut round = BettingRound::new(&players, 0, 0, Limits::no_limit(10));
+        round.apply(&mut players, 0, PlayerAction::Bet(10)).unwrap();
+        // only an all-in for less than a full raise is left
+        assert_eq!(round.raise_range(&players, 1), Some((15, 15)));
+        round.apply(&mut players, 1, PlayerAction::AllIn).unwrap();
+        assert_eq!(round.raise_range(&players, 0), None);
+    }
+
+    #[test]
+    fn test_bring_in_can_be_called_or_completed() {
+        let structure = BettingStructure::FixedLimit { small_bet: 10, big_bet: 20, max_raises: 3 };
+        let mut players = setup_players(&[1000, 1000, 1000]);
+        players[1].place_bet(3);
+        let mut round = BettingRound::with_bring_in(&players, 1, 3, limits(structure, 3));
+        assert_eq!(round.to_act(), Some(2));
+
+        // completing to a full bet is the opening bet, the next raise is a full bet more
+        assert_eq!(round.raise_range(&players, 2), Some((10, 10)));
+        round.apply(&mut players, 2, PlayerAction::Raise(10)).unwrap();
+        assert_eq!(round.raise_range(&players, 0), Some((20, 20)));
+        round.apply(&mut players, 0, PlayerAction::Call).unwrap();
+        assert_eq!(round.to_act(), Some(1));
+        assert_eq!(round.amount_to_call(&players[1]), 7);
+    }
+
+    #[test]
+    fn test_bring_in_called_around_ends_the_round() {
+        let structure = BettingStructure::FixedLimit { small_bet: 10, big_bet: 20, max_raises: 3 };
+        let mut players = setup_players(&[1000, 1000, 1000]);
+        players[1].place_bet(3);
+        let mut round = BettingRound::with_bring_in(&players, 1, 3, limits(structure, 3));
+        round.apply(&mut players, 2, PlayerAction::Call).unwrap();
+        round.apply(&mut players, 0, PlayerAction::Call).unwrap();
+        assert!(round.is_complete());
+    }
+}
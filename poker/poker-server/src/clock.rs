@@ -0,0 +1,151 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Where a table gets the time from. Times are measured from the clock's
+/// own start, so a test clock can begin at zero and be moved by hand.
+pub trait Clock: Send {
+    fn now(&self) -> Duration;
+}
+
+/// The real time, since the clock was made.
+pub struct SystemClock {
+    start: Instant,
+}
+
+impl SystemClock {
+    pub fn new() -> SystemClock {
+        SystemClock { start: Instant::now() }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+/// A clock that only moves when told to. Clones share the same time, so a
+/// test can keep one and hand the other to a table.
+#[derive(Clone, Default)]
+pub struct VirtualClock {
+    now: Arc<Mutex<Duration>>,
+}
+
+impl VirtualClock {
+    pub fn new() -> VirtualClock {
+        VirtualClock::default()
+    }
+
+    pub fn advance(&self, by: Duration) {
+        *self.now.lock().unwrap() += by;
+    }
+}
+
+impl Clock for VirtualClock {
+    fn now(&self) -> Duration {
+        *self.now.lock().unwrap()
+    }
+}
+
+/// How long players get to act at a table, how long a player whose
+/// connection dropped keeps their seat, and how long an empty table stays open.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ClockConfig {
+    /// The time for each decision before the time bank is touched.
+    pub action_time: Duration,
+    /// How long before the time runs out the player is warned.
+    pub warning: Duration,
+    /// What each player's time bank starts with, and the most it holds.
+    pub time_bank: Duration,
+    /// Added to every time bank each `refill_every`.
+    pub refill: Duration,
+    pub refill_every: Duration,
+    /// Timeouts in a row before the player is sat out.
+    pub max_timeouts: u32,
+    /// How long a disconnected player has to come back before being sat out.
+    pub reconnect_grace: Duration,
+    /// How long they then sit out before the seat goes and the chips go
+    /// back to their account.
+    pub removal: Duration,
+    /// How long a table stays open with no one seated or watching.
+    pub close_empty: Duration,
+}
+
+impl Default for ClockConfig {
+    fn default() -> Self {
+        ClockConfig {
+            action_time: Duration::from_secs(20),
+            warning: Duration::from_secs(5),
+            time_bank: Duration::from_secs(60),
+            refill: Duration::from_secs(10),
+            refill_every: Duration::from_secs(5 * 60),
+            max_timeouts: 2,
+            reconnect_grace: Duration::from_secs(60),
+            removal: Duration::from_secs(5 * 60),
+            close_empty: Duration::from_secs(60),
+        }
+    }
+}
+
+/// Extra thinking time a player can draw on once the action time runs out.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TimeBank {
+    remaining: Duration,
+    refilled_at: Duration,
+}
+
+impl TimeBank {
+    /// A full bank, refilling on the schedule from `now`.
+    pub fn new(config: &ClockConfig, now: Duration) -> TimeBank {
+        TimeBank { remaining: config.time_bank, refilled_at: now }
+    }
+
+    /// What is left at `now`, counting every refill due since the last look.
+    pub fn remaining(&mut self, config: &ClockConfig, now: Duration) -> Duration {
+        if config.refill_every > Duration::ZERO {
+            while now >= self.refilled_at + config.refill_every {
+                self.refilled_at += config.refill_every;
+                self.remaining = (self.remaining + config.refill).min(config.time_bank);
+            }
+        }
+        self.remaining
+    }
+
+    pub fn spend(&mut self, used: Duration) {
+        self.remaining = self.remaining.saturating_sub(used);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::clock::{Clock, ClockConfig, TimeBank, VirtualClock};
+
+    #[test]
+    fn test_virtual_clock_is_shared_by_clones() {
+        let clock = VirtualClock::new();
+        let table = clock.clone();
+        assert_eq!(table.now(), Duration::ZERO);
+        clock.advance(Duration::from_secs(3));
+        assert_eq!(table.now(), Duration::from_secs(3));
+    }
+
+    #[test]
+    fn test_time_bank_refills_up_to_its_size() {
+        let config = ClockConfig::default();
+        let mut bank = TimeBank::new(&config, Duration::ZERO);
+        bank.spend(Duration::from_secs(45));
+        assert_eq!(bank.remaining(&config, Duration::from_secs(299)), Duration::from_secs(15));
+        assert_eq!(bank.remaining(&config, Duration::from_secs(300)), Duration::from_secs(25));
+        assert_eq!(bank.remaining(&config, Duration::from_secs(1500)), Duration::from_secs(60));
+        bank.spend(Duration::from_secs(90));
+        assert_eq!(bank.remaining(&config, Duration::from_secs(1500)), Duration::ZERO);
+    }
+}
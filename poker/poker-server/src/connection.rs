@@ -0,0 +1,666 @@
+use std::fmt;
+use std::sync::mpsc::SyncSender;
+
+use poker_common::protocol::{ClientMessage, ServerMessage};
+
+use crate::accounts::{self, AccountError};
+use crate::actor::TableHandle;
+use crate::dealer::TableConfig;
+use crate::lobby::LobbyError;
+use crate::server::{self, Server};
+
+/// Messages waiting to be written to one client. Past this the tables
+/// and the lobby stop queueing updates for it until it catches up.
+pub const OUTBOX_CAPACITY: usize = 256;
+
+/// Where a connection is in logging in and finding a game. Each state only
+/// accepts the messages that make sense in it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConnectionState {
+    /// Waiting for a username, or a session token to resume.
+    AwaitingUsername,
+    /// Waiting for the password that registers or logs in to `username`.
+    AwaitingPassword { username: String, new_user: bool },
+    /// Logged in, before the first look at the lobby.
+    Authenticated { username: String },
+    InLobby { username: String },
+    /// At `table`, either seated or watching.
+    AtTable { username: String, table: u32, seated: bool },
+    /// The client quit; nothing more is read.
+    Closed,
+}
+
+impl fmt::Display for ConnectionState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConnectionState::AwaitingUsername => write!(f, "waiting for a username"),
+            ConnectionState::AwaitingPassword { username, .. } => write!(f, "waiting for the password for {}", username),
+            ConnectionState::Authenticated { .. } | ConnectionState::InLobby { .. } => write!(f, "in the lobby"),
+            ConnectionState::AtTable { table, seated: true, .. } => write!(f, "seated at table {}", table),
+            ConnectionState::AtTable { table, seated: false, .. } => write!(f, "watching table {}", table),
+            ConnectionState::Closed => write!(f, "closed"),
+        }
+    }
+}
+
+/// One client's login session, driven by the messages it sends. Replies go
+/// back to the caller to write, so the state machine never touches a socket;
+/// lobby and table updates pushed while logged in go to `outbox`. Anything
+/// that must reach the client before a table's state goes to `outbox` as
+/// well: the table sends `Seated` or `Watching` itself, and a login that
+/// takes a seat back queues `LoggedIn` before the table is asked.
+pub struct Connection<'a> {
+    server: &'a Server,
+    state: ConnectionState,
+    token: Option<String>,
+    // this connection's id in the server's live sessions, once logged in
+    session: Option<u64>,
+    outbox: SyncSender<ServerMessage>,
+    subscription: Option<u64>,
+}
+
+impl<'a> Connection<'a> {
+    pub fn new(server: &'a Server, outbox: SyncSender<ServerMessage>) -> Connection<'a> {
+        Connection { server, state: ConnectionState::AwaitingUsername, token: None, session: None, outbox, subscription: None }
+    }
+
+    pub fn get_state(&self) -> &ConnectionState {
+        &self.state
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.state == ConnectionState::Closed
+    }
+
+    /// Handles one message from the client and returns the replies. Messages
+    /// that don't fit the current state are answered with an error and leave
+    /// it unchanged.
+    pub fn handle(&mut self, message: ClientMessage) -> Vec<ServerMessage> {
+        if self.is_superseded() {
+            self.close(false);
+            return vec![ServerMessage::Error("logged in from another connection".to_owned()), ServerMessage::Goodbye];
+        }
+        if message == ClientMessage::Quit {
+            return self.quit();
+        }
+        let state = std::mem::replace(&mut self.state, ConnectionState::Closed);
+        let (state, replies) = match (state, message) {
+            // a new username while waiting for the password starts the login over
+            (ConnectionState::AwaitingUsername | ConnectionState::AwaitingPassword { .. }, ClientMessage::Username(username)) => {
+                self.username(username)
+            }
+            (ConnectionState::AwaitingUsername, ClientMessage::Resume { token }) => self.resume(token),
+            (ConnectionState::AwaitingPassword { username, new_user }, ClientMessage::Password(password)) => {
+                self.password(username, new_user, password)
+            }
+            (ConnectionState::Authenticated { username }, message) => self.lobby(username, false, message),
+            (ConnectionState::InLobby { username }, message) => self.lobby(username, true, message),
+            (ConnectionState::AtTable { username, table, seated }, message) => self.at_table(username, table, seated, message),
+            (state, message) => {
+                let error = format!("unexpected {} while {}", message_name(&message), state);
+                (state, vec![ServerMessage::Error(error)])
+            }
+        };
+        self.state = state;
+        replies
+    }
+
+    fn username(&mut self, username: String) -> (ConnectionState, Vec<ServerMessage>) {
+        if !accounts::is_valid_username(&username) {
+            return (ConnectionState::AwaitingUsername, vec![ServerMessage::LoginFailed(AccountError::InvalidUsername.to_string())]);
+        }
+        let new_user = !self.server.accounts().exists(&username);
+        let reply = if new_user { ServerMessage::NewUser } else { ServerMessage::ReturningUser };
+        (ConnectionState::AwaitingPassword { username, new_user }, vec![reply])
+    }
+
+    fn password(&mut self, username: String, new_user: bool, password: String) -> (ConnectionState, Vec<ServerMessage>) {
+        // the accounts are shared by every connection and table, so the
+        // password is hashed with them unlocked
+        let now = server::now();
+        let salt = if new_user {
+            self.server.accounts().check_new(&username, &password)
+        } else {
+            self.server.accounts().login_salt(&username, now)
+        };
+        let result = salt.and_then(|salt| {
+            let hashed = salt.hash(&password);
+            if new_user {
+                self.server.accounts().register_hashed(&username, &hashed)
+            } else {
+                self.server.accounts().login_hashed(&username, &hashed, now)
+            }
+        });
+        match result {
+            Ok(chips) => self.logged_in(username, chips),
+            // start over, since this account can't be logged in to or made
+            // for now
+            Err(err @ (AccountError::LockedOut { .. } | AccountError::UsernameTaken | AccountError::NoSuchUser)) => {
+                (ConnectionState::AwaitingUsername, vec![ServerMessage::LoginFailed(err.to_string())])
+            }
+            Err(err) => (ConnectionState::AwaitingPassword { username, new_user }, vec![ServerMessage::LoginFailed(err.to_string())]),
+        }
+    }
+
+    fn resume(&mut self, token: String) -> (ConnectionState, Vec<ServerMessage>) {
+        let username = self.server.tokens().resume(&token, server::now()).map(str::to_owned);
+        let chips = username.as_ref().and_then(|username| self.server.accounts().get_chips(username));
+        match (username, chips) {
+            (Some(username), Some(chips)) => self.welcome(username, chips, token),
+            _ => (ConnectionState::AwaitingUsername, vec![ServerMessage::LoginFailed("session expired, log in again".to_owned())]),
+        }
+    }
+
+    fn logged_in(&mut self, username: String, chips: u32) -> (ConnectionState, Vec<ServerMessage>) {
+        match self.server.tokens().issue(&username, server::now()) {
+            Ok(token) => self.welcome(username, chips, token),
+            Err(err) => (ConnectionState::AwaitingUsername, vec![ServerMessage::LoginFailed(err.to_string())]),
+        }
+    }
+
+    // Starts the session, taking the account over from any other connection,
+    // and puts the player back in their seat if they have one.
+    fn welcome(&mut self, username: String, chips: u32, token: String) -> (ConnectionState, Vec<ServerMessage>) {
+        self.token = Some(token.clone());
+        self.session = Some(self.server.sessions().take_over(&username, self.outbox.clone()));
+        self.subscribe();
+        let logged_in = ServerMessage::LoggedIn { chips, token };
+        let seated = self.server.lobby().seated_table(&username);
+        let Some(table) = seated else {
+            return (ConnectionState::Authenticated { username }, vec![logged_in]);
+        };
+        // the table sends `Seated` and the whole of its state once the seat
+        // is back, so the login has to be queued first
+        let _ = self.outbox.send(logged_in);
+        match self.table(table).and_then(|handle| handle.reconnect(&username, self.outbox.clone())) {
+            Ok(_) => (ConnectionState::AtTable { username, table, seated: true }, Vec::new()),
+            Err(_) => (ConnectionState::Authenticated { username }, Vec::new()),
+        }
+    }
+
+    fn lobby(&mut self, username: String, in_lobby: bool, message: ClientMessage) -> (ConnectionState, Vec<ServerMessage>) {
+        let reply = match message {
+            ClientMessage::ListTables => {
+                return (ConnectionState::InLobby { username }, vec![ServerMessage::Tables(self.server.lobby().tables())]);
+            }
+            ClientMessage::CreateTable { variant, stakes, structure, max_seats, trips_beat_straight } => {
+                let config = TableConfig { stakes, structure, trips_beat_straight, ..TableConfig::default() };
+                let created = self.server.lobby().create_table(variant, config, max_seats as usize);
+                match created {
+                    Ok(table) => return (ConnectionState::InLobby { username }, vec![ServerMessage::TableCreated { table }]),
+                    Err(err) => ServerMessage::Error(err.to_string()),
+                }
+            }
+            // the table confirms these itself, ahead of its state
+            ClientMessage::JoinTable { table, buy_in } => match self.sit(&username, table, buy_in) {
+                Ok(_) => return (ConnectionState::AtTable { username, table, seated: true }, Vec::new()),
+                Err(error) => ServerMessage::Error(error),
+            },
+            ClientMessage::Watch { table } => {
+                let watching = self.table(table).and_then(|handle| handle.watch(&username, self.outbox.clone()));
+                match watching {
+                    Ok(()) => return (ConnectionState::AtTable { username, table, seated: false }, Vec::new()),
+                    Err(err) => ServerMessage::Error(err.to_string()),
+                }
+            }
+            message => ServerMessage::Error(format!("unexpected {} while in the lobby", message_name(&message))),
+        };
+        let state = if in_lobby { ConnectionState::InLobby { username } } else { ConnectionState::Authenticated { username } };
+        (state, vec![reply])
+    }
+
+    fn at_table(&mut self, username: String, table: u32, seated: bool, message: ClientMessage) -> (ConnectionState, Vec<ServerMessage>) {
+        let reply = match message {
+            ClientMessage::ListTables => ServerMessage::Tables(self.server.lobby().tables()),
+            ClientMessage::JoinTable { table: joining, buy_in } if joining == table && !seated => {
+                match self.sit(&username, table, buy_in) {
+                    Ok(_) => return (ConnectionState::AtTable { username, table, seated: true }, Vec::new()),
+                    Err(error) => ServerMessage::Error(error),
+                }
+            }
+            ClientMessage::StandUp { table: standing } if standing == table && seated => {
+                let stood = self.table(table).and_then(|handle| handle.stand(&username));
+                match stood.map_err(|err| err.to_string()).and_then(|chips| self.cash_out(&username, chips)) {
+                    Ok(chips) => {
+                        return (ConnectionState::AtTable { username, table, seated: false }, vec![ServerMessage::StoodUp { table, chips }]);
+                    }
+                    Err(error) => ServerMessage::Error(error),
+                }
+            }
+            ClientMessage::LeaveTable { table: leaving } if leaving == table => match self.leave_table(&username, table) {
+                Ok(()) => return (ConnectionState::InLobby { username }, vec![ServerMessage::Left { table }]),
+                Err(error) => ServerMessage::Error(error),
+            },
+            // the table answers these itself, errors included
+            ClientMessage::Action { table: acting, action } if acting == table && seated => {
+                match self.table(table).and_then(|handle| handle.act(&username, action)) {
+                    Ok(()) => return (ConnectionState::AtTable { username, table, seated }, Vec::new()),
+                    Err(err) => ServerMessage::Error(err.to_string()),
+                }
+            }
+            ClientMessage::Draw { table: drawing, discards } if drawing == table && seated => {
+                match self.table(table).and_then(|handle| handle.draw(&username, discards)) {
+                    Ok(()) => return (ConnectionState::AtTable { username, table, seated }, Vec::new()),
+                    Err(err) => ServerMessage::Error(err.to_string()),
+                }
+            }
+            ClientMessage::SitOut { table: sitting } | ClientMessage::SitIn { table: sitting } if sitting == table && seated => {
+                let sitting_out = matches!(message, ClientMessage::SitOut { .. });
+                match self.table(table).and_then(|handle| handle.sit_out(&username, sitting_out)) {
+                    Ok(()) => return (ConnectionState::AtTable { username, table, seated }, Vec::new()),
+                    Err(err) => ServerMessage::Error(err.to_string()),
+                }
+            }
+            ClientMessage::Chat { table: chatting, text } if chatting == table => {
+                match self.table(table).and_then(|handle| handle.chat(&username, text)) {
+                    Ok(()) => return (ConnectionState::AtTable { username, table, seated }, Vec::new()),
+                    Err(err) => ServerMessage::Error(err.to_string()),
+                }
+            }
+            message => ServerMessage::Error(format!("unexpected {} while at table {}", message_name(&message), table)),
+        };
+        (ConnectionState::AtTable { username, table, seated }, vec![reply])
+    }
+
+    // Takes the buy-in from the account and seats the player, putting the
+    // chips back if the seat can't be had.
+    fn sit(&self, username: &str, table: u32, buy_in: u32) -> Result<(), String> {
+        let handle = self.table(table).map_err(|err| err.to_string())?;
+        self.server.accounts().withdraw(username, buy_in).map_err(|err| err.to_string())?;
+        let seated = handle.sit(username, buy_in, self.outbox.clone());
+        seated.map(drop).map_err(|err| {
+            // the account was loaded at login, so this only fails on a write error
+            let _ = self.server.accounts().deposit(username, buy_in);
+            err.to_string()
+        })
+    }
+
+    // The lobby is only locked long enough to look the table up, never
+    // while waiting on the table itself.
+    fn table(&self, table: u32) -> Result<TableHandle, LobbyError> {
+        self.server.lobby().table(table)
+    }
+
+    fn cash_out(&self, username: &str, chips: u32) -> Result<u32, String> {
+        self.server.accounts().deposit(username, chips).map_err(|err| err.to_string())?;
+        Ok(chips)
+    }
+
+    fn leave_table(&self, username: &str, table: u32) -> Result<(), String> {
+        let left = self.table(table).and_then(|handle| handle.leave(username));
+        if let Some(chips) = left.map_err(|err| err.to_string())? {
+            self.cash_out(username, chips)?;
+        }
+        Ok(())
+    }
+
+    fn username_of(&self) -> Option<&str> {
+        match &self.state {
+            ConnectionState::Authenticated { username } | ConnectionState::InLobby { username } => Some(username),
+            ConnectionState::AtTable { username, .. } => Some(username),
+            _ => None,
+        }
+    }
+
+    // Whether the account has since been logged in to from another connection,
+    // which now owns its seat and token.
+    fn is_superseded(&self) -> bool {
+        match (self.username_of(), self.session) {
+            (Some(username), Some(id)) => !self.server.sessions().is_live(username, id),
+            _ => false,
+        }
+    }
+
+    // Stops the lobby updates. A player quitting leaves their table with
+    // their chips if they can; one whose connection dropped, or who is in a
+    // hand, keeps the seat for a while in case they come back. A connection
+    // that was taken over leaves the table to the one that took it.
+    fn close(&mut self, quitting: bool) {
+        if let Some(id) = self.subscription.take() {
+            self.server.lobby().unsubscribe(id);
+        }
+        if self.is_superseded() {
+            self.token = None;
+            self.state = ConnectionState::Closed;
+            return;
+        }
+        let session = self.session.take();
+        if let (Some(username), Some(id)) = (self.username_of(), session) {
+            self.server.sessions().end(username, id);
+        }
+        if let ConnectionState::AtTable { username, table, seated } = &self.state {
+            // nothing more can be told to the client, so errors go no further
+            let left = (!*seated || quitting) && self.leave_table(username, *table).is_ok();
+            if *seated && !left {
+                let _ = self.table(*table).and_then(|handle| handle.disconnect(username));
+            }
+        }
+        self.state = ConnectionState::Closed;
+    }
+
+    // Ends the session for good, so the token can no longer resume it.
+    fn quit(&mut self) -> Vec<ServerMessage> {
+        if let Some(token) = self.token.take() {
+            self.server.tokens().revoke(&token);
+        }
+        self.close(true);
+        vec![ServerMessage::Goodbye]
+    }
+
+    fn subscribe(&mut self) {
+        if self.subscription.is_none() {
+            self.subscription = Some(self.server.lobby().subscribe(self.outbox.clone()));
+        }
+    }
+}
+
+/// A dropped connection keeps its seat for a while, and its token can still
+/// resume the session.
+impl Drop for Connection<'_> {
+    fn drop(&mut self) {
+        if !self.is_closed() {
+            self.close(false);
+        }
+    }
+}
+
+// What to call a message in an error, without echoing its contents back.
+fn message_name(message: &ClientMessage) -> &'static str {
+    match message {
+        ClientMessage::Hello { .. } => "hello",
+        ClientMessage::Username(_) => "username",
+        ClientMessage::Password(_) => "password",
+        ClientMessage::Resume { .. } => "session token",
+        ClientMessage::ListTables => "table list request",
+        ClientMessage::CreateTable { .. } => "new table",
+        ClientMessage::JoinTable { .. } => "join",
+        ClientMessage::StandUp { .. } => "stand up",
+        ClientMessage::Watch { .. } => "watch",
+        ClientMessage::LeaveTable { .. } => "leave",
+        ClientMessage::SitOut { .. } => "sit out",
+        ClientMessage::SitIn { .. } => "sit in",
+        ClientMessage::Action { .. } => "action",
+        ClientMessage::Draw { .. } => "draw",
+        ClientMessage::Chat { .. } => "chat",
+        ClientMessage::Quit => "quit",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+    use std::sync::mpsc::{self, Receiver, SyncSender};
+
+    use poker_common::action::PlayerAction;
+    use poker_common::protocol::{ClientMessage, ServerMessage};
+    use poker_common::stakes::{BettingStructure, Stakes};
+    use poker_common::variant::Variant;
+
+    use crate::accounts::{AccountStore, MAX_FAILED_LOGINS, STARTING_CHIPS};
+    use crate::connection::{Connection, ConnectionState, OUTBOX_CAPACITY};
+    use crate::server::Server;
+
+    fn server(name: &str) -> (Server, PathBuf) {
+        let path = std::env::temp_dir().join(format!("poker-connection-{}-{}.db", name, std::process::id()));
+        let _ = fs::remove_file(&path);
+        (Server::new(AccountStore::open(&path).unwrap().with_rounds(100)), path)
+    }
+
+    // An outbox nobody reads, for tests that don't look at pushed updates.
+    fn outbox() -> SyncSender<ServerMessage> {
+        mpsc::sync_channel(OUTBOX_CAPACITY).0
+    }
+
+    fn create_table() -> ClientMessage {
+        ClientMessage::CreateTable { variant: Variant::TexasHoldem, stakes: Stakes::default(), structure: BettingStructure::NoLimit, max_seats: 6, trips_beat_straight: true }
+    }
+
+    fn log_in(connection: &mut Connection, username: &str, password: &str) -> String {
+        connection.handle(ClientMessage::Username(username.to_owned()));
+        match connection.handle(ClientMessage::Password(password.to_owned())).as_slice() {
+            [ServerMessage::LoggedIn { token, .. }] => token.clone(),
+            replies => panic!("login failed: {:?}", replies),
+        }
+    }
+
+    // What reached the outbox so far, leaving out lobby updates.
+    fn from_tables(inbox: &Receiver<ServerMessage>) -> Vec<ServerMessage> {
+        inbox.try_iter().filter(|message| !matches!(message, ServerMessage::TableUpdated(_))).collect()
+    }
+
+    fn is_error(replies: &[ServerMessage]) -> bool {
+        matches!(replies, [ServerMessage::Error(_)] | [ServerMessage::LoginFailed(_)])
+    }
+
+    #[test]
+    fn test_register_then_log_in() {
+        let (server, path) = server("register");
+        let mut connection = Connection::new(&server, outbox());
+        assert_eq!(connection.handle(ClientMessage::Username("jane".to_owned())), vec![ServerMessage::NewUser]);
+        assert_eq!(connection.get_state(), &ConnectionState::AwaitingPassword { username: "jane".to_owned(), new_user: true });
+
+        // too short to register, so the password can be tried again
+        assert!(is_error(&connection.handle(ClientMessage::Password("short".to_owned()))));
+        let replies = connection.handle(ClientMessage::Password("correct horse".to_owned()));
+        assert!(matches!(replies.as_slice(), [ServerMessage::LoggedIn { chips: STARTING_CHIPS, .. }]));
+        assert_eq!(connection.get_state(), &ConnectionState::Authenticated { username: "jane".to_owned() });
+
+        let mut returning = Connection::new(&server, outbox());
+        assert_eq!(returning.handle(ClientMessage::Username("jane".to_owned())), vec![ServerMessage::ReturningUser]);
+        assert!(is_error(&returning.handle(ClientMessage::Password("wrong horse".to_owned()))));
+        log_in(&mut Connection::new(&server, outbox()), "jane", "correct horse");
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_messages_out_of_order_are_rejected() {
+        let (server, path) = server("order");
+        let mut connection = Connection::new(&server, outbox());
+        assert!(is_error(&connection.handle(ClientMessage::Password("correct horse".to_owned()))));
+        assert!(is_error(&connection.handle(ClientMessage::ListTables)));
+        assert!(is_error(&connection.handle(ClientMessage::Username(String::new()))));
+        assert!(is_error(&connection.handle(ClientMessage::Username("two words".to_owned()))));
+        assert_eq!(connection.get_state(), &ConnectionState::AwaitingUsername);
+
+        log_in(&mut connection, "jane", "correct horse");
+        assert!(is_error(&connection.handle(ClientMessage::Username("john".to_owned()))));
+        assert!(is_error(&connection.handle(ClientMessage::LeaveTable { table: 1 })));
+        assert_eq!(connection.get_state(), &ConnectionState::Authenticated { username: "jane".to_owned() });
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_lockout_restarts_the_login() {
+        let (server, path) = server("lockout");
+        log_in(&mut Connection::new(&server, outbox()), "jane", "correct horse");
+        let mut connection = Connection::new(&server, outbox());
+        connection.handle(ClientMessage::Username("jane".to_owned()));
+        for _ in 1..MAX_FAILED_LOGINS {
+            connection.handle(ClientMessage::Password("guess".to_owned()));
+            assert!(matches!(connection.get_state(), ConnectionState::AwaitingPassword { .. }));
+        }
+        connection.handle(ClientMessage::Password("guess".to_owned()));
+        assert_eq!(connection.get_state(), &ConnectionState::AwaitingUsername);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_a_taken_username_restarts_the_login() {
+        let (server, path) = server("taken");
+        let mut first = Connection::new(&server, outbox());
+        let mut second = Connection::new(&server, outbox());
+        assert_eq!(first.handle(ClientMessage::Username("jane".to_owned())), vec![ServerMessage::NewUser]);
+        assert_eq!(second.handle(ClientMessage::Username("jane".to_owned())), vec![ServerMessage::NewUser]);
+        first.handle(ClientMessage::Password("correct horse".to_owned()));
+
+        // jane was taken in the meantime, so the second picks another name
+        assert!(is_error(&second.handle(ClientMessage::Password("battery staple".to_owned()))));
+        assert_eq!(second.get_state(), &ConnectionState::AwaitingUsername);
+        log_in(&mut second, "john", "battery staple");
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_a_new_username_restarts_the_login() {
+        let (server, path) = server("retype");
+        let mut connection = Connection::new(&server, outbox());
+        // a mistyped name looks like a new user, so the client starts over
+        assert_eq!(connection.handle(ClientMessage::Username("jnae".to_owned())), vec![ServerMessage::NewUser]);
+        assert_eq!(connection.handle(ClientMessage::Username("jane".to_owned())), vec![ServerMessage::NewUser]);
+        assert_eq!(connection.get_state(), &ConnectionState::AwaitingPassword { username: "jane".to_owned(), new_user: true });
+        connection.handle(ClientMessage::Password("correct horse".to_owned()));
+        assert!(server.accounts().exists("jane"));
+        assert!(!server.accounts().exists("jnae"));
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_lobby_and_table() {
+        let (server, path) = server("lobby");
+        let (outbox, inbox) = mpsc::sync_channel(OUTBOX_CAPACITY);
+        let mut connection = Connection::new(&server, outbox);
+        log_in(&mut connection, "jane", "correct horse");
+        assert_eq!(connection.handle(ClientMessage::ListTables), vec![ServerMessage::Tables(Vec::new())]);
+        assert_eq!(connection.get_state(), &ConnectionState::InLobby { username: "jane".to_owned() });
+        assert_eq!(connection.handle(create_table()), vec![ServerMessage::TableCreated { table: 1 }]);
+
+        assert!(is_error(&connection.handle(ClientMessage::JoinTable { table: 2, buy_in: 100 })));
+        assert!(is_error(&connection.handle(ClientMessage::JoinTable { table: 1, buy_in: STARTING_CHIPS + 1 })));
+        assert_eq!(server.accounts().get_chips("jane"), Some(STARTING_CHIPS));
+        inbox.try_iter().for_each(drop);
+        // the table says where she sat before showing her the table
+        assert_eq!(connection.handle(ClientMessage::JoinTable { table: 1, buy_in: 400 }), Vec::new());
+        assert!(matches!(from_tables(&inbox).as_slice(), [ServerMessage::Seated { table: 1, seat: 0 }, ServerMessage::TableState { table: 1, .. }]));
+        assert_eq!(server.accounts().get_chips("jane"), Some(STARTING_CHIPS - 400));
+        assert!(is_error(&connection.handle(ClientMessage::JoinTable { table: 1, buy_in: 100 })));
+
+        // standing up cashes out but keeps watching
+        assert_eq!(connection.handle(ClientMessage::StandUp { table: 1 }), vec![ServerMessage::StoodUp { table: 1, chips: 400 }]);
+        assert_eq!(server.accounts().get_chips("jane"), Some(STARTING_CHIPS));
+        assert_eq!(connection.get_state(), &ConnectionState::AtTable { username: "jane".to_owned(), table: 1, seated: false });
+        assert_eq!(server.lobby().summary(1).unwrap().players, 0);
+        assert!(is_error(&connection.handle(ClientMessage::StandUp { table: 1 })));
+
+        inbox.try_iter().for_each(drop);
+        assert_eq!(connection.handle(ClientMessage::JoinTable { table: 1, buy_in: 300 }), Vec::new());
+        assert!(matches!(from_tables(&inbox).first(), Some(ServerMessage::Seated { table: 1, seat: 0 })));
+        assert_eq!(connection.handle(ClientMessage::LeaveTable { table: 1 }), vec![ServerMessage::Left { table: 1 }]);
+        assert_eq!(server.accounts().get_chips("jane"), Some(STARTING_CHIPS));
+        assert_eq!(connection.get_state(), &ConnectionState::InLobby { username: "jane".to_owned() });
+
+        inbox.try_iter().for_each(drop);
+        assert_eq!(connection.handle(ClientMessage::Watch { table: 1 }), Vec::new());
+        assert!(matches!(from_tables(&inbox).as_slice(), [ServerMessage::Watching { table: 1 }, ServerMessage::TableState { table: 1, .. }]));
+        assert_eq!(connection.get_state(), &ConnectionState::AtTable { username: "jane".to_owned(), table: 1, seated: false });
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_lobby_updates_are_pushed() {
+        let (server, path) = server("updates");
+        let (outbox, inbox) = mpsc::sync_channel(OUTBOX_CAPACITY);
+        let mut watcher = Connection::new(&server, outbox);
+        log_in(&mut watcher, "jane", "correct horse");
+        let mut player = Connection::new(&server, self::outbox());
+        log_in(&mut player, "john", "battery staple");
+
+        player.handle(create_table());
+        player.handle(ClientMessage::JoinTable { table: 1, buy_in: 500 });
+        let updates: Vec<_> = inbox.try_iter().collect();
+        match updates.as_slice() {
+            [ServerMessage::TableUpdated(created), ServerMessage::TableUpdated(joined)] => {
+                assert_eq!((created.id, created.players, created.max_seats), (1, 0, 6));
+                assert_eq!(joined.players, 1);
+            }
+            updates => panic!("unexpected updates: {:?}", updates),
+        }
+
+        // a dropped connection keeps its seat, and logging in again takes it back
+        drop(player);
+        assert!(inbox.try_recv().is_err());
+        assert_eq!(server.accounts().get_chips("john"), Some(STARTING_CHIPS - 500));
+        let (player_outbox, player_inbox) = mpsc::sync_channel(OUTBOX_CAPACITY);
+        let mut player = Connection::new(&server, player_outbox);
+        player.handle(ClientMessage::Username("john".to_owned()));
+        // the login and the seat reach him before the table does
+        assert_eq!(player.handle(ClientMessage::Password("battery staple".to_owned())), Vec::new());
+        assert!(matches!(
+            from_tables(&player_inbox).as_slice(),
+            [ServerMessage::LoggedIn { .. }, ServerMessage::Seated { table: 1, seat: 0 }, ServerMessage::TableState { table: 1, .. }]
+        ));
+        assert_eq!(player.get_state(), &ConnectionState::AtTable { username: "john".to_owned(), table: 1, seated: true });
+        assert!(matches!(inbox.try_recv(), Ok(ServerMessage::TableUpdated(summary)) if summary.players == 1));
+
+        // quitting between hands gives up the seat and the chips go home
+        player.handle(ClientMessage::Quit);
+        assert!(matches!(inbox.try_recv(), Ok(ServerMessage::TableUpdated(summary)) if summary.players == 0));
+        assert_eq!(server.accounts().get_chips("john"), Some(STARTING_CHIPS));
+
+        // once the watcher quits it hears nothing more
+        watcher.handle(ClientMessage::Quit);
+        let mut other = Connection::new(&server, self::outbox());
+        log_in(&mut other, "john", "battery staple");
+        other.handle(create_table());
+        assert!(inbox.try_recv().is_err());
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_logging_in_again_takes_the_session_over() {
+        let (server, path) = server("takeover");
+        let (outbox, inbox) = mpsc::sync_channel(OUTBOX_CAPACITY);
+        let mut first = Connection::new(&server, outbox);
+        log_in(&mut first, "jane", "correct horse");
+        first.handle(create_table());
+        first.handle(ClientMessage::JoinTable { table: 1, buy_in: 500 });
+        inbox.try_iter().for_each(drop);
+
+        // the new connection gets the seat and the old one is told
+        let (second_outbox, second_inbox) = mpsc::sync_channel(OUTBOX_CAPACITY);
+        let mut second = Connection::new(&server, second_outbox);
+        second.handle(ClientMessage::Username("jane".to_owned()));
+        assert_eq!(second.handle(ClientMessage::Password("correct horse".to_owned())), Vec::new());
+        assert!(matches!(
+            from_tables(&second_inbox).as_slice(),
+            [ServerMessage::LoggedIn { .. }, ServerMessage::Seated { table: 1, seat: 0 }, ServerMessage::TableState { table: 1, .. }]
+        ));
+        assert!(inbox.try_iter().any(|message| matches!(message, ServerMessage::Error(_))));
+
+        // the old one can't act for the seat any more, and closing it leaves
+        // the seat alone
+        let replies = first.handle(ClientMessage::Action { table: 1, action: PlayerAction::Fold });
+        assert_eq!(replies.last(), Some(&ServerMessage::Goodbye));
+        assert!(first.is_closed());
+        drop(first);
+        assert_eq!(server.lobby().summary(1).unwrap().players, 1);
+        assert_eq!(server.accounts().get_chips("jane"), Some(STARTING_CHIPS - 500));
+        assert_eq!(second.get_state(), &ConnectionState::AtTable { username: "jane".to_owned(), table: 1, seated: true });
+
+        // quitting the new one cashes out as usual
+        second.handle(ClientMessage::Quit);
+        assert_eq!(server.accounts().get_chips("jane"), Some(STARTING_CHIPS));
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_resume_with_session_token() {
+        let (server, path) = server("resume");
+        let mut first = Connection::new(&server, outbox());
+        let token = log_in(&mut first, "jane", "correct horse");
+        // the connection drops without quitting
+
+        let mut second = Connection::new(&server, outbox());
+        let replies = second.handle(ClientMessage::Resume { token: token.clone() });
+        assert_eq!(replies, vec![ServerMessage::LoggedIn { chips: STARTING_CHIPS, token: token.clone() }]);
+        assert_eq!(second.get_state(), &ConnectionState::Authenticated { username: "jane".to_owned() });
+
+        // quitting ends the session for good
+        assert_eq!(second.handle(ClientMessage::Quit), vec![ServerMessage::Goodbye]);
+        assert!(second.is_closed());
+        let mut third = Connection::new(&server, outbox());
+        assert!(is_error(&third.handle(ClientMessage::Resume { token })));
+        assert!(is_error(&third.handle(ClientMessage::Resume { token: "made up".to_owned() })));
+        fs::remove_file(path).unwrap();
+    }
+}
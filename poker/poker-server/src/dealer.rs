@@ -1,138 +1,622 @@
-use std::cmp::Ordering;
-use std::collections::HashMap;
+use std::cmp::Reverse;
+use std::fmt;
 
-use poker_common::card::{Card, Suit, Value};
+use poker_common::action::PlayerAction;
+use poker_common::badugi::{self, BadugiHand};
+use poker_common::card::{Card, Deck};
+use poker_common::evaluator::HandStrength;
+use poker_common::hand::HandEvaluation;
+use poker_common::low::{LowballHand, LowballRanking};
 use poker_common::player::Player;
-use poker_common::game::{GameSession, Game};
+use poker_common::wild::{self, WildCards};
 
+pub use poker_common::stakes::Stakes;
+pub use poker_common::view::{LegalAction, Round, SeatView, TableView, Viewer};
+
+use crate::betting::{BettingError, BettingStructure};
+use crate::pot::Pot;
+use crate::table::Table;
+use crate::variant::Variant;
+
+pub const HAND_SIZE: usize = 5;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum DealerError {
+    NotEnoughPlayers,
+    WrongRound { expected: Round, actual: Round },
+    InvalidSeat(usize),
+    NotPlayersTurn { seat: usize, current: usize },
+    PlayerNotInHand(usize),
+    TooManyDiscards(usize),
+    CardNotInHand(Card),
+    DrawNotFinished,
+    AlreadyDrawn(usize),
+    DeckExhausted,
+    CannotAdvance(Round),
+    NotBettingRound(Round),
+    BettingNotOpen,
+    BettingNotComplete,
+    Betting(BettingError),
+}
+
+impl fmt::Display for DealerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DealerError::NotEnoughPlayers => write!(f, "at least two players with chips need to be sitting in"),
+            DealerError::WrongRound { expected, actual } => {
+                write!(f, "expected round {:?} but the hand is in {:?}", expected, actual)
+            }
+            DealerError::InvalidSeat(seat) => write!(f, "seat {} does not exist", seat),
+            DealerError::NotPlayersTurn { seat, current } => {
+                write!(f, "seat {} acted out of turn, seat {} is to act", seat, current)
+            }
+            DealerError::PlayerNotInHand(seat) => write!(f, "seat {} is not in the hand", seat),
+            DealerError::TooManyDiscards(count) => {
+                write!(f, "cannot discard {} cards, more than the hand holds", count)
+            }
+            DealerError::CardNotInHand(card) => write!(f, "{:?} is not in the player's hand", card),
+            DealerError::DrawNotFinished => write!(f, "not every player has drawn yet"),
+            DealerError::AlreadyDrawn(seat) => write!(f, "seat {} has already drawn", seat),
+            DealerError::DeckExhausted => write!(f, "the deck ran out of cards"),
+            DealerError::CannotAdvance(round) => write!(f, "there is no phase to advance to from {:?}", round),
+            DealerError::NotBettingRound(round) => write!(f, "there is no betting during {:?}", round),
+            DealerError::BettingNotOpen => write!(f, "no betting round is open"),
+            DealerError::BettingNotComplete => write!(f, "the betting round is not complete"),
+            DealerError::Betting(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for DealerError {}
+
+impl From<BettingError> for DealerError {
+    fn from(err: BettingError) -> Self {
+        DealerError::Betting(err)
+    }
+}
+
+/// How a table is played: its forced bets, its betting limits, any wild
+/// cards and, in short deck, how three of a kind ranks against a straight.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TableConfig {
+    pub stakes: Stakes,
+    pub structure: BettingStructure,
+    /// Wild cards are played in five card draw only; every other game deals
+    /// and ranks natural cards.
+    pub wild: WildCards,
+    /// Short deck only: whether three of a kind beats a straight.
+    pub trips_beat_straight: bool,
+}
+
+impl Default for TableConfig {
+    fn default() -> Self {
+        TableConfig {
+            stakes: Stakes::default(),
+            structure: BettingStructure::NoLimit,
+            wild: WildCards::none(),
+            trips_beat_straight: true,
+        }
+    }
+}
+
+/// The interface every poker variant is played through, so the server can
+/// host any of them without knowing which one it is running.
+pub trait Dealer: Send {
+    fn variant(&self) -> Variant;
+
+    /// Seats a player at the next empty seat and returns the seat number.
+    fn seat_player(&mut self, player: Player) -> usize;
+
+    /// Takes the player in `seat` off the table between hands. Everyone
+    /// after them moves up a seat, and the button stays with the same player.
+    fn remove_player(&mut self, seat: usize) -> Result<Player, DealerError>;
+
+    /// Deals the player in `seat` out of every hand from the next one on,
+    /// or back in.
+    fn sit_out(&mut self, seat: usize, sitting_out: bool) -> Result<(), DealerError>;
+
+    fn players(&self) -> &[Player];
+
+    fn round(&self) -> Round;
+
+    fn button(&self) -> usize;
+
+    /// Places the button before a hand starts, as when a mixed game switches
+    /// to this variant. Stud games only use it to order the deal.
+    fn set_button(&mut self, seat: usize);
+
+    /// Starts a hand dealt from a deck shuffled with `seed`.
+    fn start_hand(&mut self, seed: u64) -> Result<(), DealerError>;
+
+    /// Starts a hand dealt from `deck` as it stands, to replay a hand or set
+    /// one up.
+    fn start_hand_from(&mut self, deck: Deck) -> Result<(), DealerError>;
+
+    /// Applies a betting action and returns whether the betting round is complete.
+    fn apply_action(&mut self, seat: usize, action: PlayerAction) -> Result<bool, DealerError>;
+
+    /// The betting actions open to `seat`, empty unless it is their turn to bet.
+    fn legal_actions(&self, seat: usize) -> Vec<LegalAction>;
+
+    /// Swaps cards in a draw game. Variants without a draw have no round for it.
+    fn draw(&mut self, _seat: usize, _discards: &[Card]) -> Result<(), DealerError> {
+        Err(DealerError::WrongRound { expected: Round::Draw, actual: self.round() })
+    }
+
+    /// Closes the current phase and moves the hand on to the next one.
+    fn advance(&mut self) -> Result<Round, DealerError>;
+
+    fn view_for(&self, viewer: Viewer) -> TableView;
+
+    /// Awards the pots and returns each winner's seat with their winnings.
+    fn showdown(&mut self) -> Result<Vec<(usize, u32)>, DealerError>;
+
+    /// Each winner's seat and winnings once the hand is complete, whether it
+    /// went to a showdown or everyone else folded.
+    fn awards(&self) -> Vec<(usize, u32)>;
+
+    fn end_hand(&mut self) -> Result<(), DealerError>;
+}
+
+/// The draw games: how many times players draw and which hand wins.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DrawGame {
+    /// One draw, best high hand.
+    FiveCardDraw,
+    /// Three draws, lowest hand at deuce-to-seven: aces high, straights and
+    /// flushes count against.
+    DeuceToSevenTripleDraw,
+    /// Three draws, lowest hand at ace-to-five: aces low, straights and
+    /// flushes ignored.
+    AceToFiveTripleDraw,
+    /// Three draws with four cards each, best badugi wins.
+    Badugi,
+}
+
+impl DrawGame {
+    pub fn draws(&self) -> usize {
+        match self {
+            DrawGame::FiveCardDraw => 1,
+            DrawGame::DeuceToSevenTripleDraw | DrawGame::AceToFiveTripleDraw | DrawGame::Badugi => 3,
+        }
+    }
+
+    pub fn hand_size(&self) -> usize {
+        match self {
+            DrawGame::Badugi => 4,
+            _ => HAND_SIZE,
+        }
+    }
+
+    /// How hands are ranked at showdown in the lowball games.
+    pub fn lowball(&self) -> Option<LowballRanking> {
+        match self {
+            DrawGame::FiveCardDraw | DrawGame::Badugi => None,
+            DrawGame::DeuceToSevenTripleDraw => Some(LowballRanking::DeuceToSeven),
+            DrawGame::AceToFiveTripleDraw => Some(LowballRanking::AceToFive),
+        }
+    }
+}
+
+/// Deals the draw games: five cards each, or four in Badugi, then a betting round before the
+/// first draw and after every draw.
 pub struct FiveDrawDealer {
-    deck: Vec<Card>,
-    dealer_hand: Vec<Card>,
-    players: Vec<Player>,
-    discard: Vec<Card>,
-    pot: u32,
-    current_bet: u32,
-    current_player: u32,
-    round: u32,
+    table: Table,
+    game: DrawGame,
+    round: Round,
+    has_drawn: Vec<bool>,
+    draws_completed: usize,
 }
 
 impl FiveDrawDealer
 {
     pub fn new() -> Self {
+        Self::with_config(TableConfig::default())
+    }
+
+    pub fn with_stakes(stakes: Stakes) -> Self {
+        Self::with_config(TableConfig { stakes, ..TableConfig::default() })
+    }
+
+    pub fn with_config(config: TableConfig) -> Self {
+        Self::with_game(DrawGame::FiveCardDraw, config)
+    }
+
+    pub fn with_game(game: DrawGame, config: TableConfig) -> Self {
         FiveDrawDealer {
-            deck: Vec::new(),
-            players: Vec::new(),
-            discard: Vec::new(),
-            dealer_hand: Vec::new(),
-            pot: 0,
-            current_bet: 0,
-            current_player: 0,
-            round: 0,
+            table: Table::new(config),
+            game,
+            round: Round::Waiting,
+            has_drawn: Vec::new(),
+            draws_completed: 0,
         }
     }
 
     pub fn add_player(&mut self, player: Player) {
-        self.players.push(player);
+        self.table.add_player(player);
     }
 
-    pub fn check_for_winning_hand(&self) -> Vec<&Player> {
-        if self.players.is_empty() {
-            return vec![]; // No players, no winner
-        }
-
-        // Find the highest-ranked hand
-        let best_hand = self.players.iter().map(|p| {
-            let hand = p.get_hand();
-            (p, Self::evaluate_hand(hand))
-        }).max_by(|(_, (rank1, values1)), (_, (rank2, values2))| {
-            match rank1.cmp(rank2) {
-                Ordering::Greater => Ordering::Greater,
-                Ordering::Less => Ordering::Less,
-                Ordering::Equal => values1.cmp(values2),
-            }
-        });
+    pub fn get_players(&self) -> &Vec<Player> {
+        &self.table.players
+    }
+
+    pub fn get_game(&self) -> DrawGame {
+        self.game
+    }
+
+    /// How many draws have been completed this hand.
+    pub fn get_draws_completed(&self) -> usize {
+        self.draws_completed
+    }
+
+    pub fn get_stakes(&self) -> Stakes {
+        self.table.config.stakes
+    }
+
+    pub fn get_config(&self) -> TableConfig {
+        self.table.config
+    }
+
+    /// The totals the player to act may bet or raise to, if they may raise at all.
+    pub fn raise_range(&self) -> Option<(u32, u32)> {
+        self.table.raise_range()
+    }
+
+    pub fn get_pot(&self) -> u32 {
+        self.table.pot
+    }
+
+    /// The main pot followed by any side pots, built from what each seat has
+    /// put in so far this hand.
+    pub fn get_pots(&self) -> Vec<Pot> {
+        self.table.pots()
+    }
+
+    pub fn get_current_bet(&self) -> u32 {
+        self.table.current_bet()
+    }
+
+    pub fn get_current_player(&self) -> usize {
+        self.table.current_player
+    }
+
+    pub fn get_button(&self) -> usize {
+        self.table.button
+    }
+
+    pub fn get_round(&self) -> Round {
+        self.round
+    }
 
-        if let Some((_, best_eval)) = best_hand {
-            // Filter all players with the best hand rank and values
-            self.players.iter()
-                .filter(|p| Self::evaluate_hand(p.get_hand()) == best_eval)
-                .collect()
-        } else {
-            vec![] // No winner case (shouldn't happen with valid hands)
-        }
-    }
-
-    fn evaluate_hand(hand: &[Card]) -> (u8, Vec<u8>) {
-        let mut values: Vec<u8> = hand.iter().map(|c| c.value.rank()).collect();
-        let mut suits: Vec<Suit> = hand.iter().map(|c| c.suit.clone()).collect();
-        let mut value_counts: HashMap<u8, u8> = HashMap::new();
-    
-        for &v in &values {
-            *value_counts.entry(v).or_insert(0) += 1;
-        }
-    
-        values.sort_by(|a, b| b.cmp(a)); // Sort highest to lowest for tiebreaker
-    
-        let is_flush = suits.iter().all(|s| *s == suits[0]);
-        let is_straight = values.windows(2).all(|w| w[1] == w[0] - 1);
-        let ace_low_straight = values == vec![14, 5, 4, 3, 2]; // A-2-3-4-5 case
-    
-        let mut counts: Vec<(u8, u8)> = value_counts.iter().map(|(&v, &c)| (c, v)).collect();
-        counts.sort_by(|a, b| b.cmp(a)); // Sort by count then value
-    
-        let hand_rank = if is_flush && (is_straight || ace_low_straight) {
-            if values.contains(&14) { 10 } else { 9 } // Royal Flush or Straight Flush
-        } else if counts[0].0 == 4 {
-            8 // Four of a Kind
-        } else if counts[0].0 == 3 && counts[1].0 == 2 {
-            7 // Full House
-        } else if is_flush {
-            6 // Flush
-        } else if is_straight || ace_low_straight {
-            5 // Straight
-        } else if counts[0].0 == 3 {
-            4 // Three of a Kind
-        } else if counts.len() > 1 && counts[0].0 == 2 && counts[1].0 == 2 {
-            3 // Two Pair
-        } else if counts[0].0 == 2 {
-            2 // One Pair
-        } else {
-            1 // High Card
+    pub fn cards_in_deck(&self) -> usize {
+        self.table.deck.remaining()
+    }
+
+    /// True once everyone still in the hand has acted on the current bet.
+    pub fn is_betting_complete(&self) -> bool {
+        self.table.is_betting_complete()
+    }
+
+    /// Starts a new hand: seats everyone with chips, posts antes and blinds,
+    /// deals five cards each and opens the first betting round.
+    pub fn start_hand(&mut self, seed: u64) -> Result<(), DealerError> {
+        let deck = match self.wild_cards().joker() {
+            Some(_) => Deck::shuffled_with_joker(seed),
+            None => Deck::shuffled(seed),
         };
-    
-        // Create tiebreaker sequence (first by hand type, then high cards)
-        let mut tiebreaker_values: Vec<u8> = counts.iter().map(|(_, v)| *v).collect();
-        tiebreaker_values.extend(values);
-    
-        (hand_rank, tiebreaker_values)
-    }
-
-    fn compare_hands(hand1: &[Card], hand2: &[Card]) -> Ordering {
-        let (rank1, values1) = Self::evaluate_hand(hand1);
-        let (rank2, values2) = Self::evaluate_hand(hand2);
-    
-        match rank1.cmp(&rank2) {
-            Ordering::Greater => Ordering::Greater,
-            Ordering::Less => Ordering::Less,
-            Ordering::Equal => {
-                for (v1, v2) in values1.iter().zip(values2.iter()) {
-                    match v1.cmp(v2) {
-                        Ordering::Greater => return Ordering::Greater,
-                        Ordering::Less => return Ordering::Less,
-                        Ordering::Equal => continue,
-                    }
+        self.start_hand_from(deck)
+    }
+
+    /// Starts a new hand as [`FiveDrawDealer::start_hand`] does, dealing
+    /// from `deck` as it stands.
+    pub fn start_hand_from(&mut self, deck: Deck) -> Result<(), DealerError> {
+        self.expect_round(Round::Waiting)?;
+        self.table.begin_hand(deck)?;
+
+        self.table.post_antes();
+        // heads up, the button posts the small blind and acts first before the draw
+        let big_blind_seat = self.table.post_blinds();
+        let first = self.table.next_active(big_blind_seat);
+        self.table.open_betting(first, self.table.config.stakes.big_blind, false);
+
+        self.deal_hands();
+        self.has_drawn = vec![false; self.table.players.len()];
+        self.draws_completed = 0;
+        self.round = Round::FirstBetting;
+        Ok(())
+    }
+
+    /// Collects every card from the previous hand and deals a hand from the
+    /// table's deck, one card at a time, to every active player.
+    pub fn deal_hands(&mut self) {
+        let table = &mut self.table;
+        for player in table.players.iter_mut() {
+            player.clear_hand();
+        }
+
+        for _ in 0..self.game.hand_size() {
+            for player in table.players.iter_mut().filter(|p| p.is_active()) {
+                if let Some(card) = table.deck.deal() {
+                    player.add_card(card);
                 }
-                Ordering::Equal
             }
         }
-    }    
+    }
+
+    /// Applies a betting action for the player whose turn it is and returns
+    /// whether the betting round is now complete. If everyone else folds,
+    /// the last player is awarded the pot and the hand is over.
+    pub fn apply_action(&mut self, seat: usize, action: PlayerAction) -> Result<bool, DealerError> {
+        if !matches!(self.round, Round::FirstBetting | Round::SecondBetting | Round::ThirdBetting | Round::FourthBetting) {
+            return Err(DealerError::NotBettingRound(self.round));
+        }
+        let complete = self.table.apply_action(seat, action)?;
+        if self.table.award_if_uncontested() {
+            self.round = Round::Complete;
+        }
+        Ok(complete)
+    }
+
+    /// Closes the current phase and moves the hand on to the next one.
+    pub fn advance(&mut self) -> Result<Round, DealerError> {
+        match self.round {
+            Round::FirstBetting | Round::SecondBetting | Round::ThirdBetting | Round::FourthBetting => {
+                self.table.end_betting_round()?;
+                if self.draws_completed == self.game.draws() {
+                    self.round = Round::Showdown;
+                } else {
+                    self.round = Round::Draw;
+                    self.has_drawn = vec![false; self.table.players.len()];
+                    self.table.current_player = self.table.next_active(self.table.button);
+                }
+            }
+            Round::Draw => {
+                let players = &self.table.players;
+                if players.iter().enumerate().any(|(i, p)| p.is_active() && !self.has_drawn[i]) {
+                    return Err(DealerError::DrawNotFinished);
+                }
+                self.draws_completed += 1;
+                self.round = match self.draws_completed {
+                    1 => Round::SecondBetting,
+                    2 => Round::ThirdBetting,
+                    _ => Round::FourthBetting,
+                };
+                // fixed-limit games bet the big bet over the second half of
+                // the hand: after the draw, or after the second of three
+                let big_street = self.draws_completed * 2 > self.game.draws();
+                let first = self.table.next_active(self.table.button);
+                self.table.open_betting(first, 0, big_street);
+            }
+            round => return Err(DealerError::CannotAdvance(round)),
+        }
+        Ok(self.round)
+    }
+
+    /// Swaps the given cards for new ones from the deck. Players draw in turn,
+    /// starting left of the button; an empty slice stands pat.
+    pub fn draw(&mut self, seat: usize, discards: &[Card]) -> Result<(), DealerError> {
+        self.expect_round(Round::Draw)?;
+        self.table.expect_turn(seat)?;
+        if self.has_drawn[seat] {
+            return Err(DealerError::AlreadyDrawn(seat));
+        }
+        if discards.len() > self.game.hand_size() {
+            return Err(DealerError::TooManyDiscards(discards.len()));
+        }
+        let table = &mut self.table;
+        for (i, card) in discards.iter().enumerate() {
+            if !table.players[seat].get_hand().contains(card) || discards[..i].contains(card) {
+                return Err(DealerError::CardNotInHand(card.clone()));
+            }
+        }
+        if table.deck.remaining() + table.deck.discarded() < discards.len() {
+            return Err(DealerError::DeckExhausted);
+        }
+
+        for card in discards {
+            table.players[seat].remove_card(card.suit.clone(), card.value.clone());
+        }
+        // replacements are dealt before the discards hit the muck, so a player
+        // can never be dealt back the cards they just threw away
+        for _ in 0..discards.len() {
+            table.deal_to(seat)?;
+        }
+        for card in discards {
+            table.deck.discard(card.clone());
+        }
+
+        self.has_drawn[seat] = true;
+        // once everyone has drawn the turn stays put until the next betting
+        // round, rather than coming back round to the first drawer
+        let players = &table.players;
+        if players.iter().enumerate().any(|(i, p)| p.is_active() && !self.has_drawn[i]) {
+            table.current_player = table.next_active(seat);
+        }
+        Ok(())
+    }
+
+    /// Awards the main pot and each side pot to the best hand eligible for it,
+    /// and returns each winner's seat with their total winnings.
+    ///
+    /// A tied pot is split evenly; chips that cannot be split go one at a time
+    /// to the tied winners starting from the first seat left of the button.
+    pub fn showdown(&mut self) -> Result<Vec<(usize, u32)>, DealerError> {
+        self.expect_round(Round::Showdown)?;
+        let awards = match (self.game, self.game.lowball()) {
+            (DrawGame::Badugi, _) => self.table.award_pots(&self.badugi_hands()),
+            (_, Some(ranking)) => self.table.award_pots(&self.lowball_hands(ranking)),
+            (_, None) => self.table.award_pots(&self.hand_strengths()),
+        };
+        self.round = Round::Complete;
+        Ok(awards)
+    }
+
+    /// Clears the finished hand and moves the button for the next one.
+    pub fn end_hand(&mut self) -> Result<(), DealerError> {
+        self.expect_round(Round::Complete)?;
+        self.table.end_hand();
+        self.round = Round::Waiting;
+        Ok(())
+    }
+
+    /// The best hand the player in `seat` holds, for showing at showdown.
+    pub fn hand_evaluation(&self, seat: usize) -> Option<HandEvaluation> {
+        HandEvaluation::wild(self.table.players.get(seat)?.get_hand(), &self.wild_cards())
+    }
+
+    /// The lowball hand the player in `seat` holds in a lowball draw game.
+    pub fn lowball_hand(&self, seat: usize) -> Option<LowballHand> {
+        self.game.lowball()?.best(self.table.players.get(seat)?.get_hand())
+    }
+
+    /// The badugi the player in `seat` holds in Badugi.
+    pub fn badugi_hand(&self, seat: usize) -> Option<BadugiHand> {
+        if self.game != DrawGame::Badugi {
+            return None;
+        }
+        badugi::best_badugi(self.table.players.get(seat)?.get_hand())
+    }
+
+    pub fn check_for_winning_hand(&self) -> Vec<&Player> {
+        let seats: Vec<usize> = (0..self.table.players.len()).collect();
+        let winners = match (self.game, self.game.lowball()) {
+            (DrawGame::Badugi, _) => self.table.best_among(&seats, &self.badugi_hands()),
+            (_, Some(ranking)) => self.table.best_among(&seats, &self.lowball_hands(ranking)),
+            (_, None) => self.table.best_among(&seats, &self.hand_strengths()),
+        };
+        winners.into_iter().map(|seat| &self.table.players[seat]).collect()
+    }
+
+    // Folded players have mucked their cards and cannot win
+    fn hand_strengths(&self) -> Vec<Option<HandStrength>> {
+        let wild = self.wild_cards();
+        self.table.players.iter()
+            .map(|player| wild::best_hand(player.get_hand(), &wild).map(|(strength, _)| strength))
+            .collect()
+    }
+
+    // Only five card draw plays with wild cards
+    fn wild_cards(&self) -> WildCards {
+        match self.game {
+            DrawGame::FiveCardDraw => self.table.config.wild,
+            _ => WildCards::none(),
+        }
+    }
+
+    // The lowest hand wins, so each is reversed to make the best compare highest
+    fn lowball_hands(&self, ranking: LowballRanking) -> Vec<Option<Reverse<LowballHand>>> {
+        self.table.players.iter().map(|player| ranking.best(player.get_hand()).map(Reverse)).collect()
+    }
+
+    fn badugi_hands(&self) -> Vec<Option<Reverse<BadugiHand>>> {
+        self.table.players.iter().map(|player| badugi::best_badugi(player.get_hand()).map(Reverse)).collect()
+    }
+
+    fn expect_round(&self, expected: Round) -> Result<(), DealerError> {
+        if self.round != expected {
+            return Err(DealerError::WrongRound { expected, actual: self.round });
+        }
+        Ok(())
+    }
+}
+
+impl Dealer for FiveDrawDealer {
+    fn variant(&self) -> Variant {
+        match self.game {
+            DrawGame::FiveCardDraw => Variant::FiveCardDraw,
+            DrawGame::DeuceToSevenTripleDraw => Variant::DeuceToSevenTripleDraw,
+            DrawGame::AceToFiveTripleDraw => Variant::AceToFiveTripleDraw,
+            DrawGame::Badugi => Variant::Badugi,
+        }
+    }
+
+    fn seat_player(&mut self, player: Player) -> usize {
+        self.add_player(player);
+        self.table.players.len() - 1
+    }
+
+    fn players(&self) -> &[Player] {
+        &self.table.players
+    }
+
+    fn round(&self) -> Round {
+        self.round
+    }
+
+    fn button(&self) -> usize {
+        self.table.button
+    }
+
+    fn set_button(&mut self, seat: usize) {
+        self.table.button = seat;
+    }
+
+    fn remove_player(&mut self, seat: usize) -> Result<Player, DealerError> {
+        self.expect_round(Round::Waiting)?;
+        self.table.remove_player(seat)
+    }
+
+    fn sit_out(&mut self, seat: usize, sitting_out: bool) -> Result<(), DealerError> {
+        self.table.sit_out(seat, sitting_out)
+    }
+
+    fn start_hand(&mut self, seed: u64) -> Result<(), DealerError> {
+        FiveDrawDealer::start_hand(self, seed)
+    }
+
+    fn start_hand_from(&mut self, deck: Deck) -> Result<(), DealerError> {
+        FiveDrawDealer::start_hand_from(self, deck)
+    }
+
+    fn apply_action(&mut self, seat: usize, action: PlayerAction) -> Result<bool, DealerError> {
+        FiveDrawDealer::apply_action(self, seat, action)
+    }
+
+    fn legal_actions(&self, seat: usize) -> Vec<LegalAction> {
+        self.table.legal_actions(seat)
+    }
+
+    fn draw(&mut self, seat: usize, discards: &[Card]) -> Result<(), DealerError> {
+        FiveDrawDealer::draw(self, seat, discards)
+    }
+
+    fn advance(&mut self) -> Result<Round, DealerError> {
+        FiveDrawDealer::advance(self)
+    }
+
+    fn view_for(&self, viewer: Viewer) -> TableView {
+        let drawing = self.round == Round::Draw && !self.has_drawn.get(self.table.current_player).copied().unwrap_or(true);
+        let to_act = if drawing { Some(self.table.current_player) } else { self.table.to_act() };
+        self.table.view(self.variant(), self.round, to_act, &[], viewer)
+    }
+
+    fn showdown(&mut self) -> Result<Vec<(usize, u32)>, DealerError> {
+        FiveDrawDealer::showdown(self)
+    }
+
+    fn awards(&self) -> Vec<(usize, u32)> {
+        self.table.awards.clone()
+    }
+
+    fn end_hand(&mut self) -> Result<(), DealerError> {
+        FiveDrawDealer::end_hand(self)
+    }
+}
+
+impl Default for FiveDrawDealer {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use poker_common::action::PlayerAction;
     use poker_common::card::{Card, Suit, Value};
+    use poker_common::hand::HandRank;
     use poker_common::player::Player;
+    use poker_common::wild::Joker;
 
     #[test]
     fn test_evaluate_hand() {
@@ -146,25 +630,25 @@ mod tests {
         dealer.add_player(player3);
 
         // give player 1 a pair of 2s and 3 random cards
-        dealer.players[0].add_card(Card {suit: Suit::Hearts, value: Value::Two});
-        dealer.players[0].add_card(Card {suit: Suit::Diamonds, value: Value::Two});
-        dealer.players[0].add_card(Card {suit: Suit::Clubs, value: Value::Five});
-        dealer.players[0].add_card(Card {suit: Suit::Spades, value: Value::Nine});
-        dealer.players[0].add_card(Card {suit: Suit::Hearts, value: Value::King});
+        dealer.table.players[0].add_card(Card {suit: Suit::Hearts, value: Value::Two});
+        dealer.table.players[0].add_card(Card {suit: Suit::Diamonds, value: Value::Two});
+        dealer.table.players[0].add_card(Card {suit: Suit::Clubs, value: Value::Five});
+        dealer.table.players[0].add_card(Card {suit: Suit::Spades, value: Value::Nine});
+        dealer.table.players[0].add_card(Card {suit: Suit::Hearts, value: Value::King});
 
         // give player 2 a pair of 3s and 3 random cards
-        dealer.players[1].add_card(Card {suit: Suit::Diamonds, value: Value::Three});
-        dealer.players[1].add_card(Card {suit: Suit::Clubs, value: Value::Three});
-        dealer.players[1].add_card(Card {suit: Suit::Spades, value: Value::Eight});
-        dealer.players[1].add_card(Card {suit: Suit::Hearts, value: Value::Four});
-        dealer.players[1].add_card(Card {suit: Suit::Diamonds, value: Value::Seven});
+        dealer.table.players[1].add_card(Card {suit: Suit::Diamonds, value: Value::Three});
+        dealer.table.players[1].add_card(Card {suit: Suit::Clubs, value: Value::Three});
+        dealer.table.players[1].add_card(Card {suit: Suit::Spades, value: Value::Eight});
+        dealer.table.players[1].add_card(Card {suit: Suit::Hearts, value: Value::Four});
+        dealer.table.players[1].add_card(Card {suit: Suit::Diamonds, value: Value::Seven});
 
         // give player 3 a pair of 4s and 3 random cards
-        dealer.players[2].add_card(Card {suit: Suit::Clubs, value: Value::Four});
-        dealer.players[2].add_card(Card {suit: Suit::Spades, value: Value::Four});
-        dealer.players[2].add_card(Card {suit: Suit::Hearts, value: Value::Ten});
-        dealer.players[2].add_card(Card {suit: Suit::Diamonds, value: Value::Six});
-        dealer.players[2].add_card(Card {suit: Suit::Clubs, value: Value::Queen});
+        dealer.table.players[2].add_card(Card {suit: Suit::Clubs, value: Value::Four});
+        dealer.table.players[2].add_card(Card {suit: Suit::Spades, value: Value::Four});
+        dealer.table.players[2].add_card(Card {suit: Suit::Hearts, value: Value::Ten});
+        dealer.table.players[2].add_card(Card {suit: Suit::Diamonds, value: Value::Six});
+        dealer.table.players[2].add_card(Card {suit: Suit::Clubs, value: Value::Queen});
 
         let winner = dealer.check_for_winning_hand();
         assert_eq!(winner.len(), 1);
@@ -184,25 +668,25 @@ mod tests {
         dealer.add_player(player3);
 
         // give player 1 a pair of 2s and 3 random cards
-        dealer.players[0].add_card(Card {suit: Suit::Hearts, value: Value::Two});
-        dealer.players[0].add_card(Card {suit: Suit::Diamonds, value: Value::Two});
-        dealer.players[0].add_card(Card {suit: Suit::Clubs, value: Value::Five});
-        dealer.players[0].add_card(Card {suit: Suit::Spades, value: Value::Nine});
-        dealer.players[0].add_card(Card {suit: Suit::Hearts, value: Value::King});
+        dealer.table.players[0].add_card(Card {suit: Suit::Hearts, value: Value::Two});
+        dealer.table.players[0].add_card(Card {suit: Suit::Diamonds, value: Value::Two});
+        dealer.table.players[0].add_card(Card {suit: Suit::Clubs, value: Value::Five});
+        dealer.table.players[0].add_card(Card {suit: Suit::Spades, value: Value::Nine});
+        dealer.table.players[0].add_card(Card {suit: Suit::Hearts, value: Value::King});
 
         // give player 2 a pair of 2s and 3 random cards
-        dealer.players[1].add_card(Card {suit: Suit::Spades, value: Value::Two});
-        dealer.players[1].add_card(Card {suit: Suit::Clubs, value: Value::Two});
-        dealer.players[1].add_card(Card {suit: Suit::Hearts, value: Value::Five});
-        dealer.players[1].add_card(Card {suit: Suit::Diamonds, value: Value::Nine});
-        dealer.players[1].add_card(Card {suit: Suit::Spades, value: Value::King});
+        dealer.table.players[1].add_card(Card {suit: Suit::Spades, value: Value::Two});
+        dealer.table.players[1].add_card(Card {suit: Suit::Clubs, value: Value::Two});
+        dealer.table.players[1].add_card(Card {suit: Suit::Hearts, value: Value::Five});
+        dealer.table.players[1].add_card(Card {suit: Suit::Diamonds, value: Value::Nine});
+        dealer.table.players[1].add_card(Card {suit: Suit::Spades, value: Value::King});
 
         // give player 3 a pair of 5 random cards
-        dealer.players[2].add_card(Card {suit: Suit::Hearts, value: Value::Three});
-        dealer.players[2].add_card(Card {suit: Suit::Diamonds, value: Value::Four});
-        dealer.players[2].add_card(Card {suit: Suit::Clubs, value: Value::Five});
-        dealer.players[2].add_card(Card {suit: Suit::Spades, value: Value::Six});
-        dealer.players[2].add_card(Card {suit: Suit::Hearts, value: Value::Eight});
+        dealer.table.players[2].add_card(Card {suit: Suit::Hearts, value: Value::Three});
+        dealer.table.players[2].add_card(Card {suit: Suit::Diamonds, value: Value::Four});
+        dealer.table.players[2].add_card(Card {suit: Suit::Clubs, value: Value::Five});
+        dealer.table.players[2].add_card(Card {suit: Suit::Spades, value: Value::Six});
+        dealer.table.players[2].add_card(Card {suit: Suit::Hearts, value: Value::Eight});
 
         let winner = dealer.check_for_winning_hand();
         assert_eq!(winner.len(), 2);
@@ -223,25 +707,25 @@ mod tests {
         dealer.add_player(player3);
 
         // give player 1 a pair of 2s and 3 random cards
-        dealer.players[0].add_card(Card {suit: Suit::Hearts, value: Value::Two});
-        dealer.players[0].add_card(Card {suit: Suit::Diamonds, value: Value::Two});
-        dealer.players[0].add_card(Card {suit: Suit::Clubs, value: Value::Five});
-        dealer.players[0].add_card(Card {suit: Suit::Spades, value: Value::Nine});
-        dealer.players[0].add_card(Card {suit: Suit::Hearts, value: Value::King});
+        dealer.table.players[0].add_card(Card {suit: Suit::Hearts, value: Value::Two});
+        dealer.table.players[0].add_card(Card {suit: Suit::Diamonds, value: Value::Two});
+        dealer.table.players[0].add_card(Card {suit: Suit::Clubs, value: Value::Five});
+        dealer.table.players[0].add_card(Card {suit: Suit::Spades, value: Value::Nine});
+        dealer.table.players[0].add_card(Card {suit: Suit::Hearts, value: Value::King});
 
         // give player 2 a pair of 2s and 3 random cards
-        dealer.players[1].add_card(Card {suit: Suit::Spades, value: Value::Two});
-        dealer.players[1].add_card(Card {suit: Suit::Clubs, value: Value::Two});
-        dealer.players[1].add_card(Card {suit: Suit::Hearts, value: Value::Five});
-        dealer.players[1].add_card(Card {suit: Suit::Diamonds, value: Value::Nine});
-        dealer.players[1].add_card(Card {suit: Suit::Spades, value: Value::Queen});
+        dealer.table.players[1].add_card(Card {suit: Suit::Spades, value: Value::Two});
+        dealer.table.players[1].add_card(Card {suit: Suit::Clubs, value: Value::Two});
+        dealer.table.players[1].add_card(Card {suit: Suit::Hearts, value: Value::Five});
+        dealer.table.players[1].add_card(Card {suit: Suit::Diamonds, value: Value::Nine});
+        dealer.table.players[1].add_card(Card {suit: Suit::Spades, value: Value::Queen});
 
         // give player 3 a pair of 5 random cards
-        dealer.players[2].add_card(Card {suit: Suit::Hearts, value: Value::Three});
-        dealer.players[2].add_card(Card {suit: Suit::Diamonds, value: Value::Four});
-        dealer.players[2].add_card(Card {suit: Suit::Clubs, value: Value::Five});
-        dealer.players[2].add_card(Card {suit: Suit::Spades, value: Value::Six});
-        dealer.players[2].add_card(Card {suit: Suit::Hearts, value: Value::Eight});
+        dealer.table.players[2].add_card(Card {suit: Suit::Hearts, value: Value::Three});
+        dealer.table.players[2].add_card(Card {suit: Suit::Diamonds, value: Value::Four});
+        dealer.table.players[2].add_card(Card {suit: Suit::Clubs, value: Value::Five});
+        dealer.table.players[2].add_card(Card {suit: Suit::Spades, value: Value::Six});
+        dealer.table.players[2].add_card(Card {suit: Suit::Hearts, value: Value::Eight});
 
         let winner = dealer.check_for_winning_hand();
         assert_eq!(winner.len(), 1);
@@ -261,30 +745,565 @@ mod tests {
         dealer.add_player(player3);
 
         // give player 1 a pair of 2s and 3 random cards
-        dealer.players[1].add_card(Card {suit: Suit::Hearts, value: Value::Ten});
-        dealer.players[1].add_card(Card {suit: Suit::Hearts, value: Value::Queen});
-        dealer.players[1].add_card(Card {suit: Suit::Hearts, value: Value::King});
-        dealer.players[1].add_card(Card {suit: Suit::Hearts, value: Value::Jack});
-        dealer.players[1].add_card(Card {suit: Suit::Hearts, value: Value::Ace});
+        dealer.table.players[1].add_card(Card {suit: Suit::Hearts, value: Value::Ten});
+        dealer.table.players[1].add_card(Card {suit: Suit::Hearts, value: Value::Queen});
+        dealer.table.players[1].add_card(Card {suit: Suit::Hearts, value: Value::King});
+        dealer.table.players[1].add_card(Card {suit: Suit::Hearts, value: Value::Jack});
+        dealer.table.players[1].add_card(Card {suit: Suit::Hearts, value: Value::Ace});
 
         // give player 2 a pair of 2s and 3 random cards
-        dealer.players[0].add_card(Card {suit: Suit::Spades, value: Value::King});
-        dealer.players[0].add_card(Card {suit: Suit::Spades, value: Value::Ten});
-        dealer.players[0].add_card(Card {suit: Suit::Spades, value: Value::Nine});
-        dealer.players[0].add_card(Card {suit: Suit::Spades, value: Value::Jack});
-        dealer.players[0].add_card(Card {suit: Suit::Spades, value: Value::Queen});
+        dealer.table.players[0].add_card(Card {suit: Suit::Spades, value: Value::King});
+        dealer.table.players[0].add_card(Card {suit: Suit::Spades, value: Value::Ten});
+        dealer.table.players[0].add_card(Card {suit: Suit::Spades, value: Value::Nine});
+        dealer.table.players[0].add_card(Card {suit: Suit::Spades, value: Value::Jack});
+        dealer.table.players[0].add_card(Card {suit: Suit::Spades, value: Value::Queen});
 
         // give player 3 a pair of 5 random cards
-        dealer.players[2].add_card(Card {suit: Suit::Hearts, value: Value::Three});
-        dealer.players[2].add_card(Card {suit: Suit::Diamonds, value: Value::Four});
-        dealer.players[2].add_card(Card {suit: Suit::Clubs, value: Value::Five});
-        dealer.players[2].add_card(Card {suit: Suit::Spades, value: Value::Six});
-        dealer.players[2].add_card(Card {suit: Suit::Hearts, value: Value::Seven});
+        dealer.table.players[2].add_card(Card {suit: Suit::Hearts, value: Value::Three});
+        dealer.table.players[2].add_card(Card {suit: Suit::Diamonds, value: Value::Four});
+        dealer.table.players[2].add_card(Card {suit: Suit::Clubs, value: Value::Five});
+        dealer.table.players[2].add_card(Card {suit: Suit::Spades, value: Value::Six});
+        dealer.table.players[2].add_card(Card {suit: Suit::Hearts, value: Value::Seven});
 
         let winner = dealer.check_for_winning_hand();
         assert_eq!(winner.len(), 1);
         assert_eq!(winner[0].get_name(), "Jane");
+        assert_eq!(dealer.hand_evaluation(1).unwrap().rank(), HandRank::RoyalFlush);
+        assert_eq!(dealer.hand_evaluation(0).unwrap().to_string(), "Straight Flush, King high");
     }
 
-}
+    #[test]
+    fn test_deal_hands() {
+        let mut dealer = FiveDrawDealer::new();
+        for (i, name) in ["John", "Jane", "Bob"].iter().enumerate() {
+            let mut player = Player::new(name.to_string(), i as u32, 1000);
+            player.set_active(true);
+            dealer.add_player(player);
+        }
+        dealer.table.players[2].set_active(false);
+
+        dealer.table.deck = Deck::shuffled(1234);
+        dealer.deal_hands();
+        assert_eq!(dealer.table.players[0].get_hand().len(), HAND_SIZE);
+        assert_eq!(dealer.table.players[1].get_hand().len(), HAND_SIZE);
+        assert_eq!(dealer.table.players[2].get_hand().len(), 0);
+        assert_eq!(dealer.cards_in_deck(), 52 - 2 * HAND_SIZE);
+        for card in dealer.table.players[0].get_hand() {
+            assert!(!dealer.table.players[1].get_hand().contains(card));
+        }
+
+        // the same seed deals the same hands
+        let first_hand = dealer.table.players[0].get_hand().clone();
+        dealer.table.deck = Deck::shuffled(1234);
+        dealer.deal_hands();
+        assert_eq!(dealer.table.players[0].get_hand(), &first_hand);
+    }
+
+    fn setup_table(stacks: &[u32]) -> FiveDrawDealer {
+        let mut dealer = FiveDrawDealer::with_stakes(Stakes { ante: 1, ..Stakes::default() });
+        for (i, &chips) in stacks.iter().enumerate() {
+            dealer.add_player(Player::new(format!("Player {}", i), i as u32, chips));
+        }
+        dealer
+    }
+
+    fn call_around(dealer: &mut FiveDrawDealer) {
+        while !dealer.is_betting_complete() {
+            let seat = dealer.get_current_player();
+            let action = if dealer.table.players[seat].get_current_bet() < dealer.get_current_bet() {
+                PlayerAction::Call
+            } else {
+                PlayerAction::Check
+            };
+            dealer.apply_action(seat, action).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_start_hand_posts_antes_and_blinds() {
+        let mut dealer = setup_table(&[1000, 1000, 1000]);
+        dealer.start_hand(1).unwrap();
+
+        assert_eq!(dealer.get_round(), Round::FirstBetting);
+        assert_eq!(dealer.get_pot(), 18);
+        assert_eq!(dealer.table.players[1].get_current_bet(), 5);
+        assert_eq!(dealer.table.players[2].get_current_bet(), 10);
+        assert_eq!(dealer.table.players[2].get_total_chips(), 989);
+        assert_eq!(dealer.get_current_bet(), 10);
+        assert_eq!(dealer.get_current_player(), 0);
+        for player in dealer.get_players() {
+            assert_eq!(player.get_hand().len(), HAND_SIZE);
+        }
+    }
+
+    #[test]
+    fn test_heads_up_button_posts_small_blind() {
+        let mut dealer = setup_table(&[1000, 1000]);
+        dealer.start_hand(1).unwrap();
+        assert_eq!(dealer.table.players[0].get_current_bet(), 5);
+        assert_eq!(dealer.table.players[1].get_current_bet(), 10);
+        assert_eq!(dealer.get_current_player(), 0);
+    }
+
+    #[test]
+    fn test_start_hand_needs_two_players() {
+        let mut dealer = setup_table(&[1000, 0]);
+        assert_eq!(dealer.start_hand(1), Err(DealerError::NotEnoughPlayers));
+    }
+
+    #[test]
+    fn test_illegal_transitions() {
+        let mut dealer = setup_table(&[1000, 1000, 1000]);
+        assert_eq!(dealer.advance(), Err(DealerError::CannotAdvance(Round::Waiting)));
+        assert_eq!(dealer.showdown(), Err(DealerError::WrongRound { expected: Round::Showdown, actual: Round::Waiting }));
+
+        dealer.start_hand(1).unwrap();
+        assert_eq!(dealer.start_hand(2), Err(DealerError::WrongRound { expected: Round::Waiting, actual: Round::FirstBetting }));
+        assert_eq!(dealer.draw(0, &[]), Err(DealerError::WrongRound { expected: Round::Draw, actual: Round::FirstBetting }));
+
+        assert_eq!(dealer.advance(), Err(DealerError::BettingNotComplete));
+        call_around(&mut dealer);
+        dealer.advance().unwrap();
+        assert_eq!(dealer.advance(), Err(DealerError::DrawNotFinished));
+        assert_eq!(dealer.apply_action(1, PlayerAction::Check), Err(DealerError::NotBettingRound(Round::Draw)));
+    }
+
+    #[test]
+    fn test_draw_validation() {
+        let mut dealer = setup_table(&[1000, 1000, 1000]);
+        dealer.start_hand(1).unwrap();
+        call_around(&mut dealer);
+        dealer.advance().unwrap();
+        assert_eq!(dealer.get_current_player(), 1);
+
+        assert_eq!(dealer.draw(0, &[]), Err(DealerError::NotPlayersTurn { seat: 0, current: 1 }));
+        assert_eq!(dealer.draw(7, &[]), Err(DealerError::InvalidSeat(7)));
+
+        let not_held = dealer.table.players[0].get_hand()[0].clone();
+        assert_eq!(dealer.draw(1, std::slice::from_ref(&not_held)), Err(DealerError::CardNotInHand(not_held)));
+
+        let held = dealer.table.players[1].get_hand()[0].clone();
+        assert_eq!(dealer.draw(1, &[held.clone(), held.clone()]), Err(DealerError::CardNotInHand(held)));
+    }
+
+    #[test]
+    fn test_draw_replaces_discards() {
+        let mut dealer = setup_table(&[1000, 1000, 1000]);
+        dealer.start_hand(1).unwrap();
+        call_around(&mut dealer);
+        dealer.advance().unwrap();
+
+        let discards: Vec<Card> = dealer.table.players[1].get_hand()[..3].to_vec();
+        let kept: Vec<Card> = dealer.table.players[1].get_hand()[3..].to_vec();
+        dealer.draw(1, &discards).unwrap();
+
+        let hand = dealer.table.players[1].get_hand();
+        assert_eq!(hand.len(), HAND_SIZE);
+        for card in &kept {
+            assert!(hand.contains(card));
+        }
+        for card in &discards {
+            assert!(!hand.contains(card));
+        }
+        assert_eq!(dealer.get_current_player(), 2);
+    }
+
+    #[test]
+    fn test_no_second_draw() {
+        let mut dealer = setup_table(&[1000, 1000, 1000]);
+        dealer.start_hand(1).unwrap();
+        call_around(&mut dealer);
+        dealer.advance().unwrap();
+        for seat in [1, 2, 0] {
+            dealer.draw(seat, &[]).unwrap();
+        }
+
+        // the turn doesn't wrap back round to seat 1
+        assert_eq!(dealer.get_current_player(), 0);
+        let hand = dealer.table.players[1].get_hand().clone();
+        assert_eq!(dealer.draw(1, &hand), Err(DealerError::NotPlayersTurn { seat: 1, current: 0 }));
+        assert_eq!(dealer.draw(0, &[]), Err(DealerError::AlreadyDrawn(0)));
+        assert_eq!(dealer.view_for(Viewer::Seat(0)).to_act, None);
+        assert_eq!(dealer.advance(), Ok(Round::SecondBetting));
+    }
+
+    #[test]
+    fn test_full_hand() {
+        let mut dealer = setup_table(&[1000, 1000, 1000]);
+        dealer.start_hand(9).unwrap();
+        call_around(&mut dealer);
+        assert_eq!(dealer.advance(), Ok(Round::Draw));
+        assert_eq!(dealer.get_pot(), 33);
+
+        dealer.draw(1, &[]).unwrap();
+        let discard = dealer.table.players[2].get_hand()[0].clone();
+        dealer.draw(2, &[discard]).unwrap();
+        dealer.draw(0, &[]).unwrap();
+        assert_eq!(dealer.advance(), Ok(Round::SecondBetting));
+        assert_eq!(dealer.get_current_player(), 1);
+        dealer.apply_action(1, PlayerAction::Bet(20)).unwrap();
+        call_around(&mut dealer);
+        assert_eq!(dealer.advance(), Ok(Round::Showdown));
+
+        let awards = dealer.showdown().unwrap();
+        assert!(!awards.is_empty());
+        assert_eq!(awards.iter().map(|(_, amount)| amount).sum::<u32>(), 93);
+        assert_eq!(dealer.get_pot(), 0);
+        let total: u32 = dealer.get_players().iter().map(|p| p.get_total_chips()).sum();
+        assert_eq!(total, 3000);
+
+        dealer.end_hand().unwrap();
+        assert_eq!(dealer.get_round(), Round::Waiting);
+        assert_eq!(dealer.get_button(), 1);
+        assert!(dealer.get_players().iter().all(|p| p.get_hand().is_empty()));
+        dealer.start_hand(10).unwrap();
+    }
+
+    #[test]
+    fn test_split_pot_odd_chip() {
+        let mut dealer = setup_table(&[1000, 1000, 1000]);
+        dealer.round = Round::Showdown;
+        dealer.table.button = 1;
+        dealer.table.pot = 25;
+        dealer.table.contributions = vec![8, 9, 8];
+        let chop = [
+            [(Suit::Hearts, Value::Ace), (Suit::Hearts, Value::King), (Suit::Clubs, Value::Nine), (Suit::Clubs, Value::Seven), (Suit::Spades, Value::Two)],
+            [(Suit::Diamonds, Value::Three), (Suit::Hearts, Value::Four), (Suit::Clubs, Value::Six), (Suit::Clubs, Value::Eight), (Suit::Spades, Value::Ten)],
+            [(Suit::Spades, Value::Ace), (Suit::Spades, Value::King), (Suit::Diamonds, Value::Nine), (Suit::Diamonds, Value::Seven), (Suit::Hearts, Value::Two)],
+        ];
+        for (seat, hand) in chop.iter().enumerate() {
+            // seat 1 put in the odd chip and then folded
+            dealer.table.players[seat].set_active(seat != 1);
+            for (suit, value) in hand.iter() {
+                dealer.table.players[seat].add_card(Card { suit: suit.clone(), value: value.clone() });
+            }
+        }
+
+        // seat 2 is first left of the button and gets the odd chip
+        let awards = dealer.showdown().unwrap();
+        assert_eq!(awards, vec![(2, 13), (0, 12)]);
+    }
+
+    fn give_hand(dealer: &mut FiveDrawDealer, seat: usize, hand: &[(Suit, Value)]) {
+        dealer.table.players[seat].clear_hand();
+        for (suit, value) in hand {
+            dealer.table.players[seat].add_card(Card { suit: suit.clone(), value: value.clone() });
+        }
+    }
+
+    #[test]
+    fn test_short_all_in_only_wins_main_pot() {
+        let mut dealer = setup_table(&[1000, 1000, 101]);
+        dealer.start_hand(11).unwrap();
+        // seat 2 is the big blind and goes all-in for 100 in total
+        dealer.apply_action(0, PlayerAction::Raise(300)).unwrap();
+        dealer.apply_action(1, PlayerAction::Call).unwrap();
+        dealer.apply_action(2, PlayerAction::AllIn).unwrap();
+        dealer.advance().unwrap();
+        for seat in [1, 2, 0] {
+            dealer.draw(seat, &[]).unwrap();
+        }
+        dealer.advance().unwrap();
+        dealer.apply_action(1, PlayerAction::Check).unwrap();
+        dealer.apply_action(0, PlayerAction::Check).unwrap();
+        dealer.advance().unwrap();
 
+        assert_eq!(dealer.get_pots(), vec![
+            Pot { amount: 303, eligible: vec![0, 1, 2] },
+            Pot { amount: 400, eligible: vec![0, 1] },
+        ]);
+
+        // the short stack holds the best hand, seat 1 the second best
+        give_hand(&mut dealer, 2, &[(Suit::Hearts, Value::Ace), (Suit::Clubs, Value::Ace), (Suit::Spades, Value::Ace), (Suit::Hearts, Value::Two), (Suit::Clubs, Value::Three)]);
+        give_hand(&mut dealer, 1, &[(Suit::Hearts, Value::King), (Suit::Clubs, Value::King), (Suit::Spades, Value::Four), (Suit::Hearts, Value::Five), (Suit::Clubs, Value::Seven)]);
+        give_hand(&mut dealer, 0, &[(Suit::Diamonds, Value::Queen), (Suit::Clubs, Value::Queen), (Suit::Spades, Value::Nine), (Suit::Diamonds, Value::Five), (Suit::Diamonds, Value::Seven)]);
+
+        let awards = dealer.showdown().unwrap();
+        assert_eq!(awards, vec![(2, 303), (1, 400)]);
+        assert_eq!(dealer.table.players[2].get_total_chips(), 303);
+        assert_eq!(dealer.table.players[1].get_total_chips(), 1099);
+        assert_eq!(dealer.table.players[0].get_total_chips(), 699);
+    }
+
+    #[test]
+    fn test_side_pot_split_between_tied_hands() {
+        let mut dealer = setup_table(&[1000, 1000, 1000]);
+        dealer.round = Round::Showdown;
+        dealer.table.pot = 301;
+        dealer.table.contributions = vec![150, 50, 101];
+        for seat in 0..3 {
+            dealer.table.players[seat].set_active(true);
+        }
+        give_hand(&mut dealer, 1, &[(Suit::Hearts, Value::Ace), (Suit::Clubs, Value::Ace), (Suit::Spades, Value::Ace), (Suit::Hearts, Value::Two), (Suit::Clubs, Value::Three)]);
+        give_hand(&mut dealer, 0, &[(Suit::Hearts, Value::King), (Suit::Clubs, Value::King), (Suit::Spades, Value::Four), (Suit::Hearts, Value::Five), (Suit::Clubs, Value::Seven)]);
+        give_hand(&mut dealer, 2, &[(Suit::Diamonds, Value::King), (Suit::Spades, Value::King), (Suit::Diamonds, Value::Four), (Suit::Diamonds, Value::Five), (Suit::Spades, Value::Seven)]);
+
+        // main pot of 150 to seat 1, side pot of 102 split, the leftover 49 back to seat 0
+        let awards = dealer.showdown().unwrap();
+        assert_eq!(awards, vec![(1, 150), (2, 51), (0, 100)]);
+    }
+
+    #[test]
+    fn test_everyone_folds_to_big_blind() {
+        let mut dealer = setup_table(&[1000, 1000, 1000]);
+        dealer.start_hand(3).unwrap();
+        assert_eq!(dealer.apply_action(1, PlayerAction::Fold), Err(DealerError::Betting(BettingError::NotPlayersTurn { seat: 1, current: 0 })));
+
+        dealer.apply_action(0, PlayerAction::Fold).unwrap();
+        assert!(dealer.table.players[0].get_hand().is_empty());
+        dealer.apply_action(1, PlayerAction::Fold).unwrap();
+
+        assert_eq!(dealer.get_round(), Round::Complete);
+        assert_eq!(dealer.get_pot(), 0);
+        // big blind wins the antes and the small blind
+        assert_eq!(dealer.table.players[2].get_total_chips(), 1007);
+        assert_eq!(dealer.table.players[1].get_total_chips(), 994);
+        dealer.end_hand().unwrap();
+    }
+
+    #[test]
+    fn test_all_in_player_skips_second_betting_round() {
+        let mut dealer = setup_table(&[1000, 1000, 51]);
+        dealer.start_hand(5).unwrap();
+        dealer.apply_action(0, PlayerAction::Raise(40)).unwrap();
+        dealer.apply_action(1, PlayerAction::Fold).unwrap();
+        dealer.apply_action(2, PlayerAction::AllIn).unwrap();
+        dealer.apply_action(0, PlayerAction::Call).unwrap();
+        assert!(dealer.is_betting_complete());
+
+        dealer.advance().unwrap();
+        dealer.draw(2, &[]).unwrap();
+        dealer.draw(0, &[]).unwrap();
+        dealer.advance().unwrap();
+        assert!(dealer.is_betting_complete());
+        assert_eq!(dealer.advance(), Ok(Round::Showdown));
+    }
+
+    #[test]
+    fn test_fixed_limit_uses_big_bet_after_draw() {
+        let structure = BettingStructure::FixedLimit { small_bet: 10, big_bet: 20, max_raises: 3 };
+        let mut dealer = FiveDrawDealer::with_config(TableConfig { structure, ..TableConfig::default() });
+        for i in 0..3 {
+            dealer.add_player(Player::new(format!("Player {}", i), i, 1000));
+        }
+        dealer.start_hand(4).unwrap();
+        assert_eq!(dealer.raise_range(), Some((20, 20)));
+        call_around(&mut dealer);
+        dealer.advance().unwrap();
+        for seat in [1, 2, 0] {
+            dealer.draw(seat, &[]).unwrap();
+        }
+        dealer.advance().unwrap();
+        assert_eq!(dealer.raise_range(), Some((20, 20)));
+        assert_eq!(dealer.apply_action(1, PlayerAction::Bet(10)), Err(DealerError::Betting(BettingError::BetTooSmall { min: 20 })));
+    }
+
+    #[test]
+    fn test_pot_limit_raise_includes_antes() {
+        let mut dealer = FiveDrawDealer::with_config(TableConfig {
+            stakes: Stakes { ante: 1, ..Stakes::default() },
+            structure: BettingStructure::PotLimit,
+            ..TableConfig::default()
+        });
+        for i in 0..3 {
+            dealer.add_player(Player::new(format!("Player {}", i), i, 1000));
+        }
+        dealer.start_hand(4).unwrap();
+        // 18 in the pot, call 10 and raise 28 more
+        assert_eq!(dealer.raise_range(), Some((20, 38)));
+    }
+
+    fn setup_triple_draw(game: DrawGame, structure: BettingStructure) -> FiveDrawDealer {
+        let mut dealer = FiveDrawDealer::with_game(game, TableConfig { structure, ..TableConfig::default() });
+        for i in 0..3 {
+            dealer.add_player(Player::new(format!("Player {}", i), i, 1000));
+        }
+        dealer
+    }
+
+    fn stand_pat_around(dealer: &mut FiveDrawDealer) {
+        for seat in [1, 2, 0] {
+            dealer.draw(seat, &[]).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_triple_draw_has_three_draws_and_four_betting_rounds() {
+        let structure = BettingStructure::FixedLimit { small_bet: 10, big_bet: 20, max_raises: 3 };
+        let mut dealer = setup_triple_draw(DrawGame::DeuceToSevenTripleDraw, structure);
+        dealer.start_hand(6).unwrap();
+
+        // small bets before and after the first draw, big bets after the second and third
+        let rounds = [Round::SecondBetting, Round::ThirdBetting, Round::FourthBetting];
+        let bets = [10, 20, 20];
+        for (draw, (&round, &bet)) in rounds.iter().zip(bets.iter()).enumerate() {
+            call_around(&mut dealer);
+            assert_eq!(dealer.advance(), Ok(Round::Draw));
+            assert_eq!(dealer.get_draws_completed(), draw);
+            let discard = dealer.table.players[1].get_hand()[0].clone();
+            dealer.draw(1, &[discard]).unwrap();
+            dealer.draw(2, &[]).unwrap();
+            dealer.draw(0, &[]).unwrap();
+            assert_eq!(dealer.advance(), Ok(round));
+            assert_eq!(dealer.raise_range(), Some((bet, bet)));
+            dealer.apply_action(1, PlayerAction::Bet(bet)).unwrap();
+        }
+        call_around(&mut dealer);
+        assert_eq!(dealer.advance(), Ok(Round::Showdown));
+        assert_eq!(dealer.get_draws_completed(), 3);
+
+        // ten a head preflop, then 10, 20 and 20 called three ways
+        let awards = dealer.showdown().unwrap();
+        assert_eq!(awards.iter().map(|(_, amount)| amount).sum::<u32>(), 180);
+        dealer.end_hand().unwrap();
+        dealer.start_hand(7).unwrap();
+        assert_eq!(dealer.get_draws_completed(), 0);
+    }
+
+    #[test]
+    fn test_five_card_draw_has_one_draw() {
+        let mut dealer = setup_table(&[1000, 1000, 1000]);
+        assert_eq!(dealer.get_game().draws(), 1);
+        dealer.start_hand(2).unwrap();
+        call_around(&mut dealer);
+        dealer.advance().unwrap();
+        stand_pat_around(&mut dealer);
+        assert_eq!(dealer.advance(), Ok(Round::SecondBetting));
+        call_around(&mut dealer);
+        assert_eq!(dealer.advance(), Ok(Round::Showdown));
+    }
+
+    fn setup_lowball_showdown(game: DrawGame) -> FiveDrawDealer {
+        let mut dealer = setup_triple_draw(game, BettingStructure::NoLimit);
+        dealer.round = Round::Showdown;
+        dealer.table.pot = 30;
+        dealer.table.contributions = vec![10, 10, 10];
+        for seat in 0..3 {
+            dealer.table.players[seat].set_active(true);
+        }
+        // the wheel, a seven-five low and a king-high hand
+        give_hand(&mut dealer, 0, &[(Suit::Hearts, Value::Ace), (Suit::Diamonds, Value::Two), (Suit::Clubs, Value::Three), (Suit::Spades, Value::Four), (Suit::Hearts, Value::Five)]);
+        give_hand(&mut dealer, 1, &[(Suit::Clubs, Value::Seven), (Suit::Hearts, Value::Five), (Suit::Diamonds, Value::Four), (Suit::Clubs, Value::Three), (Suit::Spades, Value::Two)]);
+        give_hand(&mut dealer, 2, &[(Suit::Spades, Value::King), (Suit::Hearts, Value::Queen), (Suit::Hearts, Value::Jack), (Suit::Diamonds, Value::Nine), (Suit::Clubs, Value::Eight)]);
+        dealer
+    }
+
+    #[test]
+    fn test_deuce_to_seven_lowest_hand_wins() {
+        let mut dealer = setup_lowball_showdown(DrawGame::DeuceToSevenTripleDraw);
+        // the wheel is only ace high, so seven-five wins
+        assert_eq!(dealer.lowball_hand(0).unwrap().to_string(), "A-5-4-3-2");
+        assert_eq!(dealer.check_for_winning_hand()[0].get_name(), "Player 1");
+        assert_eq!(dealer.showdown().unwrap(), vec![(1, 30)]);
+    }
+
+    #[test]
+    fn test_ace_to_five_lowest_hand_wins() {
+        let mut dealer = setup_lowball_showdown(DrawGame::AceToFiveTripleDraw);
+        assert_eq!(dealer.lowball_hand(0).unwrap().to_string(), "5-4-3-2-A");
+        assert_eq!(dealer.showdown().unwrap(), vec![(0, 30)]);
+    }
+
+    #[test]
+    fn test_badugi_deals_and_draws_four_cards() {
+        let mut dealer = setup_triple_draw(DrawGame::Badugi, BettingStructure::NoLimit);
+        dealer.start_hand(8).unwrap();
+        for player in dealer.get_players() {
+            assert_eq!(player.get_hand().len(), 4);
+        }
+        assert_eq!(dealer.cards_in_deck(), 52 - 12);
+
+        call_around(&mut dealer);
+        dealer.advance().unwrap();
+        let hand = dealer.table.players[1].get_hand().clone();
+        let mut five = hand.clone();
+        five.push(dealer.table.players[2].get_hand()[0].clone());
+        assert_eq!(dealer.draw(1, &five), Err(DealerError::TooManyDiscards(5)));
+        dealer.draw(1, &hand).unwrap();
+        assert_eq!(dealer.table.players[1].get_hand().len(), 4);
+        assert!(hand.iter().all(|card| !dealer.table.players[1].get_hand().contains(card)));
+    }
+
+    #[test]
+    fn test_badugi_showdown() {
+        let mut dealer = setup_triple_draw(DrawGame::Badugi, BettingStructure::NoLimit);
+        dealer.round = Round::Showdown;
+        dealer.table.pot = 30;
+        dealer.table.contributions = vec![10, 10, 10];
+        for seat in 0..3 {
+            dealer.table.players[seat].set_active(true);
+        }
+        // a three-card wheel, a king-high badugi and a two-card hand
+        give_hand(&mut dealer, 0, &[(Suit::Hearts, Value::Ace), (Suit::Diamonds, Value::Two), (Suit::Clubs, Value::Three), (Suit::Clubs, Value::Four)]);
+        give_hand(&mut dealer, 1, &[(Suit::Hearts, Value::King), (Suit::Diamonds, Value::Queen), (Suit::Clubs, Value::Jack), (Suit::Spades, Value::Ten)]);
+        give_hand(&mut dealer, 2, &[(Suit::Hearts, Value::Two), (Suit::Hearts, Value::Three), (Suit::Spades, Value::Ace), (Suit::Spades, Value::Four)]);
+
+        assert_eq!(dealer.badugi_hand(0).unwrap().to_string(), "3-2-A");
+        assert!(dealer.badugi_hand(1).unwrap().is_badugi());
+        assert_eq!(dealer.badugi_hand(2).unwrap().size(), 2);
+        assert_eq!(dealer.check_for_winning_hand()[0].get_name(), "Player 1");
+        assert_eq!(dealer.showdown().unwrap(), vec![(1, 30)]);
+    }
+
+    #[test]
+    fn test_deuces_wild_showdown() {
+        let wild = WildCards::none().with_rank(Value::Two);
+        let mut dealer = FiveDrawDealer::with_config(TableConfig { wild, ..TableConfig::default() });
+        for i in 0..2 {
+            dealer.add_player(Player::new(format!("Player {}", i), i, 1000));
+        }
+        dealer.round = Round::Showdown;
+        dealer.table.pot = 20;
+        dealer.table.contributions = vec![10, 10];
+        dealer.table.players[0].set_active(true);
+        dealer.table.players[1].set_active(true);
+        // the deuce makes four kings, which beats the natural full house
+        give_hand(&mut dealer, 0, &[(Suit::Hearts, Value::King), (Suit::Diamonds, Value::King), (Suit::Clubs, Value::King), (Suit::Spades, Value::Two), (Suit::Hearts, Value::Five)]);
+        give_hand(&mut dealer, 1, &[(Suit::Hearts, Value::Ace), (Suit::Diamonds, Value::Ace), (Suit::Clubs, Value::Ace), (Suit::Spades, Value::Nine), (Suit::Hearts, Value::Nine)]);
+
+        assert_eq!(dealer.hand_evaluation(0).unwrap().to_string(), "Four of a Kind, Kings");
+        assert_eq!(dealer.showdown().unwrap(), vec![(0, 20)]);
+    }
+
+    #[test]
+    fn test_joker_is_shuffled_into_the_deck() {
+        let wild = WildCards::none().with_joker(Joker::Bug);
+        let mut dealer = FiveDrawDealer::with_config(TableConfig { wild, ..TableConfig::default() });
+        for i in 0..3 {
+            dealer.add_player(Player::new(format!("Player {}", i), i, 1000));
+        }
+        dealer.start_hand(1).unwrap();
+        assert_eq!(dealer.cards_in_deck(), 53 - 3 * HAND_SIZE);
+
+        // lowball draw games keep to the natural deck
+        let mut lowball = FiveDrawDealer::with_game(DrawGame::DeuceToSevenTripleDraw, TableConfig { wild, ..TableConfig::default() });
+        for i in 0..3 {
+            lowball.add_player(Player::new(format!("Player {}", i), i, 1000));
+        }
+        lowball.start_hand(1).unwrap();
+        assert_eq!(lowball.cards_in_deck(), 52 - 3 * HAND_SIZE);
+    }
+
+    #[test]
+    fn evaluate_wheel_vs_six_high_straight() {
+        let mut dealer = setup_table(&[1000, 1000]);
+        give_hand(&mut dealer, 0, &[(Suit::Hearts, Value::Ace), (Suit::Diamonds, Value::Two), (Suit::Clubs, Value::Three), (Suit::Spades, Value::Four), (Suit::Hearts, Value::Five)]);
+        give_hand(&mut dealer, 1, &[(Suit::Clubs, Value::Two), (Suit::Hearts, Value::Three), (Suit::Diamonds, Value::Four), (Suit::Clubs, Value::Five), (Suit::Spades, Value::Six)]);
+
+        let winner = dealer.check_for_winning_hand();
+        assert_eq!(winner.len(), 1);
+        assert_eq!(winner[0].get_name(), "Player 1");
+    }
+
+    #[test]
+    fn evaluate_steel_wheel_is_not_royal() {
+        let mut dealer = setup_table(&[1000, 1000]);
+        give_hand(&mut dealer, 0, &[(Suit::Hearts, Value::Ace), (Suit::Hearts, Value::Two), (Suit::Hearts, Value::Three), (Suit::Hearts, Value::Four), (Suit::Hearts, Value::Five)]);
+        give_hand(&mut dealer, 1, &[(Suit::Clubs, Value::Two), (Suit::Clubs, Value::Three), (Suit::Clubs, Value::Four), (Suit::Clubs, Value::Five), (Suit::Clubs, Value::Six)]);
+
+        assert_eq!(dealer.hand_evaluation(0).unwrap().rank(), HandRank::StraightFlush);
+        let winner = dealer.check_for_winning_hand();
+        assert_eq!(winner.len(), 1);
+        assert_eq!(winner[0].get_name(), "Player 1");
+    }
+}
@@ -0,0 +1,689 @@
+use std::cmp::Reverse;
+
+use poker_common::action::PlayerAction;
+use poker_common::card::{Card, Deck};
+use poker_common::evaluator::{self, HandRanking, HandStrength};
+use poker_common::hand::HandEvaluation;
+use poker_common::low::{self, LowHand};
+use poker_common::player::Player;
+
+use crate::dealer::{Dealer, DealerError, LegalAction, Round, Stakes, TableConfig, TableView, Viewer};
+use crate::pot::Pot;
+use crate::table::Table;
+use crate::variant::Variant;
+
+/// The community card games dealt by [`HoldemDealer`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HoldemGame {
+    /// Two hole cards, best five of seven.
+    TexasHoldem,
+    /// Four hole cards, exactly two of them with exactly three from the board.
+    Omaha,
+    /// Omaha with the pot split between the best high and the best
+    /// eight-or-better low.
+    OmahaHiLo,
+    /// Hold'em with a 36 card deck from the six up, where a flush beats a
+    /// full house and, if the table chooses, three of a kind beats a straight.
+    ShortDeck { trips_beat_straight: bool },
+}
+
+impl HoldemGame {
+    pub fn hole_cards(&self) -> usize {
+        match self {
+            HoldemGame::TexasHoldem | HoldemGame::ShortDeck { .. } => 2,
+            HoldemGame::Omaha | HoldemGame::OmahaHiLo => 4,
+        }
+    }
+
+    /// How high hands rank, which also decides the lowest card in the deck.
+    pub fn ranking(&self) -> HandRanking {
+        match self {
+            HoldemGame::ShortDeck { trips_beat_straight } => HandRanking::short_deck(*trips_beat_straight),
+            _ => HandRanking::standard(),
+        }
+    }
+}
+
+/// Deals the hold'em family: hole cards for each player, then a flop, turn
+/// and river shared by everyone, with a betting round before and after each.
+pub struct HoldemDealer {
+    table: Table,
+    game: HoldemGame,
+    round: Round,
+    board: Vec<Card>,
+}
+
+impl HoldemDealer {
+    pub fn new() -> Self {
+        Self::with_config(TableConfig::default())
+    }
+
+    pub fn with_stakes(stakes: Stakes) -> Self {
+        Self::with_config(TableConfig { stakes, ..TableConfig::default() })
+    }
+
+    pub fn with_config(config: TableConfig) -> Self {
+        Self::with_game(HoldemGame::TexasHoldem, config)
+    }
+
+    pub fn with_game(game: HoldemGame, config: TableConfig) -> Self {
+        HoldemDealer {
+            table: Table::new(config),
+            game,
+            round: Round::Waiting,
+            board: Vec::new(),
+        }
+    }
+
+    pub fn add_player(&mut self, player: Player) {
+        self.table.add_player(player);
+    }
+
+    pub fn get_players(&self) -> &Vec<Player> {
+        &self.table.players
+    }
+
+    pub fn get_config(&self) -> TableConfig {
+        self.table.config
+    }
+
+    pub fn get_game(&self) -> HoldemGame {
+        self.game
+    }
+
+    /// The community cards dealt so far.
+    pub fn get_board(&self) -> &[Card] {
+        &self.board
+    }
+
+    pub fn get_pot(&self) -> u32 {
+        self.table.pot
+    }
+
+    pub fn get_pots(&self) -> Vec<Pot> {
+        self.table.pots()
+    }
+
+    pub fn get_current_bet(&self) -> u32 {
+        self.table.current_bet()
+    }
+
+    pub fn get_current_player(&self) -> usize {
+        self.table.current_player
+    }
+
+    pub fn get_button(&self) -> usize {
+        self.table.button
+    }
+
+    pub fn get_round(&self) -> Round {
+        self.round
+    }
+
+    pub fn cards_in_deck(&self) -> usize {
+        self.table.deck.remaining()
+    }
+
+    pub fn raise_range(&self) -> Option<(u32, u32)> {
+        self.table.raise_range()
+    }
+
+    pub fn is_betting_complete(&self) -> bool {
+        self.table.is_betting_complete()
+    }
+
+    /// Starts a new hand: posts antes and blinds, deals the hole cards
+    /// and opens the preflop betting with the player left of the big blind.
+    pub fn start_hand(&mut self, seed: u64) -> Result<(), DealerError> {
+        let mut deck = Deck::with_lowest(self.game.ranking().lowest());
+        deck.shuffle(seed);
+        self.start_hand_from(deck)
+    }
+
+    /// Starts a new hand as [`HoldemDealer::start_hand`] does, dealing from
+    /// `deck` as it stands.
+    pub fn start_hand_from(&mut self, deck: Deck) -> Result<(), DealerError> {
+        self.expect_round(Round::Waiting)?;
+        self.table.begin_hand(deck)?;
+        self.board.clear();
+
+        self.table.post_antes();
+        // heads up, the button posts the small blind and acts first preflop
+        let big_blind_seat = self.table.post_blinds();
+
+        // one card at a time, starting left of the button
+        let first = self.table.next_active(self.table.button);
+        for _ in 0..self.game.hole_cards() {
+            let mut seat = first;
+            loop {
+                self.table.deal_to(seat)?;
+                seat = self.table.next_active(seat);
+                if seat == first {
+                    break;
+                }
+            }
+        }
+
+        let first_to_act = self.table.next_active(big_blind_seat);
+        self.table.open_betting(first_to_act, self.table.config.stakes.big_blind, false);
+        self.round = Round::Preflop;
+        Ok(())
+    }
+
+    /// Applies a betting action for the player whose turn it is and returns
+    /// whether the betting round is now complete. If everyone else folds,
+    /// the last player is awarded the pot and the hand is over.
+    pub fn apply_action(&mut self, seat: usize, action: PlayerAction) -> Result<bool, DealerError> {
+        if !matches!(self.round, Round::Preflop | Round::Flop | Round::Turn | Round::River) {
+            return Err(DealerError::NotBettingRound(self.round));
+        }
+        let complete = self.table.apply_action(seat, action)?;
+        if self.table.award_if_uncontested() {
+            self.round = Round::Complete;
+        }
+        Ok(complete)
+    }
+
+    /// Closes the betting on the current street, burns a card and deals the
+    /// next street. After the river the hand goes to showdown.
+    pub fn advance(&mut self) -> Result<Round, DealerError> {
+        let (next, cards) = match self.round {
+            Round::Preflop => (Round::Flop, 3),
+            Round::Flop => (Round::Turn, 1),
+            Round::Turn => (Round::River, 1),
+            Round::River => (Round::Showdown, 0),
+            round => return Err(DealerError::CannotAdvance(round)),
+        };
+        self.table.end_betting_round()?;
+        self.round = next;
+        if next == Round::Showdown {
+            return Ok(next);
+        }
+
+        self.table.burn()?;
+        for _ in 0..cards {
+            let card = self.table.deal_card()?;
+            self.board.push(card);
+        }
+        // fixed-limit games bet the big bet on the turn and river
+        let first = self.table.next_active(self.table.button);
+        self.table.open_betting(first, 0, matches!(next, Round::Turn | Round::River));
+        Ok(next)
+    }
+
+    /// Awards each pot to the best five card hand eligible for it, made from
+    /// the player's hole cards and the board, and returns each winner's seat
+    /// with their total winnings. In Omaha Hi-Lo the best low takes half.
+    pub fn showdown(&mut self) -> Result<Vec<(usize, u32)>, DealerError> {
+        self.expect_round(Round::Showdown)?;
+        let seats = 0..self.table.players.len();
+        let strengths: Vec<Option<HandStrength>> = seats.clone().map(|seat| self.best_hand(seat)).collect();
+        let awards = if self.game == HoldemGame::OmahaHiLo {
+            // the lowest low wins, so reverse it for the table's highest-key-wins rule
+            let lows: Vec<Option<Reverse<LowHand>>> = seats.map(|seat| self.low_hand(seat).map(Reverse)).collect();
+            self.table.award_hi_lo_pots(&strengths, &lows)
+        } else {
+            self.table.award_pots(&strengths)
+        };
+        self.round = Round::Complete;
+        Ok(awards)
+    }
+
+    /// Clears the finished hand and moves the button for the next one.
+    pub fn end_hand(&mut self) -> Result<(), DealerError> {
+        self.expect_round(Round::Complete)?;
+        for card in self.board.drain(..) {
+            self.table.deck.discard(card);
+        }
+        self.table.end_hand();
+        self.round = Round::Waiting;
+        Ok(())
+    }
+
+    /// The best hand the player in `seat` makes with the board, once there
+    /// are at least five cards to choose from.
+    pub fn hand_evaluation(&self, seat: usize) -> Option<HandEvaluation> {
+        match self.game {
+            HoldemGame::TexasHoldem => HandEvaluation::new(&self.cards_for(seat)?),
+            HoldemGame::ShortDeck { .. } => HandEvaluation::ranked(&self.cards_for(seat)?, &self.game.ranking()),
+            HoldemGame::Omaha | HoldemGame::OmahaHiLo => {
+                HandEvaluation::omaha(self.table.players.get(seat)?.get_hand(), &self.board)
+            }
+        }
+    }
+
+    /// The best eight-or-better low the player in `seat` makes, in Omaha Hi-Lo.
+    pub fn low_hand(&self, seat: usize) -> Option<LowHand> {
+        if self.game != HoldemGame::OmahaHiLo {
+            return None;
+        }
+        low::best_omaha_low(self.table.players.get(seat)?.get_hand(), &self.board)
+    }
+
+    // Folded players have mucked their hole cards and cannot win
+    fn best_hand(&self, seat: usize) -> Option<HandStrength> {
+        match self.game {
+            HoldemGame::TexasHoldem => evaluator::best_hand(&self.cards_for(seat)?).map(|(strength, _)| strength),
+            HoldemGame::ShortDeck { .. } => {
+                self.game.ranking().best_hand(&self.cards_for(seat)?).map(|(strength, _)| strength)
+            }
+            HoldemGame::Omaha | HoldemGame::OmahaHiLo => {
+                let hole = self.table.players.get(seat)?.get_hand();
+                evaluator::best_omaha_hand(hole, &self.board).map(|(strength, _, _)| strength)
+            }
+        }
+    }
+
+    fn cards_for(&self, seat: usize) -> Option<Vec<Card>> {
+        let hole = self.table.players.get(seat)?.get_hand();
+        if hole.is_empty() {
+            return None;
+        }
+        Some(hole.iter().chain(self.board.iter()).cloned().collect())
+    }
+
+    fn expect_round(&self, expected: Round) -> Result<(), DealerError> {
+        if self.round != expected {
+            return Err(DealerError::WrongRound { expected, actual: self.round });
+        }
+        Ok(())
+    }
+}
+
+impl Dealer for HoldemDealer {
+    fn variant(&self) -> Variant {
+        match self.game {
+            HoldemGame::TexasHoldem => Variant::TexasHoldem,
+            HoldemGame::Omaha => Variant::PotLimitOmaha,
+            HoldemGame::OmahaHiLo => Variant::OmahaHiLo,
+            HoldemGame::ShortDeck { .. } => Variant::ShortDeck,
+        }
+    }
+
+    fn seat_player(&mut self, player: Player) -> usize {
+        self.add_player(player);
+        self.table.players.len() - 1
+    }
+
+    fn players(&self) -> &[Player] {
+        &self.table.players
+    }
+
+    fn round(&self) -> Round {
+        self.round
+    }
+
+    fn button(&self) -> usize {
+        self.table.button
+    }
+
+    fn set_button(&mut self, seat: usize) {
+        self.table.button = seat;
+    }
+
+    fn remove_player(&mut self, seat: usize) -> Result<Player, DealerError> {
+        self.expect_round(Round::Waiting)?;
+        self.table.remove_player(seat)
+    }
+
+    fn sit_out(&mut self, seat: usize, sitting_out: bool) -> Result<(), DealerError> {
+        self.table.sit_out(seat, sitting_out)
+    }
+
+    fn start_hand(&mut self, seed: u64) -> Result<(), DealerError> {
+        HoldemDealer::start_hand(self, seed)
+    }
+
+    fn start_hand_from(&mut self, deck: Deck) -> Result<(), DealerError> {
+        HoldemDealer::start_hand_from(self, deck)
+    }
+
+    fn apply_action(&mut self, seat: usize, action: PlayerAction) -> Result<bool, DealerError> {
+        HoldemDealer::apply_action(self, seat, action)
+    }
+
+    fn legal_actions(&self, seat: usize) -> Vec<LegalAction> {
+        self.table.legal_actions(seat)
+    }
+
+    fn advance(&mut self) -> Result<Round, DealerError> {
+        HoldemDealer::advance(self)
+    }
+
+    fn view_for(&self, viewer: Viewer) -> TableView {
+        self.table.view(self.variant(), self.round, self.table.to_act(), &self.board, viewer)
+    }
+
+    fn showdown(&mut self) -> Result<Vec<(usize, u32)>, DealerError> {
+        HoldemDealer::showdown(self)
+    }
+
+    fn awards(&self) -> Vec<(usize, u32)> {
+        self.table.awards.clone()
+    }
+
+    fn end_hand(&mut self) -> Result<(), DealerError> {
+        HoldemDealer::end_hand(self)
+    }
+}
+
+impl Default for HoldemDealer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::betting::BettingStructure;
+    use poker_common::card::cards;
+    use poker_common::hand::HandRank;
+
+    fn setup_table(stacks: &[u32]) -> HoldemDealer {
+        let mut dealer = HoldemDealer::with_stakes(Stakes::default());
+        for (i, &chips) in stacks.iter().enumerate() {
+            dealer.add_player(Player::new(format!("Player {}", i), i as u32, chips));
+        }
+        dealer
+    }
+
+    fn call_around(dealer: &mut HoldemDealer) {
+        while !dealer.is_betting_complete() {
+            let seat = dealer.get_current_player();
+            let action = if dealer.table.players[seat].get_current_bet() < dealer.get_current_bet() {
+                PlayerAction::Call
+            } else {
+                PlayerAction::Check
+            };
+            dealer.apply_action(seat, action).unwrap();
+        }
+    }
+
+    fn give_hole(dealer: &mut HoldemDealer, seat: usize, codes: &str) {
+        dealer.table.players[seat].clear_hand();
+        for card in cards(codes) {
+            dealer.table.players[seat].add_card(card);
+        }
+    }
+
+    #[test]
+    fn test_start_hand_deals_hole_cards_and_posts_blinds() {
+        let mut dealer = setup_table(&[1000, 1000, 1000]);
+        dealer.start_hand(1).unwrap();
+
+        assert_eq!(dealer.get_round(), Round::Preflop);
+        assert_eq!(dealer.table.players[1].get_current_bet(), 5);
+        assert_eq!(dealer.table.players[2].get_current_bet(), 10);
+        assert_eq!(dealer.get_pot(), 15);
+        // under the gun is the button, three handed
+        assert_eq!(dealer.get_current_player(), 0);
+        for player in dealer.get_players() {
+            assert_eq!(player.get_hand().len(), 2);
+        }
+        assert!(dealer.get_board().is_empty());
+        assert_eq!(dealer.cards_in_deck(), 52 - 3 * 2);
+    }
+
+    #[test]
+    fn test_heads_up_blinds_and_action_order() {
+        let mut dealer = setup_table(&[1000, 1000]);
+        dealer.start_hand(1).unwrap();
+
+        // the button posts the small blind and acts first before the flop
+        assert_eq!(dealer.table.players[0].get_current_bet(), 5);
+        assert_eq!(dealer.table.players[1].get_current_bet(), 10);
+        assert_eq!(dealer.get_current_player(), 0);
+        call_around(&mut dealer);
+
+        // and last after it
+        dealer.advance().unwrap();
+        assert_eq!(dealer.get_current_player(), 1);
+    }
+
+    #[test]
+    fn test_blinds_rotate_each_hand() {
+        let mut dealer = setup_table(&[1000, 1000, 1000, 1000]);
+        for hand in 0..4 {
+            dealer.start_hand(hand as u64).unwrap();
+            assert_eq!(dealer.get_button(), hand);
+            assert_eq!(dealer.table.players[(hand + 1) % 4].get_current_bet(), 5);
+            assert_eq!(dealer.table.players[(hand + 2) % 4].get_current_bet(), 10);
+            assert_eq!(dealer.get_current_player(), (hand + 3) % 4);
+
+            let seat = dealer.get_current_player();
+            dealer.apply_action(seat, PlayerAction::Fold).unwrap();
+            let seat = dealer.get_current_player();
+            dealer.apply_action(seat, PlayerAction::Fold).unwrap();
+            let seat = dealer.get_current_player();
+            dealer.apply_action(seat, PlayerAction::Fold).unwrap();
+            assert_eq!(dealer.get_round(), Round::Complete);
+            dealer.end_hand().unwrap();
+        }
+    }
+
+    #[test]
+    fn test_button_skips_busted_player() {
+        let mut dealer = setup_table(&[1000, 0, 1000, 1000]);
+        dealer.table.button = 1;
+        dealer.start_hand(1).unwrap();
+        assert_eq!(dealer.get_button(), 2);
+        assert_eq!(dealer.table.players[3].get_current_bet(), 5);
+        assert_eq!(dealer.table.players[0].get_current_bet(), 10);
+        assert!(dealer.table.players[1].get_hand().is_empty());
+    }
+
+    #[test]
+    fn test_streets_burn_and_deal_the_board() {
+        let mut dealer = setup_table(&[1000, 1000, 1000]);
+        dealer.start_hand(7).unwrap();
+        assert_eq!(dealer.advance(), Err(DealerError::BettingNotComplete));
+
+        let mut expected_deck = 52 - 3 * 2;
+        for (street, dealt) in [(Round::Flop, 3), (Round::Turn, 1), (Round::River, 1)] {
+            call_around(&mut dealer);
+            assert_eq!(dealer.advance(), Ok(street));
+            expected_deck -= 1 + dealt;
+            assert_eq!(dealer.cards_in_deck(), expected_deck);
+            // the first player left of the button acts first after the flop
+            assert_eq!(dealer.get_current_player(), 1);
+        }
+        assert_eq!(dealer.get_board().len(), 5);
+
+        call_around(&mut dealer);
+        assert_eq!(dealer.advance(), Ok(Round::Showdown));
+        assert_eq!(dealer.advance(), Err(DealerError::CannotAdvance(Round::Showdown)));
+        assert_eq!(dealer.apply_action(1, PlayerAction::Check), Err(DealerError::NotBettingRound(Round::Showdown)));
+
+        let awards = dealer.showdown().unwrap();
+        assert_eq!(awards.iter().map(|(_, amount)| amount).sum::<u32>(), 30);
+        let total: u32 = dealer.get_players().iter().map(|p| p.get_total_chips()).sum();
+        assert_eq!(total, 3000);
+        dealer.end_hand().unwrap();
+        assert_eq!(dealer.get_button(), 1);
+    }
+
+    #[test]
+    fn test_fixed_limit_big_bet_on_turn() {
+        let structure = BettingStructure::FixedLimit { small_bet: 10, big_bet: 20, max_raises: 3 };
+        let mut dealer = HoldemDealer::with_config(TableConfig { structure, ..TableConfig::default() });
+        for i in 0..3 {
+            dealer.add_player(Player::new(format!("Player {}", i), i, 1000));
+        }
+        dealer.start_hand(2).unwrap();
+        call_around(&mut dealer);
+        dealer.advance().unwrap();
+        assert_eq!(dealer.raise_range(), Some((10, 10)));
+        call_around(&mut dealer);
+        dealer.advance().unwrap();
+        assert_eq!(dealer.raise_range(), Some((20, 20)));
+    }
+
+    #[test]
+    fn test_showdown_uses_best_five_of_seven() {
+        let mut dealer = setup_table(&[1000, 1000, 1000]);
+        dealer.start_hand(3).unwrap();
+        call_around(&mut dealer);
+        for _ in 0..3 {
+            dealer.advance().unwrap();
+            call_around(&mut dealer);
+        }
+        dealer.advance().unwrap();
+
+        dealer.board = cards("Ah Kh 7h 7d 2c");
+        give_hole(&mut dealer, 0, "Qh 3h");
+        give_hole(&mut dealer, 1, "7s 7c");
+        give_hole(&mut dealer, 2, "Ac Kd");
+
+        assert_eq!(dealer.hand_evaluation(0).unwrap().rank(), HandRank::Flush);
+        assert_eq!(dealer.hand_evaluation(1).unwrap().rank(), HandRank::FourOfAKind);
+        assert_eq!(dealer.hand_evaluation(2).unwrap().to_string(), "Two Pair, Aces and Kings");
+
+        let awards = dealer.showdown().unwrap();
+        assert_eq!(awards, vec![(1, 30)]);
+    }
+
+    #[test]
+    fn test_board_plays_and_pot_is_split() {
+        let mut dealer = setup_table(&[1000, 1000]);
+        dealer.start_hand(4).unwrap();
+        call_around(&mut dealer);
+        for _ in 0..3 {
+            dealer.advance().unwrap();
+            call_around(&mut dealer);
+        }
+        dealer.advance().unwrap();
+
+        dealer.board = cards("Ts Js Qd Kc Ah");
+        give_hole(&mut dealer, 0, "2c 3d");
+        give_hole(&mut dealer, 1, "4h 4d");
+
+        let awards = dealer.showdown().unwrap();
+        assert_eq!(awards, vec![(1, 10), (0, 10)]);
+    }
+
+    #[test]
+    fn test_folded_player_cannot_win() {
+        let mut dealer = setup_table(&[1000, 1000, 1000]);
+        dealer.start_hand(5).unwrap();
+        dealer.apply_action(0, PlayerAction::Fold).unwrap();
+        assert!(dealer.table.players[0].get_hand().is_empty());
+        assert!(dealer.hand_evaluation(0).is_none());
+        dealer.apply_action(1, PlayerAction::Fold).unwrap();
+
+        assert_eq!(dealer.get_round(), Round::Complete);
+        assert_eq!(dealer.table.players[2].get_total_chips(), 1005);
+    }
+
+    fn omaha_at_showdown(game: HoldemGame, board: &str, holes: &[&str]) -> HoldemDealer {
+        let mut dealer = HoldemDealer::with_game(game, TableConfig::default());
+        for i in 0..holes.len() {
+            dealer.add_player(Player::new(format!("Player {}", i), i as u32, 1000));
+        }
+        dealer.start_hand(6).unwrap();
+        call_around(&mut dealer);
+        for _ in 0..3 {
+            dealer.advance().unwrap();
+            call_around(&mut dealer);
+        }
+        dealer.advance().unwrap();
+
+        dealer.board = cards(board);
+        for (seat, hole) in holes.iter().enumerate() {
+            give_hole(&mut dealer, seat, hole);
+        }
+        dealer
+    }
+
+    #[test]
+    fn test_short_deck_deals_from_six_up() {
+        let game = HoldemGame::ShortDeck { trips_beat_straight: false };
+        let mut dealer = HoldemDealer::with_game(game, TableConfig::default());
+        for i in 0..3 {
+            dealer.add_player(Player::new(format!("Player {}", i), i, 1000));
+        }
+        dealer.start_hand(2).unwrap();
+        assert_eq!(dealer.cards_in_deck(), 36 - 3 * 2);
+        for player in dealer.get_players() {
+            assert!(player.get_hand().iter().all(|card| card.value.rank() >= 6));
+        }
+    }
+
+    #[test]
+    fn test_short_deck_flush_beats_full_house() {
+        let game = HoldemGame::ShortDeck { trips_beat_straight: false };
+        let mut dealer = omaha_at_showdown(game, "Kh 9h 9s 7h 6d", &["Ah Qh", "Kd Kc"]);
+        assert_eq!(dealer.hand_evaluation(0).unwrap().rank(), HandRank::Flush);
+        assert_eq!(dealer.hand_evaluation(1).unwrap().rank(), HandRank::FullHouse);
+        let pot = dealer.get_pot();
+        assert_eq!(dealer.showdown().unwrap(), vec![(0, pot)]);
+    }
+
+    #[test]
+    fn test_short_deck_trips_and_straights() {
+        // A-6-7-8-9 is a straight, and with the option on trips beat it
+        let board = "6h 7d 8c Ts Td";
+        let holes = ["Ah 9d", "Tc Kh"];
+        for (trips_beat_straight, winner) in [(false, 0), (true, 1)] {
+            let game = HoldemGame::ShortDeck { trips_beat_straight };
+            let mut dealer = omaha_at_showdown(game, board, &holes);
+            assert_eq!(dealer.hand_evaluation(0).unwrap().to_string(), "Straight, Ten high");
+            let pot = dealer.get_pot();
+            assert_eq!(dealer.showdown().unwrap(), vec![(winner, pot)]);
+        }
+        let game = HoldemGame::ShortDeck { trips_beat_straight: false };
+        let dealer = omaha_at_showdown(game, "6h 7d 8c Js Qs", &["Ah 9d", "Tc Kh"]);
+        assert_eq!(dealer.hand_evaluation(0).unwrap().to_string(), "Straight, Nine high");
+    }
+
+    #[test]
+    fn test_omaha_deals_four_hole_cards() {
+        let mut dealer = HoldemDealer::with_game(HoldemGame::Omaha, TableConfig::default());
+        for i in 0..3 {
+            dealer.add_player(Player::new(format!("Player {}", i), i, 1000));
+        }
+        dealer.start_hand(1).unwrap();
+        for player in dealer.get_players() {
+            assert_eq!(player.get_hand().len(), 4);
+        }
+        assert_eq!(dealer.cards_in_deck(), 52 - 3 * 4);
+    }
+
+    #[test]
+    fn test_omaha_plays_exactly_two_hole_cards() {
+        // one heart in hand is no flush on a four heart board
+        let mut dealer = omaha_at_showdown(HoldemGame::Omaha, "2h 5h 8h 9h Tc", &["Ah Kc Kd 2s", "Qc Qd 3s 4d"]);
+        assert_eq!(dealer.hand_evaluation(0).unwrap().to_string(), "One Pair, Kings");
+        assert!(dealer.low_hand(0).is_none());
+
+        let awards = dealer.showdown().unwrap();
+        assert_eq!(awards, vec![(0, 20)]);
+    }
+
+    #[test]
+    fn test_hi_lo_splits_between_high_and_low() {
+        let mut dealer = omaha_at_showdown(HoldemGame::OmahaHiLo, "Ah 2c 7d Kd Qs", &["Kc Ks 9h 9s", "3h 4h Jc Jd", "Th Td 8c 8s"]);
+        assert_eq!(dealer.low_hand(1).unwrap().to_string(), "7-4-3-2-A");
+        assert!(dealer.low_hand(2).is_none());
+
+        let awards = dealer.showdown().unwrap();
+        assert_eq!(awards, vec![(0, 15), (1, 15)]);
+    }
+
+    #[test]
+    fn test_hi_lo_quarters_a_tied_low() {
+        // seat 0 has the high and ties seat 1 for low, so takes three quarters
+        let mut dealer = omaha_at_showdown(HoldemGame::OmahaHiLo, "Ah 2c 7d Kd Qs", &["3h 4h Kc Ks", "3c 4c Jc Jd", "Th Td 9c 9s"]);
+
+        // the odd chip in the low half goes to seat 1, first left of the button
+        let awards = dealer.showdown().unwrap();
+        assert_eq!(awards, vec![(0, 22), (1, 8)]);
+    }
+
+    #[test]
+    fn test_hi_lo_without_a_low_scoops() {
+        let mut dealer = omaha_at_showdown(HoldemGame::OmahaHiLo, "Ah Kc Qd 9s 9h", &["2h 3h 4c 5c", "9c Jd Ts 6d"]);
+        let awards = dealer.showdown().unwrap();
+        assert_eq!(awards, vec![(1, 20)]);
+    }
+}
@@ -0,0 +1,15 @@
+pub mod accounts;
+pub mod actor;
+pub mod betting;
+pub mod clock;
+pub mod connection;
+pub mod dealer;
+pub mod holdem;
+pub mod lobby;
+pub mod mixed;
+pub mod pot;
+pub mod server;
+pub mod stud;
+pub mod table;
+pub mod tokens;
+pub mod variant;
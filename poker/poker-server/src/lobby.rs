@@ -0,0 +1,410 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::mpsc::{SyncSender, TrySendError};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use poker_common::protocol::{ServerMessage, TableSummary};
+
+use crate::accounts::AccountStore;
+use crate::actor::{TableActor, TableHandle, HAND_DELAY};
+use crate::betting::BettingStructure;
+use crate::clock::ClockConfig;
+use crate::dealer::{DealerError, TableConfig};
+use crate::variant::Variant;
+
+/// Hands the lobby's average pot is taken over.
+pub const AVERAGE_POT_HANDS: usize = 50;
+
+/// The most tables open at once. Each runs on its own thread, so this also
+/// bounds the threads the tables take.
+pub const MAX_TABLES: usize = 500;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum LobbyError {
+    NoSuchTable(u32),
+    /// A table seats from two players up to the most its variant allows.
+    InvalidSeats { max: usize },
+    /// Stakes or a betting structure that can't be played, and why.
+    InvalidStakes(&'static str),
+    /// The lobby already has as many tables open as it allows.
+    TooManyTables { max: usize },
+    /// The table's thread couldn't be started, and why.
+    CannotOpen(String),
+    TableFull(u32),
+    AlreadySeated(u32),
+    NotSeated(u32),
+    BuyInTooSmall { min: u32 },
+    /// Players can only sit down or stand up between hands.
+    HandInProgress(u32),
+    /// The table's thread has stopped.
+    TableClosed(u32),
+    Dealer(DealerError),
+}
+
+impl fmt::Display for LobbyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LobbyError::NoSuchTable(table) => write!(f, "there is no table {}", table),
+            LobbyError::InvalidSeats { max } => write!(f, "a table seats from 2 to {} players", max),
+            LobbyError::InvalidStakes(reason) => write!(f, "invalid stakes: {}", reason),
+            LobbyError::TooManyTables { max } => write!(f, "no more than {} tables can be open at once", max),
+            LobbyError::CannotOpen(reason) => write!(f, "the table could not be opened: {}", reason),
+            LobbyError::TableFull(table) => write!(f, "table {} is full", table),
+            LobbyError::AlreadySeated(table) => write!(f, "already seated at table {}", table),
+            LobbyError::NotSeated(table) => write!(f, "not seated at table {}", table),
+            LobbyError::BuyInTooSmall { min } => write!(f, "the buy-in is at least {} chips", min),
+            LobbyError::HandInProgress(table) => write!(f, "wait for the hand at table {} to finish", table),
+            LobbyError::TableClosed(table) => write!(f, "table {} has closed", table),
+            LobbyError::Dealer(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for LobbyError {}
+
+impl From<DealerError> for LobbyError {
+    fn from(err: DealerError) -> Self {
+        LobbyError::Dealer(err)
+    }
+}
+
+/// Hands a message to a client without waiting. A client too far behind
+/// misses it, which is harmless while every update is a full snapshot;
+/// returns false only once the client has gone.
+pub(crate) fn deliver(client: &SyncSender<ServerMessage>, message: ServerMessage) -> bool {
+    !matches!(client.try_send(message), Err(TrySendError::Disconnected(_)))
+}
+
+/// The connections that want to hear whenever a table changes, shared by
+/// the lobby and every table.
+#[derive(Default)]
+pub struct Subscribers {
+    clients: BTreeMap<u64, SyncSender<ServerMessage>>,
+    next_id: u64,
+}
+
+impl Subscribers {
+    /// Sends `message` to every subscriber, dropping any whose connection has gone.
+    pub fn publish(&mut self, message: ServerMessage) {
+        self.clients.retain(|_, client| deliver(client, message.clone()));
+    }
+
+    pub fn len(&self) -> usize {
+        self.clients.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.clients.is_empty()
+    }
+}
+
+/// Which table each seated player is at, kept by the tables themselves, so
+/// logging in again, from a new connection or after one dropped, takes the
+/// player back to their seat.
+#[derive(Default)]
+pub struct Seating {
+    tables: BTreeMap<String, u32>,
+}
+
+impl Seating {
+    pub fn seat(&mut self, username: &str, table: u32) {
+        self.tables.insert(username.to_owned(), table);
+    }
+
+    pub fn unseat(&mut self, username: &str) {
+        self.tables.remove(username);
+    }
+
+    pub fn get_table(&self, username: &str) -> Option<u32> {
+        self.tables.get(username).copied()
+    }
+}
+
+/// The server's live tables, each running on its own thread, and the
+/// connections that want to hear when they change. Every change to a table
+/// is pushed to each subscriber as a `TableUpdated` message, and a table
+/// that closes after standing empty as a `TableClosed` message.
+pub struct Lobby {
+    tables: BTreeMap<u32, TableHandle>,
+    next_table: u32,
+    max_tables: usize,
+    hand_delay: Duration,
+    clock_config: ClockConfig,
+    subscribers: Arc<Mutex<Subscribers>>,
+    seating: Arc<Mutex<Seating>>,
+    accounts: Option<Arc<Mutex<AccountStore>>>,
+}
+
+impl Lobby {
+    pub fn new() -> Lobby {
+        Lobby {
+            tables: BTreeMap::new(),
+            next_table: 0,
+            max_tables: MAX_TABLES,
+            hand_delay: HAND_DELAY,
+            clock_config: ClockConfig::default(),
+            subscribers: Arc::default(),
+            seating: Arc::default(),
+            accounts: None,
+        }
+    }
+
+    /// Where tables opened from now on pay out the players they remove
+    /// themselves, after a disconnect.
+    pub fn with_accounts(mut self, accounts: Arc<Mutex<AccountStore>>) -> Lobby {
+        self.accounts = Some(accounts);
+        self
+    }
+
+    /// Sets the pause between hands at tables opened from now on.
+    pub fn with_hand_delay(mut self, hand_delay: Duration) -> Lobby {
+        self.hand_delay = hand_delay;
+        self
+    }
+
+    /// Sets the action clock, and how long an empty table stays open, at
+    /// tables opened from now on.
+    pub fn with_clock_config(mut self, clock_config: ClockConfig) -> Lobby {
+        self.clock_config = clock_config;
+        self
+    }
+
+    pub fn with_max_tables(mut self, max_tables: usize) -> Lobby {
+        self.max_tables = max_tables;
+        self
+    }
+
+    /// Opens an empty table and returns its id. It closes again if no one
+    /// sits down or watches for a while.
+    pub fn create_table(&mut self, variant: Variant, config: TableConfig, max_seats: usize) -> Result<u32, LobbyError> {
+        if !(2..=variant.max_players()).contains(&max_seats) {
+            return Err(LobbyError::InvalidSeats { max: variant.max_players() });
+        }
+        check_stakes(&config)?;
+        self.tables.retain(|_, table| table.is_open());
+        if self.tables.len() >= self.max_tables {
+            return Err(LobbyError::TooManyTables { max: self.max_tables });
+        }
+        self.next_table += 1;
+        let id = self.next_table;
+        let mut actor = TableActor::new(id, variant, config, max_seats, Arc::clone(&self.subscribers))
+            .with_hand_delay(self.hand_delay)
+            .with_clock_config(self.clock_config)
+            .with_seating(Arc::clone(&self.seating));
+        if let Some(accounts) = &self.accounts {
+            actor = actor.with_accounts(Arc::clone(accounts));
+        }
+        let handle = actor.spawn().map_err(|err| LobbyError::CannotOpen(err.to_string()))?;
+        self.tables.insert(id, handle);
+        Ok(id)
+    }
+
+    pub fn tables(&self) -> Vec<TableSummary> {
+        self.open_tables().map(TableHandle::summary).collect()
+    }
+
+    pub fn summary(&self, table: u32) -> Option<TableSummary> {
+        self.open_table(table).map(TableHandle::summary)
+    }
+
+    /// A handle for talking to the table. Take it and let go of the lobby
+    /// before using it, since a table's answer can take a moment.
+    pub fn table(&self, table: u32) -> Result<TableHandle, LobbyError> {
+        self.open_table(table).cloned().ok_or(LobbyError::NoSuchTable(table))
+    }
+
+    // Closed tables are only let go of when the next one opens, so they are
+    // passed over until then.
+    fn open_tables(&self) -> impl Iterator<Item = &TableHandle> {
+        self.tables.values().filter(|handle| handle.is_open())
+    }
+
+    fn open_table(&self, table: u32) -> Option<&TableHandle> {
+        self.tables.get(&table).filter(|handle| handle.is_open())
+    }
+
+    /// The table `username` has a seat at, if any.
+    pub fn seated_table(&self, username: &str) -> Option<u32> {
+        self.seating.lock().unwrap().get_table(username)
+    }
+
+    /// Sends every future table update to `client` and returns an id to
+    /// unsubscribe with.
+    pub fn subscribe(&mut self, client: SyncSender<ServerMessage>) -> u64 {
+        let mut subscribers = self.subscribers.lock().unwrap();
+        subscribers.next_id += 1;
+        let id = subscribers.next_id;
+        subscribers.clients.insert(id, client);
+        id
+    }
+
+    pub fn unsubscribe(&mut self, id: u64) {
+        self.subscribers.lock().unwrap().clients.remove(&id);
+    }
+}
+
+// Bets have to cost something, or a player could bet nothing forever, and
+// the blinds and bet sizes have to come in the right order.
+fn check_stakes(config: &TableConfig) -> Result<(), LobbyError> {
+    let stakes = config.stakes;
+    if stakes.small_blind > stakes.big_blind {
+        return Err(LobbyError::InvalidStakes("the small blind is bigger than the big blind"));
+    }
+    match config.structure {
+        // the big blind is the smallest bet
+        BettingStructure::NoLimit | BettingStructure::PotLimit if stakes.big_blind == 0 => {
+            Err(LobbyError::InvalidStakes("the big blind sets the smallest bet, so it can't be zero"))
+        }
+        BettingStructure::FixedLimit { small_bet, big_bet, .. } if small_bet == 0 || big_bet < small_bet => {
+            Err(LobbyError::InvalidStakes("the small bet can't be zero or more than the big bet"))
+        }
+        BettingStructure::SpreadLimit { min, max } if min == 0 || max < min => {
+            Err(LobbyError::InvalidStakes("the smallest bet can't be zero or more than the largest"))
+        }
+        _ => Ok(()),
+    }
+}
+
+impl Default for Lobby {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+    use std::time::{Duration, Instant};
+
+    use poker_common::action::PlayerAction;
+    use poker_common::protocol::ServerMessage;
+
+    use crate::betting::BettingStructure;
+    use crate::clock::ClockConfig;
+    use crate::dealer::{Round, Stakes, TableConfig};
+    use crate::lobby::{Lobby, LobbyError};
+    use crate::variant::Variant;
+
+    #[test]
+    fn test_create_and_list_tables() {
+        let mut lobby = Lobby::new();
+        let config = TableConfig {
+            stakes: Stakes { ante: 1, small_blind: 0, big_blind: 0, bring_in: 5 },
+            structure: BettingStructure::FixedLimit { small_bet: 10, big_bet: 20, max_raises: 4 },
+            ..TableConfig::default()
+        };
+        assert_eq!(lobby.create_table(Variant::Razz, config, 8), Err(LobbyError::InvalidSeats { max: 7 }));
+        assert_eq!(lobby.create_table(Variant::Razz, config, 1), Err(LobbyError::InvalidSeats { max: 7 }));
+        let razz = lobby.create_table(Variant::Razz, config, 7).unwrap();
+        let holdem = lobby.create_table(Variant::TexasHoldem, TableConfig::default(), 9).unwrap();
+        assert_ne!(razz, holdem);
+        assert!(matches!(lobby.table(99), Err(LobbyError::NoSuchTable(99))));
+
+        let tables = lobby.tables();
+        assert_eq!(tables.len(), 2);
+        assert_eq!(tables[0].variant, Variant::Razz);
+        assert_eq!(tables[0].stakes.bring_in, 5);
+        assert_eq!((tables[1].players, tables[1].max_seats, tables[1].average_pot), (0, 9, 0));
+    }
+
+    #[test]
+    fn test_stakes_are_checked() {
+        let mut lobby = Lobby::new();
+        let stakes = |small_blind, big_blind| Stakes { ante: 0, small_blind, big_blind, bring_in: 0 };
+        let table = |lobby: &mut Lobby, stakes, structure| {
+            lobby.create_table(Variant::TexasHoldem, TableConfig { stakes, structure, ..TableConfig::default() }, 6)
+        };
+        let invalid = |result| matches!(result, Err(LobbyError::InvalidStakes(_)));
+        for structure in [BettingStructure::NoLimit, BettingStructure::PotLimit] {
+            assert!(invalid(table(&mut lobby, stakes(0, 0), structure)));
+            assert!(invalid(table(&mut lobby, stakes(20, 10), structure)));
+        }
+        let fixed = |small_bet, big_bet| BettingStructure::FixedLimit { small_bet, big_bet, max_raises: 3 };
+        assert!(invalid(table(&mut lobby, stakes(5, 10), fixed(0, 20))));
+        assert!(invalid(table(&mut lobby, stakes(5, 10), fixed(20, 10))));
+        assert!(invalid(table(&mut lobby, stakes(5, 10), BettingStructure::SpreadLimit { min: 0, max: 10 })));
+        assert!(invalid(table(&mut lobby, stakes(5, 10), BettingStructure::SpreadLimit { min: 10, max: 5 })));
+        assert!(lobby.tables().is_empty());
+
+        // fixed limit needs no big blind, since the bets are set by the structure
+        assert!(table(&mut lobby, stakes(0, 0), fixed(10, 20)).is_ok());
+        assert!(table(&mut lobby, stakes(5, 10), BettingStructure::SpreadLimit { min: 10, max: 10 }).is_ok());
+    }
+
+    #[test]
+    fn test_tables_are_capped_and_close_when_empty() {
+        let config = ClockConfig { close_empty: Duration::from_millis(20), ..ClockConfig::default() };
+        let mut lobby = Lobby::new().with_max_tables(1).with_clock_config(config);
+        let (client, updates) = mpsc::sync_channel(16);
+        lobby.subscribe(client);
+        let table = lobby.create_table(Variant::TexasHoldem, TableConfig::default(), 6).unwrap();
+        assert_eq!(lobby.create_table(Variant::TexasHoldem, TableConfig::default(), 6), Err(LobbyError::TooManyTables { max: 1 }));
+
+        // no one sits down or watches, so it closes and makes room for another
+        loop {
+            match updates.recv_timeout(Duration::from_secs(5)) {
+                Ok(ServerMessage::TableClosed { table: closed }) if closed == table => break,
+                Ok(_) => continue,
+                Err(_) => panic!("table {} never closed", table),
+            }
+        }
+        assert!(lobby.tables().is_empty());
+        assert!(matches!(lobby.table(table), Err(LobbyError::NoSuchTable(_))));
+        assert!(lobby.create_table(Variant::TexasHoldem, TableConfig::default(), 6).is_ok());
+        assert_eq!(lobby.tables.len(), 1);
+    }
+
+    #[test]
+    fn test_updates_are_pushed_to_subscribers() {
+        let mut lobby = Lobby::new();
+        let (client, updates) = mpsc::sync_channel(16);
+        let id = lobby.subscribe(client);
+        let table = lobby.create_table(Variant::OmahaHiLo, TableConfig::default(), 6).unwrap();
+        let handle = lobby.table(table).unwrap();
+        handle.sit("jane", 500, mpsc::sync_channel(16).0).unwrap();
+
+        let pushed: Vec<ServerMessage> = updates.try_iter().collect();
+        assert_eq!(pushed.len(), 2);
+        assert!(matches!(&pushed[1], ServerMessage::TableUpdated(summary) if summary.id == table && summary.players == 1));
+        assert_eq!(lobby.summary(table).unwrap().players, 1);
+
+        lobby.unsubscribe(id);
+        handle.stand("jane").unwrap();
+        assert!(updates.try_recv().is_err());
+
+        // a subscriber whose connection is gone is dropped
+        let (client, updates) = mpsc::sync_channel(16);
+        lobby.subscribe(client);
+        drop(updates);
+        handle.sit("jane", 500, mpsc::sync_channel(16).0).unwrap();
+        assert!(lobby.subscribers.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_a_client_that_stops_reading_cannot_stall_the_table() {
+        let mut lobby = Lobby::new().with_hand_delay(Duration::ZERO);
+        let table = lobby.create_table(Variant::TexasHoldem, TableConfig::default(), 6).unwrap();
+        let handle = lobby.table(table).unwrap();
+        // jane's connection never reads a thing, john's reads everything
+        let (stalled, _unread) = mpsc::sync_channel(1);
+        let (events, received) = mpsc::sync_channel(1024);
+        handle.sit("jane", 500, stalled).unwrap();
+        handle.sit("john", 500, events).unwrap();
+
+        // the hands go on without waiting for jane to read them
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut folded = 0;
+        while folded < 3 {
+            let Ok(message) = received.recv_timeout(deadline.saturating_duration_since(Instant::now())) else {
+                panic!("the table stalled after {} hands", folded);
+            };
+            if let ServerMessage::TableState { view, .. } = message {
+                if let (Round::Preflop, Some(seat)) = (view.round, view.to_act) {
+                    handle.act(&view.seats[seat].name, PlayerAction::Fold).unwrap();
+                    folded += 1;
+                }
+            }
+        }
+    }
+}
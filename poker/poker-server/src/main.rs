@@ -1,67 +1,105 @@
 use std::net::{TcpStream, TcpListener};
-use std::io::{Read, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
 
-#[cfg(test)]
-pub mod dealer;
+use poker_common::protocol::{read_frame, receive, send, ClientMessage, Message, ProtocolError, ServerMessage, PROTOCOL_VERSION};
+use poker_server::accounts::AccountStore;
+use poker_server::connection::{Connection, OUTBOX_CAPACITY};
+use poker_server::server::Server;
 
-fn game_session_selection(stream: &mut TcpStream)
-{
-    // TODO: implement game session selection by spawning all games that are available with current players in the game
-    // TODO: send a list of games to the client
+// Each client takes two threads, one blocked reading from it and one writing
+// its outbox, as the server keeps to blocking sockets. Past this many clients
+// new ones are turned away rather than let the threads run out.
+const MAX_CLIENTS: usize = 1000;
 
-    // TODO: once session is selected, start the game which should be a dealer game
+// A client's place under `MAX_CLIENTS`, given back when its thread ends.
+struct ClientSlot(Arc<AtomicUsize>);
+
+impl Drop for ClientSlot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
 }
 
-fn verify_user(stream: &mut TcpStream) {
-    let mut buf = [0; 512];
-    let mut is_new_user = false;
-    let username_entered = false;
-    let mut username = String::new();
-    loop {
-        let bytes_read = stream.read(&mut buf).unwrap();
-        // split the buffer into a vector of strings separated by spaces
-        let tokens: Vec<&str> = std::str::from_utf8(&buf[..bytes_read]).unwrap().split_whitespace().collect();
-        if tokens.len() == 0 {
-            continue
-        } else if tokens[0] == "u" {
-            // username
-            username = tokens[1].to_owned();
-            let mut response = "";
-            // if username is in database, send a response to ask for password
-            // response = "returning user";
-            // else, send a response to ask for password
-            response = "new user";
-            stream.write(response.as_bytes()).unwrap();
-        } else if tokens[0] == "p" && username_entered {
-            let password = tokens[1];
-            if is_new_user {
-                // TODO: create new user with password saved
-            } else {
-                // TODO: verify password
-            }
-            let response: &str = "pass good";
-            stream.write(response.as_bytes()).unwrap();
-            game_session_selection(stream);
-        } else if tokens[0] == "q" {
-            // quit
-            let response = "Goodbye!";
-            stream.write(response.as_bytes()).unwrap();
-            break;
+fn handle_client(stream: &mut TcpStream, server: &Server) -> Result<(), ProtocolError> {
+    match receive(stream)? {
+        Some(ClientMessage::Hello { version }) if version == PROTOCOL_VERSION => {
+            send(stream, &ServerMessage::Welcome { version: PROTOCOL_VERSION })?;
+        }
+        Some(_) => {
+            let error = format!("expected hello with protocol version {}", PROTOCOL_VERSION);
+            return send(stream, &ServerMessage::Error(error));
         }
+        None => return Ok(()),
     }
+
+    // replies and pushed lobby and table updates share one writer, so they
+    // reach the client in the order they were produced
+    let (outbox, inbox) = mpsc::sync_channel::<ServerMessage>(OUTBOX_CAPACITY);
+    let mut outgoing = stream.try_clone()?;
+    let writer = thread::Builder::new().spawn(move || {
+        for message in inbox {
+            if send(&mut outgoing, &message).is_err() {
+                break;
+            }
+        }
+    })?;
+
+    let mut connection = Connection::new(server, outbox.clone());
+    let result = loop {
+        if connection.is_closed() {
+            break Ok(());
+        }
+        let frame = match read_frame(stream) {
+            Ok(Some(frame)) => frame,
+            Ok(None) => break Ok(()),
+            Err(err) => break Err(err),
+        };
+        // a frame that doesn't decode is still a whole frame, so the stream
+        // is in step and the client can carry on
+        let replies = match ClientMessage::decode(&frame) {
+            Ok(message) => connection.handle(message),
+            Err(err) => vec![ServerMessage::Error(format!("malformed message: {}", err))],
+        };
+        for reply in replies {
+            let _ = outbox.send(reply);
+        }
+    };
+
+    // the writer stops once every sender is gone
+    drop(connection);
+    drop(outbox);
+    let _ = writer.join();
+    result
 }
 
-fn setup_server() {
+fn setup_server(server: Arc<Server>) {
     let addr = "127.0.0.1:8080";
     let listener = TcpListener::bind(addr).unwrap();
     println!("Server listening on {}", addr);
+    let clients = Arc::new(AtomicUsize::new(0));
     for stream in listener.incoming() {
         match stream {
             // create multiple threads to handle multiple clients
             Ok(mut stream) => {
-                let thread = std::thread::spawn(move || {
-                    verify_user(&mut stream);
+                if clients.fetch_add(1, Ordering::SeqCst) >= MAX_CLIENTS {
+                    clients.fetch_sub(1, Ordering::SeqCst);
+                    let _ = send(&mut stream, &ServerMessage::Error("the server is full, try again later".to_owned()));
+                    continue;
+                }
+                let slot = ClientSlot(Arc::clone(&clients));
+                let server = Arc::clone(&server);
+                // a thread that won't start drops the slot and the stream with it
+                let spawned = thread::Builder::new().spawn(move || {
+                    let _slot = slot;
+                    if let Err(err) = handle_client(&mut stream, &server) {
+                        println!("Client error: {}", err);
+                    }
                 });
+                if let Err(err) = spawned {
+                    println!("Error: {}", err);
+                }
             }
             Err(e) => {
                 println!("Error: {}", e);
@@ -70,11 +108,15 @@ fn setup_server() {
     }
 }
 
-fn setup_database() {
-
+fn setup_database() -> AccountStore {
+    let path = "accounts.db";
+    match AccountStore::open(path) {
+        Ok(accounts) => accounts,
+        Err(err) => panic!("cannot open {}: {}", path, err),
+    }
 }
 
 fn main() {
-    setup_database();
-    setup_server();
+    let accounts = setup_database();
+    setup_server(Arc::new(Server::new(accounts)));
 }
@@ -0,0 +1,323 @@
+use poker_common::game::GameSession;
+use poker_common::player::Player;
+
+use crate::betting::BettingStructure;
+use crate::dealer::{Dealer, DealerError, Round, Stakes, TableConfig};
+use crate::variant::{DealerFactory, Variant};
+
+/// When a mixed game moves on to the next game in its rotation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rotation {
+    /// After this many hands of each game.
+    Hands(u32),
+    /// At the first hand after each new level, see [`MixedTable::level_up`].
+    Levels,
+}
+
+/// One game in a mixed rotation, with the stakes and structure it is played at.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MixedGame {
+    pub variant: Variant,
+    pub config: TableConfig,
+}
+
+impl MixedGame {
+    /// The variant at `stakes` under `structure`. Games with a bring-in post
+    /// antes and the bring-in, the others post blinds and no ante.
+    pub fn new(variant: Variant, stakes: Stakes, structure: BettingStructure) -> MixedGame {
+        let stakes = if variant.has_bring_in() {
+            Stakes { small_blind: 0, big_blind: 0, ..stakes }
+        } else {
+            Stakes { ante: 0, bring_in: 0, ..stakes }
+        };
+        MixedGame { variant, config: TableConfig { stakes, structure, ..TableConfig::default() } }
+    }
+
+    /// The variant played fixed limit, with the big bet double the small one.
+    pub fn fixed_limit(variant: Variant, stakes: Stakes, small_bet: u32) -> MixedGame {
+        let structure = BettingStructure::FixedLimit { small_bet, big_bet: 2 * small_bet, max_raises: 3 };
+        MixedGame::new(variant, stakes, structure)
+    }
+}
+
+/// A table that deals a rotation of games, such as HORSE, moving to the next
+/// game every few hands or every level. Stacks carry over from game to game,
+/// and the button only moves in the games that use one, so it picks up where
+/// it left off after a round of stud.
+///
+/// Every hand is recorded in the table's [`GameSession`] with the variant it
+/// was dealt as.
+pub struct MixedTable {
+    games: Vec<MixedGame>,
+    rotation: Rotation,
+    current: usize,
+    hands_this_game: u32,
+    level_changed: bool,
+    button: usize,
+    dealer: Box<dyn Dealer>,
+    session: GameSession,
+}
+
+impl MixedTable {
+    /// A table starting with the first of `games`. Panics if `games` is empty.
+    pub fn new(session_id: u32, games: Vec<MixedGame>, rotation: Rotation) -> MixedTable {
+        assert!(!games.is_empty(), "a mixed game needs at least one game");
+        let dealer = games[0].variant.dealer(games[0].config);
+        MixedTable {
+            games,
+            rotation,
+            current: 0,
+            hands_this_game: 0,
+            level_changed: false,
+            button: 0,
+            dealer,
+            session: GameSession::new(session_id),
+        }
+    }
+
+    /// Hold'em, Omaha Hi-Lo, Razz, Seven Card Stud and Stud Hi-Lo, all fixed limit.
+    pub fn horse(session_id: u32, stakes: Stakes, small_bet: u32, rotation: Rotation) -> MixedTable {
+        let games = [Variant::TexasHoldem, Variant::OmahaHiLo, Variant::Razz, Variant::SevenCardStud, Variant::StudHiLo]
+            .map(|variant| MixedGame::fixed_limit(variant, stakes, small_bet));
+        MixedTable::new(session_id, games.to_vec(), rotation)
+    }
+
+    /// 2-7 Triple Draw and HORSE fixed limit, then No-Limit Hold'em and
+    /// Pot-Limit Omaha.
+    pub fn eight_game(session_id: u32, stakes: Stakes, small_bet: u32, rotation: Rotation) -> MixedTable {
+        let mut games: Vec<MixedGame> = [
+            Variant::DeuceToSevenTripleDraw,
+            Variant::TexasHoldem,
+            Variant::OmahaHiLo,
+            Variant::Razz,
+            Variant::SevenCardStud,
+            Variant::StudHiLo,
+        ].iter().map(|&variant| MixedGame::fixed_limit(variant, stakes, small_bet)).collect();
+        games.push(MixedGame::new(Variant::TexasHoldem, stakes, BettingStructure::NoLimit));
+        games.push(MixedGame::new(Variant::PotLimitOmaha, stakes, BettingStructure::PotLimit));
+        MixedTable::new(session_id, games, rotation)
+    }
+
+    pub fn seat_player(&mut self, player: Player) -> usize {
+        self.dealer.seat_player(player)
+    }
+
+    /// The dealer for the game being played, for betting and drawing.
+    pub fn dealer(&mut self) -> &mut dyn Dealer {
+        self.dealer.as_mut()
+    }
+
+    pub fn get_dealer(&self) -> &dyn Dealer {
+        self.dealer.as_ref()
+    }
+
+    pub fn get_game(&self) -> MixedGame {
+        self.games[self.current]
+    }
+
+    pub fn get_variant(&self) -> Variant {
+        self.games[self.current].variant
+    }
+
+    pub fn get_session(&self) -> &GameSession {
+        &self.session
+    }
+
+    /// Marks the start of a new level. With [`Rotation::Levels`] the next
+    /// hand is dealt as the next game.
+    pub fn level_up(&mut self) {
+        self.level_changed = true;
+    }
+
+    /// Moves on to the next game if it is due, then starts a hand of it.
+    pub fn start_hand(&mut self, seed: u64) -> Result<(), DealerError> {
+        if self.dealer.round() != Round::Waiting {
+            return Err(DealerError::WrongRound { expected: Round::Waiting, actual: self.dealer.round() });
+        }
+        let due = match self.rotation {
+            Rotation::Hands(hands) => self.hands_this_game >= hands,
+            Rotation::Levels => self.level_changed,
+        };
+        if due {
+            self.next_game();
+        }
+
+        self.dealer.start_hand(seed)?;
+        self.session.start_game(self.get_variant(), self.dealer.players().to_vec());
+        Ok(())
+    }
+
+    /// Finishes the hand, records it in the session with everyone who won a
+    /// share of the pot, and moves the button.
+    pub fn end_hand(&mut self) -> Result<(), DealerError> {
+        // the dealer forgets its awards once the hand is over
+        let awards = self.dealer.awards();
+        self.dealer.end_hand()?;
+
+        if let Some(game) = self.session.get_current_game_mut() {
+            let mut winners: Vec<usize> = awards.iter().map(|&(seat, _)| seat).collect();
+            winners.sort_unstable();
+            winners.dedup();
+            for seat in winners {
+                game.add_winning_player(self.dealer.players()[seat].clone());
+            }
+            game.set_total_chips(awards.iter().map(|&(_, chips)| chips).sum());
+        }
+        self.session.end_game();
+
+        self.hands_this_game += 1;
+        if !self.get_variant().has_bring_in() {
+            self.button = self.dealer.button();
+        }
+        Ok(())
+    }
+
+    // Reseats everyone at a dealer for the next game, keeping their stacks.
+    fn next_game(&mut self) {
+        self.current = (self.current + 1) % self.games.len();
+        self.hands_this_game = 0;
+        self.level_changed = false;
+
+        let game = self.games[self.current];
+        let mut dealer = game.variant.dealer(game.config);
+        for player in self.dealer.players() {
+            dealer.seat_player(player.clone());
+        }
+        dealer.set_button(self.button);
+        self.dealer = dealer;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use poker_common::action::PlayerAction;
+    use poker_common::player::Player;
+
+    use crate::dealer::{Round, Stakes, Viewer};
+    use crate::mixed::{MixedTable, Rotation};
+    use crate::variant::Variant;
+
+    fn horse(rotation: Rotation) -> MixedTable {
+        let stakes = Stakes { ante: 1, small_blind: 5, big_blind: 10, bring_in: 3 };
+        let mut table = MixedTable::horse(1, stakes, 10, rotation);
+        for i in 0..3 {
+            table.seat_player(Player::new(format!("Player {}", i), i, 1000));
+        }
+        table
+    }
+
+    // Everyone folds in turn until one player is left with the pot.
+    fn fold_around(table: &mut MixedTable) {
+        while table.get_dealer().round() != Round::Complete {
+            let dealer = table.dealer();
+            let seat = (0..dealer.players().len()).find(|&seat| !dealer.legal_actions(seat).is_empty()).unwrap();
+            dealer.apply_action(seat, PlayerAction::Fold).unwrap();
+        }
+    }
+
+    fn play_hand(table: &mut MixedTable, seed: u64) {
+        table.start_hand(seed).unwrap();
+        fold_around(table);
+        table.end_hand().unwrap();
+    }
+
+    fn chips(table: &MixedTable) -> u32 {
+        table.get_dealer().players().iter().map(|p| p.get_total_chips()).sum()
+    }
+
+    #[test]
+    fn test_rotates_every_n_hands() {
+        let mut table = horse(Rotation::Hands(2));
+        for seed in 0..10 {
+            play_hand(&mut table, seed);
+            assert_eq!(chips(&table), 3000);
+        }
+        let played: Vec<Variant> = table.get_session().get_games().iter().map(|game| game.get_variant()).collect();
+        assert_eq!(played, vec![
+            Variant::TexasHoldem, Variant::TexasHoldem,
+            Variant::OmahaHiLo, Variant::OmahaHiLo,
+            Variant::Razz, Variant::Razz,
+            Variant::SevenCardStud, Variant::SevenCardStud,
+            Variant::StudHiLo, Variant::StudHiLo,
+        ]);
+        // and back to the start
+        play_hand(&mut table, 10);
+        assert_eq!(table.get_variant(), Variant::TexasHoldem);
+    }
+
+    #[test]
+    fn test_structure_switches_with_the_game() {
+        let mut table = horse(Rotation::Hands(1));
+        // hold'em posts blinds and no ante
+        table.start_hand(1).unwrap();
+        assert_eq!(table.get_dealer().view_for(Viewer::Seat(0)).pot, 15);
+        fold_around(&mut table);
+        table.end_hand().unwrap();
+
+        play_hand(&mut table, 2);
+        // razz posts antes and a bring-in and no blinds
+        table.start_hand(3).unwrap();
+        assert_eq!(table.get_variant(), Variant::Razz);
+        assert_eq!(table.get_dealer().view_for(Viewer::Seat(0)).pot, 3 + 3);
+        fold_around(&mut table);
+        table.end_hand().unwrap();
+        assert_eq!(chips(&table), 3000);
+    }
+
+    #[test]
+    fn test_stacks_and_winners_carry_over() {
+        let mut table = horse(Rotation::Hands(1));
+        play_hand(&mut table, 1);
+        let stacks: Vec<u32> = table.get_dealer().players().iter().map(|p| p.get_total_chips()).collect();
+        let game = &table.get_session().get_games()[0];
+        assert_eq!(game.get_winning_players().len(), 1);
+        // the whole pot of blinds, not just what the winner gained
+        assert_eq!(game.get_total_chips(), 15);
+
+        // the next game is dealt to the same stacks
+        table.start_hand(2).unwrap();
+        assert_eq!(table.get_variant(), Variant::OmahaHiLo);
+        let view = table.get_dealer().view_for(Viewer::Seat(0));
+        for (seat, stack) in stacks.iter().enumerate() {
+            assert_eq!(view.seats[seat].chips + view.seats[seat].current_bet, *stack);
+        }
+    }
+
+    #[test]
+    fn test_button_skips_the_stud_games() {
+        let mut table = horse(Rotation::Hands(1));
+        play_hand(&mut table, 1);
+        play_hand(&mut table, 2);
+        let button = table.get_dealer().button();
+        assert_eq!(button, 2);
+        // razz, stud and stud hi-lo leave it where it was
+        for seed in 3..6 {
+            play_hand(&mut table, seed);
+        }
+        table.start_hand(6).unwrap();
+        assert_eq!(table.get_variant(), Variant::TexasHoldem);
+        assert_eq!(table.get_dealer().button(), button);
+    }
+
+    #[test]
+    fn test_rotates_on_level_up() {
+        let mut table = horse(Rotation::Levels);
+        for seed in 0..3 {
+            play_hand(&mut table, seed);
+        }
+        assert_eq!(table.get_variant(), Variant::TexasHoldem);
+        table.level_up();
+        play_hand(&mut table, 3);
+        assert_eq!(table.get_variant(), Variant::OmahaHiLo);
+        play_hand(&mut table, 4);
+        assert_eq!(table.get_variant(), Variant::OmahaHiLo);
+    }
+
+    #[test]
+    fn test_cannot_switch_mid_hand() {
+        let mut table = horse(Rotation::Hands(1));
+        table.start_hand(1).unwrap();
+        assert!(table.start_hand(2).is_err());
+        assert_eq!(MixedTable::eight_game(2, Stakes::default(), 10, Rotation::Levels).get_variant(), Variant::DeuceToSevenTripleDraw);
+    }
+}
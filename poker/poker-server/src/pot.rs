@@ -0,0 +1,116 @@
+/// A main or side pot and the seats that are eligible to win it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pot {
+    pub amount: u32,
+    pub eligible: Vec<usize>,
+}
+
+/// Splits the chips put in over a hand into a main pot and side pots.
+///
+/// `contributions` holds each seat's total for the hand, including antes and
+/// blinds, and `in_hand` marks the seats that have not folded. A player can
+/// only win, from each opponent, as much as they put in themselves, so every
+/// all-in amount starts a new side pot. Chips from folded players stay in the
+/// pots they reached but make nobody eligible.
+pub fn build_pots(contributions: &[u32], in_hand: &[bool]) -> Vec<Pot> {
+    let mut levels: Vec<u32> = contributions.iter()
+        .zip(in_hand)
+        .filter(|(&chips, &live)| live && chips > 0)
+        .map(|(&chips, _)| chips)
+        .collect();
+    levels.sort_unstable();
+    levels.dedup();
+
+    let mut pots: Vec<Pot> = Vec::new();
+    let mut previous = 0;
+    for &level in &levels {
+        let amount = contributions.iter()
+            .map(|&chips| chips.min(level) - chips.min(previous))
+            .sum();
+        let eligible: Vec<usize> = (0..contributions.len())
+            .filter(|&seat| in_hand[seat] && contributions[seat] >= level)
+            .collect();
+
+        // a level that nobody new drops out at just tops up the previous pot
+        match pots.last_mut() {
+            Some(last) if last.eligible == eligible => last.amount += amount,
+            _ => pots.push(Pot { amount, eligible }),
+        }
+        previous = level;
+    }
+
+    // folded chips above the highest live contribution go to the last pot
+    let leftover: u32 = contributions.iter().map(|&chips| chips - chips.min(previous)).sum();
+    if let Some(last) = pots.last_mut() {
+        last.amount += leftover;
+    }
+    pots
+}
+
+/// Divides a pot evenly between `winners`, which must be ordered starting
+/// from the first seat left of the button. Any odd chips that cannot be
+/// split go one at a time to the winners in that order.
+pub fn split_pot(amount: u32, winners: &[usize]) -> Vec<(usize, u32)> {
+    if winners.is_empty() {
+        return Vec::new();
+    }
+    let share = amount / winners.len() as u32;
+    let odd_chips = (amount % winners.len() as u32) as usize;
+    winners.iter()
+        .enumerate()
+        .map(|(i, &seat)| (seat, share + u32::from(i < odd_chips)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_pot_when_everyone_matches() {
+        let pots = build_pots(&[100, 100, 100], &[true, true, true]);
+        assert_eq!(pots, vec![Pot { amount: 300, eligible: vec![0, 1, 2] }]);
+    }
+
+    #[test]
+    fn test_short_all_in_creates_side_pot() {
+        let pots = build_pots(&[100, 40, 100], &[true, true, true]);
+        assert_eq!(pots, vec![
+            Pot { amount: 120, eligible: vec![0, 1, 2] },
+            Pot { amount: 120, eligible: vec![0, 2] },
+        ]);
+    }
+
+    #[test]
+    fn test_multiple_all_ins() {
+        let pots = build_pots(&[25, 300, 100, 300], &[true, true, true, true]);
+        assert_eq!(pots, vec![
+            Pot { amount: 100, eligible: vec![0, 1, 2, 3] },
+            Pot { amount: 225, eligible: vec![1, 2, 3] },
+            Pot { amount: 400, eligible: vec![1, 3] },
+        ]);
+    }
+
+    #[test]
+    fn test_folded_chips_stay_in_pots() {
+        // seat 0 folded after putting in 60, more than the all-in seat 1
+        let pots = build_pots(&[60, 40, 200], &[false, true, true]);
+        assert_eq!(pots, vec![
+            Pot { amount: 120, eligible: vec![1, 2] },
+            Pot { amount: 180, eligible: vec![2] },
+        ]);
+        assert_eq!(pots.iter().map(|p| p.amount).sum::<u32>(), 300);
+
+        // folded chips above every live contribution still end up somewhere
+        let pots = build_pots(&[80, 50, 50], &[false, true, true]);
+        assert_eq!(pots, vec![Pot { amount: 180, eligible: vec![1, 2] }]);
+    }
+
+    #[test]
+    fn test_split_pot_odd_chips() {
+        assert_eq!(split_pot(100, &[2, 0]), vec![(2, 50), (0, 50)]);
+        assert_eq!(split_pot(101, &[2, 0]), vec![(2, 51), (0, 50)]);
+        assert_eq!(split_pot(11, &[1, 2, 0]), vec![(1, 4), (2, 4), (0, 3)]);
+        assert!(split_pot(10, &[]).is_empty());
+    }
+}
@@ -0,0 +1,84 @@
+use std::collections::HashMap;
+use std::sync::mpsc::SyncSender;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use poker_common::protocol::ServerMessage;
+
+use crate::accounts::AccountStore;
+use crate::lobby::{deliver, Lobby};
+use crate::tokens::SessionTokens;
+
+/// The connection each logged-in account is using. Logging in again takes
+/// the account over from the older connection, which is told so and can do
+/// nothing more with it.
+#[derive(Default)]
+pub struct LiveSessions {
+    connections: HashMap<String, (u64, SyncSender<ServerMessage>)>,
+    next_id: u64,
+}
+
+impl LiveSessions {
+    /// Makes the connection sending to `outbox` the live one for `username`,
+    /// and returns an id to check it by.
+    pub fn take_over(&mut self, username: &str, outbox: SyncSender<ServerMessage>) -> u64 {
+        self.next_id += 1;
+        if let Some((_, older)) = self.connections.insert(username.to_owned(), (self.next_id, outbox)) {
+            deliver(&older, ServerMessage::Error("logged in from another connection".to_owned()));
+        }
+        self.next_id
+    }
+
+    pub fn is_live(&self, username: &str, id: u64) -> bool {
+        self.connections.get(username).is_some_and(|(live, _)| *live == id)
+    }
+
+    /// Forgets the connection, unless another has taken the account over.
+    pub fn end(&mut self, username: &str, id: u64) {
+        if self.is_live(username, id) {
+            self.connections.remove(username);
+        }
+    }
+}
+
+/// State shared by every connection to the server.
+pub struct Server {
+    // shared with the tables, which pay out players they remove themselves
+    accounts: Arc<Mutex<AccountStore>>,
+    tokens: Mutex<SessionTokens>,
+    sessions: Mutex<LiveSessions>,
+    lobby: Mutex<Lobby>,
+}
+
+impl Server {
+    pub fn new(accounts: AccountStore) -> Server {
+        let accounts = Arc::new(Mutex::new(accounts));
+        Server {
+            tokens: Mutex::new(SessionTokens::new()),
+            sessions: Mutex::new(LiveSessions::default()),
+            lobby: Mutex::new(Lobby::new().with_accounts(Arc::clone(&accounts))),
+            accounts,
+        }
+    }
+
+    pub fn accounts(&self) -> MutexGuard<'_, AccountStore> {
+        self.accounts.lock().unwrap()
+    }
+
+    pub fn tokens(&self) -> MutexGuard<'_, SessionTokens> {
+        self.tokens.lock().unwrap()
+    }
+
+    pub fn sessions(&self) -> MutexGuard<'_, LiveSessions> {
+        self.sessions.lock().unwrap()
+    }
+
+    pub fn lobby(&self) -> MutexGuard<'_, Lobby> {
+        self.lobby.lock().unwrap()
+    }
+}
+
+/// Seconds since the Unix epoch.
+pub fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |since| since.as_secs())
+}
@@ -0,0 +1,632 @@
+use std::cmp::Reverse;
+
+use poker_common::action::PlayerAction;
+use poker_common::card::{Card, Deck};
+use poker_common::evaluator::{self, HandStrength};
+use poker_common::hand::HandEvaluation;
+use poker_common::low::{self, LowHand, LowballHand};
+use poker_common::player::Player;
+
+use crate::dealer::{Dealer, DealerError, LegalAction, Round, TableConfig, TableView, Viewer};
+use crate::pot::Pot;
+use crate::table::Table;
+use crate::variant::Variant;
+
+/// The seven card stud games dealt by [`StudDealer`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StudGame {
+    /// Best five card high hand out of seven.
+    SevenCardStud,
+    /// The pot split between the best high and the best eight-or-better low.
+    StudHiLo,
+    /// Best ace-to-five low hand out of seven.
+    Razz,
+}
+
+/// Deals seven card stud: two cards down and one up, three more up with a
+/// betting round after each, and a last card down. There are no blinds; the
+/// lowest door card (the highest in Razz) brings it in.
+pub struct StudDealer {
+    table: Table,
+    game: StudGame,
+    round: Round,
+    bring_in: Option<usize>,
+    // dealt face up to everyone when the deck cannot give each player a river card
+    community: Vec<Card>,
+}
+
+impl StudDealer {
+    pub fn new(game: StudGame) -> Self {
+        Self::with_config(game, TableConfig::default())
+    }
+
+    pub fn with_config(game: StudGame, config: TableConfig) -> Self {
+        StudDealer {
+            table: Table::new(config),
+            game,
+            round: Round::Waiting,
+            bring_in: None,
+            community: Vec::new(),
+        }
+    }
+
+    pub fn add_player(&mut self, player: Player) {
+        self.table.add_player(player);
+    }
+
+    pub fn get_players(&self) -> &Vec<Player> {
+        &self.table.players
+    }
+
+    pub fn get_game(&self) -> StudGame {
+        self.game
+    }
+
+    pub fn get_pot(&self) -> u32 {
+        self.table.pot
+    }
+
+    pub fn get_pots(&self) -> Vec<Pot> {
+        self.table.pots()
+    }
+
+    pub fn get_current_bet(&self) -> u32 {
+        self.table.current_bet()
+    }
+
+    pub fn get_current_player(&self) -> usize {
+        self.table.current_player
+    }
+
+    pub fn get_round(&self) -> Round {
+        self.round
+    }
+
+    /// The seat that was forced to bring in the betting this hand.
+    pub fn get_bring_in(&self) -> Option<usize> {
+        self.bring_in
+    }
+
+    pub fn raise_range(&self) -> Option<(u32, u32)> {
+        self.table.raise_range()
+    }
+
+    pub fn is_betting_complete(&self) -> bool {
+        self.table.is_betting_complete()
+    }
+
+    /// Starts a new hand: posts antes, deals two cards down and one up to
+    /// each player and has the bring-in post and open the betting.
+    pub fn start_hand(&mut self, seed: u64) -> Result<(), DealerError> {
+        self.start_hand_from(Deck::shuffled(seed))
+    }
+
+    /// Starts a new hand as [`StudDealer::start_hand`] does, dealing from
+    /// `deck` as it stands.
+    pub fn start_hand_from(&mut self, deck: Deck) -> Result<(), DealerError> {
+        self.expect_round(Round::Waiting)?;
+        self.table.begin_hand(deck)?;
+        self.community.clear();
+        self.table.post_antes();
+
+        let seats = self.dealing_order();
+        for face_up in [false, false, true] {
+            for &seat in &seats {
+                self.deal(seat, face_up)?;
+            }
+        }
+
+        let seat = self.bring_in_seat(&seats);
+        self.bring_in = Some(seat);
+        let bring_in = self.table.post_bet(seat, self.table.config.stakes.bring_in);
+        if bring_in > 0 {
+            self.table.open_bring_in_betting(seat, bring_in);
+        } else {
+            self.table.open_betting(seat, 0, false);
+        }
+        self.round = Round::ThirdStreet;
+        Ok(())
+    }
+
+    /// Applies a betting action for the player whose turn it is and returns
+    /// whether the betting round is now complete. If everyone else folds,
+    /// the last player is awarded the pot and the hand is over.
+    pub fn apply_action(&mut self, seat: usize, action: PlayerAction) -> Result<bool, DealerError> {
+        let betting = matches!(
+            self.round,
+            Round::ThirdStreet | Round::FourthStreet | Round::FifthStreet | Round::SixthStreet | Round::SeventhStreet
+        );
+        if !betting {
+            return Err(DealerError::NotBettingRound(self.round));
+        }
+        let complete = self.table.apply_action(seat, action)?;
+        if self.table.award_if_uncontested() {
+            self.round = Round::Complete;
+        }
+        Ok(complete)
+    }
+
+    /// Closes the betting on the current street and deals the next one, with
+    /// the best hand showing first to act. After seventh street the hand goes
+    /// to showdown.
+    pub fn advance(&mut self) -> Result<Round, DealerError> {
+        let next = match self.round {
+            Round::ThirdStreet => Round::FourthStreet,
+            Round::FourthStreet => Round::FifthStreet,
+            Round::FifthStreet => Round::SixthStreet,
+            Round::SixthStreet => Round::SeventhStreet,
+            Round::SeventhStreet => Round::Showdown,
+            round => return Err(DealerError::CannotAdvance(round)),
+        };
+        self.table.end_betting_round()?;
+        self.round = next;
+        if next == Round::Showdown {
+            return Ok(next);
+        }
+
+        let seats = self.dealing_order();
+        let deck = &self.table.deck;
+        let enough_for_everyone = deck.remaining() + deck.discarded() > seats.len();
+        if next != Round::SeventhStreet {
+            self.table.burn()?;
+            for &seat in &seats {
+                self.deal(seat, true)?;
+            }
+        } else if enough_for_everyone {
+            self.table.burn()?;
+            for &seat in &seats {
+                self.deal(seat, false)?;
+            }
+        } else {
+            // not enough cards to go round, so the last one is shared face up
+            let card = self.table.deal_card()?;
+            self.community.push(card);
+        }
+
+        // fixed-limit games bet the big bet from fifth street on
+        let first = self.best_showing(&seats);
+        self.table.open_betting(first, 0, next != Round::FourthStreet);
+        Ok(next)
+    }
+
+    /// Awards each pot to the best hand eligible for it and returns each
+    /// winner's seat with their total winnings. In Stud Hi-Lo the best
+    /// eight-or-better low takes half; in Razz the lowest hand wins.
+    pub fn showdown(&mut self) -> Result<Vec<(usize, u32)>, DealerError> {
+        self.expect_round(Round::Showdown)?;
+        let seats = 0..self.table.players.len();
+        let awards = match self.game {
+            StudGame::SevenCardStud => {
+                let strengths: Vec<Option<HandStrength>> = seats.map(|seat| self.best_hand(seat)).collect();
+                self.table.award_pots(&strengths)
+            }
+            StudGame::StudHiLo => {
+                let strengths: Vec<Option<HandStrength>> = seats.clone().map(|seat| self.best_hand(seat)).collect();
+                let lows: Vec<Option<Reverse<LowHand>>> = seats.map(|seat| self.low_hand(seat).map(Reverse)).collect();
+                self.table.award_hi_lo_pots(&strengths, &lows)
+            }
+            StudGame::Razz => {
+                let lows: Vec<Option<Reverse<LowballHand>>> =
+                    seats.map(|seat| self.lowball_hand(seat).map(Reverse)).collect();
+                self.table.award_pots(&lows)
+            }
+        };
+        self.round = Round::Complete;
+        Ok(awards)
+    }
+
+    /// Clears the finished hand and moves the dealer position for the next one.
+    pub fn end_hand(&mut self) -> Result<(), DealerError> {
+        self.expect_round(Round::Complete)?;
+        for card in self.community.drain(..) {
+            self.table.deck.discard(card);
+        }
+        self.bring_in = None;
+        self.table.end_hand();
+        self.round = Round::Waiting;
+        Ok(())
+    }
+
+    /// The best high hand the player in `seat` makes from their seven cards.
+    pub fn hand_evaluation(&self, seat: usize) -> Option<HandEvaluation> {
+        HandEvaluation::new(&self.cards_for(seat)?)
+    }
+
+    /// The best eight-or-better low the player in `seat` makes, in Stud Hi-Lo.
+    pub fn low_hand(&self, seat: usize) -> Option<LowHand> {
+        if self.game != StudGame::StudHiLo {
+            return None;
+        }
+        low::best_low(&self.cards_for(seat)?)
+    }
+
+    /// The best ace-to-five hand the player in `seat` makes, in Razz.
+    pub fn lowball_hand(&self, seat: usize) -> Option<LowballHand> {
+        if self.game != StudGame::Razz {
+            return None;
+        }
+        low::best_ace_to_five(&self.cards_for(seat)?)
+    }
+
+    fn deal(&mut self, seat: usize, face_up: bool) -> Result<(), DealerError> {
+        let card = self.table.deal_card()?;
+        if face_up {
+            self.table.players[seat].add_face_up_card(card);
+        } else {
+            self.table.players[seat].add_card(card);
+        }
+        Ok(())
+    }
+
+    // Players still in the hand, starting left of the dealer.
+    fn dealing_order(&self) -> Vec<usize> {
+        let mut seats = self.table.in_hand();
+        seats.sort_by_key(|&seat| self.table.seats_from_button(seat));
+        seats
+    }
+
+    // The lowest door card brings it in, aces high and clubs the lowest suit.
+    // In Razz it is the highest door card, kings high and spades the highest suit.
+    fn bring_in_seat(&self, seats: &[usize]) -> usize {
+        let door = |seat: usize| {
+            let card = self.table.players[seat].get_up_cards()[0].clone();
+            match self.game {
+                StudGame::Razz => (card.value.low_rank(), card.suit.rank()),
+                _ => (card.value.rank(), card.suit.rank()),
+            }
+        };
+        let chosen = match self.game {
+            StudGame::Razz => seats.iter().max_by_key(|&&seat| door(seat)),
+            _ => seats.iter().min_by_key(|&&seat| door(seat)),
+        };
+        *chosen.expect("a hand is never started with fewer than two players")
+    }
+
+    // The best hand showing acts first: the highest in high games, the
+    // lowest in Razz. Ties go to the player nearest the dealer's left.
+    fn best_showing(&self, seats: &[usize]) -> usize {
+        let up = |seat: usize| self.table.players[seat].get_up_cards();
+        let mut best = seats[0];
+        for &seat in &seats[1..] {
+            let better = match self.game {
+                StudGame::Razz => low::ace_to_five(&up(seat)) < low::ace_to_five(&up(best)),
+                _ => showing_high(&up(seat)) > showing_high(&up(best)),
+            };
+            if better {
+                best = seat;
+            }
+        }
+        best
+    }
+
+    // Folded players have mucked their cards and cannot win
+    fn best_hand(&self, seat: usize) -> Option<HandStrength> {
+        evaluator::best_hand(&self.cards_for(seat)?).map(|(strength, _)| strength)
+    }
+
+    fn cards_for(&self, seat: usize) -> Option<Vec<Card>> {
+        let hand = self.table.players.get(seat)?.get_hand();
+        if hand.is_empty() {
+            return None;
+        }
+        Some(hand.iter().chain(self.community.iter()).cloned().collect())
+    }
+
+    fn expect_round(&self, expected: Round) -> Result<(), DealerError> {
+        if self.round != expected {
+            return Err(DealerError::WrongRound { expected, actual: self.round });
+        }
+        Ok(())
+    }
+}
+
+// Ranks up to four up cards as a high hand. Only pairs, trips and quads
+// count, since a partial hand cannot show a straight or flush.
+fn showing_high(cards: &[Card]) -> (Vec<usize>, Vec<u8>) {
+    let mut groups: Vec<(usize, u8)> = Vec::new();
+    for card in cards {
+        let rank = card.value.rank();
+        match groups.iter_mut().find(|(_, r)| *r == rank) {
+            Some(group) => group.0 += 1,
+            None => groups.push((1, rank)),
+        }
+    }
+    groups.sort_unstable_by(|a, b| b.cmp(a));
+    groups.into_iter().unzip()
+}
+
+impl Dealer for StudDealer {
+    fn variant(&self) -> Variant {
+        match self.game {
+            StudGame::SevenCardStud => Variant::SevenCardStud,
+            StudGame::StudHiLo => Variant::StudHiLo,
+            StudGame::Razz => Variant::Razz,
+        }
+    }
+
+    fn seat_player(&mut self, player: Player) -> usize {
+        self.add_player(player);
+        self.table.players.len() - 1
+    }
+
+    fn players(&self) -> &[Player] {
+        &self.table.players
+    }
+
+    fn round(&self) -> Round {
+        self.round
+    }
+
+    fn button(&self) -> usize {
+        self.table.button
+    }
+
+    fn set_button(&mut self, seat: usize) {
+        self.table.button = seat;
+    }
+
+    fn remove_player(&mut self, seat: usize) -> Result<Player, DealerError> {
+        self.expect_round(Round::Waiting)?;
+        self.table.remove_player(seat)
+    }
+
+    fn sit_out(&mut self, seat: usize, sitting_out: bool) -> Result<(), DealerError> {
+        self.table.sit_out(seat, sitting_out)
+    }
+
+    fn start_hand(&mut self, seed: u64) -> Result<(), DealerError> {
+        StudDealer::start_hand(self, seed)
+    }
+
+    fn start_hand_from(&mut self, deck: Deck) -> Result<(), DealerError> {
+        StudDealer::start_hand_from(self, deck)
+    }
+
+    fn apply_action(&mut self, seat: usize, action: PlayerAction) -> Result<bool, DealerError> {
+        StudDealer::apply_action(self, seat, action)
+    }
+
+    fn legal_actions(&self, seat: usize) -> Vec<LegalAction> {
+        self.table.legal_actions(seat)
+    }
+
+    fn advance(&mut self) -> Result<Round, DealerError> {
+        StudDealer::advance(self)
+    }
+
+    fn view_for(&self, viewer: Viewer) -> TableView {
+        self.table.view(self.variant(), self.round, self.table.to_act(), &self.community, viewer)
+    }
+
+    fn showdown(&mut self) -> Result<Vec<(usize, u32)>, DealerError> {
+        StudDealer::showdown(self)
+    }
+
+    fn awards(&self) -> Vec<(usize, u32)> {
+        self.table.awards.clone()
+    }
+
+    fn end_hand(&mut self) -> Result<(), DealerError> {
+        StudDealer::end_hand(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::betting::BettingStructure;
+    use crate::dealer::Stakes;
+    use poker_common::card::cards;
+
+    fn setup_table(game: StudGame, players: usize) -> StudDealer {
+        let structure = BettingStructure::FixedLimit { small_bet: 10, big_bet: 20, max_raises: 3 };
+        let stakes = Stakes { ante: 1, bring_in: 3, ..Stakes::default() };
+        let mut dealer = StudDealer::with_config(game, TableConfig { stakes, structure, ..TableConfig::default() });
+        for i in 0..players {
+            dealer.add_player(Player::new(format!("Player {}", i), i as u32, 1000));
+        }
+        dealer
+    }
+
+    // Replaces a player's cards: two down, then the up cards, then the rest down.
+    fn give_cards(dealer: &mut StudDealer, seat: usize, down: &str, up: &str) {
+        let player = &mut dealer.table.players[seat];
+        player.clear_hand();
+        let down = cards(down);
+        for card in down[..2.min(down.len())].iter() {
+            player.add_card(card.clone());
+        }
+        for card in cards(up) {
+            player.add_face_up_card(card);
+        }
+        for card in down.iter().skip(2) {
+            player.add_card(card.clone());
+        }
+    }
+
+    fn call_around(dealer: &mut StudDealer) {
+        while !dealer.is_betting_complete() {
+            let seat = dealer.get_current_player();
+            let action = if dealer.table.players[seat].get_current_bet() < dealer.get_current_bet() {
+                PlayerAction::Call
+            } else {
+                PlayerAction::Check
+            };
+            dealer.apply_action(seat, action).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_third_street_deal_and_visibility() {
+        let mut dealer = setup_table(StudGame::SevenCardStud, 3);
+        dealer.start_hand(1).unwrap();
+        assert_eq!(dealer.get_round(), Round::ThirdStreet);
+        for player in dealer.get_players() {
+            assert_eq!(player.get_hand().len(), 3);
+            assert_eq!(player.get_up_cards().len(), 1);
+            assert!(player.is_face_up(2));
+        }
+
+        // each player sees their own down cards but only the others' up cards
+        let view = dealer.view_for(Viewer::Seat(0));
+        assert_eq!(view.hand(), dealer.get_players()[0].get_hand());
+        for seat in [1, 2] {
+            assert_eq!(view.seats[seat].up_cards, dealer.get_players()[seat].get_up_cards());
+            for (i, card) in dealer.get_players()[seat].get_hand().iter().enumerate() {
+                if !dealer.get_players()[seat].is_face_up(i) {
+                    assert!(!view.seats.iter().any(|s| s.up_cards.contains(card)));
+                    assert!(!view.hand().contains(card));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_lowest_door_card_brings_in() {
+        let mut dealer = setup_table(StudGame::SevenCardStud, 3);
+        dealer.start_hand(1).unwrap();
+        let door = |seat: usize| {
+            let card = dealer.get_players()[seat].get_up_cards()[0].clone();
+            (card.value.rank(), card.suit.rank())
+        };
+        let lowest = (0..3).min_by_key(|&seat| door(seat)).unwrap();
+        assert_eq!(dealer.get_bring_in(), Some(lowest));
+        assert_eq!(dealer.get_players()[lowest].get_current_bet(), 3);
+        assert_eq!(dealer.get_pot(), 6);
+        assert_eq!(dealer.get_current_player(), (lowest + 1) % 3);
+        assert_eq!(dealer.raise_range(), Some((10, 10)));
+    }
+
+    #[test]
+    fn test_bring_in_ties_broken_by_suit() {
+        let mut dealer = setup_table(StudGame::SevenCardStud, 3);
+        give_cards(&mut dealer, 0, "Ah Kh", "2d");
+        give_cards(&mut dealer, 1, "Ad Kd", "2c");
+        give_cards(&mut dealer, 2, "Ac Kc", "2s");
+        assert_eq!(dealer.bring_in_seat(&[1, 2, 0]), 1);
+
+        // in Razz the highest card brings it in, kings high and aces low
+        let mut razz = setup_table(StudGame::Razz, 3);
+        give_cards(&mut razz, 0, "2h 3h", "Kd");
+        give_cards(&mut razz, 1, "2d 3d", "Ac");
+        give_cards(&mut razz, 2, "2c 3c", "Ks");
+        assert_eq!(razz.bring_in_seat(&[1, 2, 0]), 2);
+    }
+
+    #[test]
+    fn test_best_showing_hand_acts_first() {
+        let mut dealer = setup_table(StudGame::SevenCardStud, 3);
+        give_cards(&mut dealer, 0, "2h 3h", "Ad Kc");
+        give_cards(&mut dealer, 1, "2d 3d", "5c 5s");
+        give_cards(&mut dealer, 2, "2c 3c", "Ah Ks");
+        assert_eq!(dealer.best_showing(&[1, 2, 0]), 1);
+
+        // equal boards go to the player nearest the dealer's left
+        give_cards(&mut dealer, 1, "2d 3d", "4c 7s");
+        assert_eq!(dealer.best_showing(&[1, 2, 0]), 2);
+
+        let mut razz = setup_table(StudGame::Razz, 3);
+        give_cards(&mut razz, 0, "2h 3h", "Ad 4c");
+        give_cards(&mut razz, 1, "2d 3d", "5c 5s");
+        give_cards(&mut razz, 2, "2c 3c", "6h 2s");
+        assert_eq!(razz.best_showing(&[1, 2, 0]), 0);
+    }
+
+    #[test]
+    fn test_full_hand_through_seventh_street() {
+        let mut dealer = setup_table(StudGame::SevenCardStud, 3);
+        dealer.start_hand(8).unwrap();
+        call_around(&mut dealer);
+        for (street, cards, up) in [
+            (Round::FourthStreet, 4, 2),
+            (Round::FifthStreet, 5, 3),
+            (Round::SixthStreet, 6, 4),
+            (Round::SeventhStreet, 7, 4),
+        ] {
+            assert_eq!(dealer.advance(), Ok(street));
+            for player in dealer.get_players() {
+                assert_eq!(player.get_hand().len(), cards);
+                assert_eq!(player.get_up_cards().len(), up);
+            }
+            let showing: Vec<usize> = vec![1, 2, 0];
+            assert_eq!(dealer.get_current_player(), dealer.best_showing(&showing));
+            call_around(&mut dealer);
+        }
+        assert_eq!(dealer.advance(), Ok(Round::Showdown));
+
+        let awards = dealer.showdown().unwrap();
+        assert_eq!(awards.iter().map(|(_, amount)| amount).sum::<u32>(), 12);
+        let total: u32 = dealer.get_players().iter().map(|p| p.get_total_chips()).sum();
+        assert_eq!(total, 3000);
+        dealer.end_hand().unwrap();
+    }
+
+    #[test]
+    fn test_fifth_street_uses_big_bet() {
+        let mut dealer = setup_table(StudGame::SevenCardStud, 2);
+        dealer.start_hand(3).unwrap();
+        call_around(&mut dealer);
+        dealer.advance().unwrap();
+        assert_eq!(dealer.raise_range(), Some((10, 10)));
+        call_around(&mut dealer);
+        dealer.advance().unwrap();
+        assert_eq!(dealer.raise_range(), Some((20, 20)));
+    }
+
+    #[test]
+    fn test_community_card_when_the_deck_runs_short() {
+        let mut dealer = setup_table(StudGame::SevenCardStud, 8);
+        dealer.start_hand(2).unwrap();
+        for _ in 0..4 {
+            call_around(&mut dealer);
+            dealer.advance().unwrap();
+        }
+        // 8 players hold 48 cards and 3 were burned, leaving one to share
+        assert_eq!(dealer.get_round(), Round::SeventhStreet);
+        assert_eq!(dealer.community.len(), 1);
+        assert!(dealer.get_players().iter().all(|p| p.get_hand().len() == 6));
+        assert_eq!(dealer.hand_evaluation(0).unwrap().cards().len(), 5);
+        assert_eq!(dealer.view_for(Viewer::Seat(3)).board, dealer.community);
+    }
+
+    #[test]
+    fn test_stud_hi_lo_split() {
+        let mut dealer = setup_table(StudGame::StudHiLo, 2);
+        dealer.start_hand(4).unwrap();
+        call_around(&mut dealer);
+        for _ in 0..4 {
+            dealer.advance().unwrap();
+            call_around(&mut dealer);
+        }
+        dealer.advance().unwrap();
+
+        give_cards(&mut dealer, 0, "Kh Kd Kc", "Qs Qd 9c 2h");
+        give_cards(&mut dealer, 1, "Ah 2d 4c", "5s 7d Jc Jh");
+        assert_eq!(dealer.low_hand(1).unwrap().to_string(), "7-5-4-2-A");
+        assert!(dealer.low_hand(0).is_none());
+
+        let awards = dealer.showdown().unwrap();
+        assert_eq!(awards, vec![(0, 4), (1, 4)]);
+    }
+
+    #[test]
+    fn test_razz_lowest_hand_wins() {
+        let mut dealer = setup_table(StudGame::Razz, 2);
+        dealer.start_hand(5).unwrap();
+        call_around(&mut dealer);
+        for _ in 0..4 {
+            dealer.advance().unwrap();
+            call_around(&mut dealer);
+        }
+        dealer.advance().unwrap();
+
+        // a straight and a flush do not hurt in Razz, but a pair does
+        give_cards(&mut dealer, 0, "Ah 2h 3h", "4h 5h Kd Kc");
+        give_cards(&mut dealer, 1, "Ad 2d 3d", "4d 4c Qs Qc");
+        assert_eq!(dealer.lowball_hand(0).unwrap().to_string(), "5-4-3-2-A");
+        assert_eq!(dealer.lowball_hand(1).unwrap().to_string(), "Q-4-3-2-A");
+
+        let awards = dealer.showdown().unwrap();
+        assert_eq!(awards, vec![(0, 8)]);
+    }
+}
@@ -0,0 +1,399 @@
+use poker_common::action::PlayerAction;
+use poker_common::card::{Card, Deck};
+use poker_common::player::Player;
+
+use crate::betting::{BettingRound, Limits};
+use crate::dealer::{DealerError, LegalAction, Round, TableConfig, TableView, Viewer};
+use crate::pot::{self, Pot};
+use crate::variant::Variant;
+
+/// The state every poker variant shares: the seated players, the button, the
+/// deck, the pot and the betting round in progress.
+///
+/// Dealers own a `Table` and decide what gets dealt when and who acts first;
+/// the table takes care of forced bets, betting, pots and payouts.
+pub struct Table {
+    pub(crate) players: Vec<Player>,
+    pub(crate) config: TableConfig,
+    pub(crate) deck: Deck,
+    pub(crate) pot: u32,
+    pub(crate) contributions: Vec<u32>,
+    pub(crate) betting: Option<BettingRound>,
+    pub(crate) current_player: usize,
+    pub(crate) button: usize,
+    // what each seat won in the hand just finished, kept until it's cleared
+    pub(crate) awards: Vec<(usize, u32)>,
+}
+
+impl Table {
+    pub fn new(config: TableConfig) -> Table {
+        Table {
+            players: Vec::new(),
+            config,
+            deck: Deck::new(),
+            pot: 0,
+            contributions: Vec::new(),
+            betting: None,
+            current_player: 0,
+            button: 0,
+            awards: Vec::new(),
+        }
+    }
+
+    pub fn add_player(&mut self, player: Player) {
+        self.players.push(player);
+    }
+
+    pub fn sit_out(&mut self, seat: usize, sitting_out: bool) -> Result<(), DealerError> {
+        let player = self.players.get_mut(seat).ok_or(DealerError::InvalidSeat(seat))?;
+        player.set_sitting_out(sitting_out);
+        Ok(())
+    }
+
+    pub fn remove_player(&mut self, seat: usize) -> Result<Player, DealerError> {
+        if seat >= self.players.len() {
+            return Err(DealerError::InvalidSeat(seat));
+        }
+        let player = self.players.remove(seat);
+        if seat < self.button {
+            self.button -= 1;
+        }
+        if self.button >= self.players.len() {
+            self.button = 0;
+        }
+        Ok(player)
+    }
+
+    /// The main pot followed by any side pots, built from what each seat has
+    /// put in so far this hand.
+    pub fn pots(&self) -> Vec<Pot> {
+        let in_hand: Vec<bool> = self.players.iter().map(|p| p.is_active()).collect();
+        pot::build_pots(&self.contributions, &in_hand)
+    }
+
+    pub fn current_bet(&self) -> u32 {
+        self.betting.as_ref().map_or(0, |betting| betting.current_bet())
+    }
+
+    /// True once everyone still in the hand has acted on the current bet.
+    pub fn is_betting_complete(&self) -> bool {
+        self.betting.as_ref().is_some_and(|betting| betting.is_complete())
+    }
+
+    /// The totals the player to act may bet or raise to, if they may raise at all.
+    pub fn raise_range(&self) -> Option<(u32, u32)> {
+        self.betting.as_ref()?.raise_range(&self.players, self.current_player)
+    }
+
+    /// The seat whose turn it is to bet, if betting is open.
+    pub fn to_act(&self) -> Option<usize> {
+        self.betting.as_ref()?.to_act()
+    }
+
+    /// The betting actions open to `seat`, empty unless it is their turn to bet.
+    pub fn legal_actions(&self, seat: usize) -> Vec<LegalAction> {
+        let betting = match &self.betting {
+            Some(betting) if betting.to_act() == Some(seat) => betting,
+            _ => return Vec::new(),
+        };
+        let player = &self.players[seat];
+        let to_call = betting.amount_to_call(player);
+
+        let mut actions = vec![LegalAction::Fold];
+        if to_call == 0 {
+            actions.push(LegalAction::Check);
+        } else {
+            actions.push(LegalAction::Call(to_call.min(player.get_total_chips())));
+        }
+        if let Some((min, max)) = betting.raise_range(&self.players, seat) {
+            if betting.current_bet() == 0 {
+                actions.push(LegalAction::Bet { min, max });
+            } else {
+                actions.push(LegalAction::Raise { min, max });
+            }
+        }
+        actions
+    }
+
+    /// The table as `viewer` sees it: everyone's chips and card counts, but
+    /// only the cards the viewer is allowed to see.
+    pub fn view(&self, variant: Variant, round: Round, to_act: Option<usize>, board: &[Card], viewer: Viewer) -> TableView {
+        TableView {
+            variant,
+            round,
+            button: self.button,
+            to_act,
+            pot: self.pot,
+            current_bet: self.current_bet(),
+            board: board.to_vec(),
+            seats: TableView::seats(&self.players, viewer),
+            viewer,
+            legal_actions: match viewer {
+                Viewer::Seat(seat) => self.legal_actions(seat),
+                _ => Vec::new(),
+            },
+        }
+    }
+
+    /// Seats that have not folded.
+    pub fn in_hand(&self) -> Vec<usize> {
+        (0..self.players.len()).filter(|&seat| self.players[seat].is_active()).collect()
+    }
+
+    /// Deals in everyone with chips who isn't sitting out, moves the button
+    /// onto a live seat and deals the hand from `deck`, which the caller has
+    /// already shuffled.
+    pub fn begin_hand(&mut self, deck: Deck) -> Result<(), DealerError> {
+        let dealt_in = |player: &Player| player.get_total_chips() > 0 && !player.is_sitting_out();
+        if self.players.iter().filter(|p| dealt_in(p)).count() < 2 {
+            return Err(DealerError::NotEnoughPlayers);
+        }
+        for player in self.players.iter_mut() {
+            let playing = dealt_in(player);
+            player.clear_hand();
+            player.set_current_bet(0);
+            player.set_active(playing);
+        }
+        if !self.players[self.button].is_active() {
+            self.button = self.next_active(self.button);
+        }
+        self.contributions = vec![0; self.players.len()];
+        self.deck = deck;
+        Ok(())
+    }
+
+    pub fn post_antes(&mut self) {
+        let ante = self.config.stakes.ante;
+        for (seat, player) in self.players.iter_mut().enumerate().filter(|(_, p)| p.is_active()) {
+            let posted = ante.min(player.get_total_chips());
+            player.remove_chips(posted);
+            self.pot += posted;
+            self.contributions[seat] += posted;
+        }
+    }
+
+    /// Posts the small and big blind and returns the big blind's seat.
+    /// Heads up, the button posts the small blind.
+    pub fn post_blinds(&mut self) -> usize {
+        let small_blind_seat = if self.in_hand().len() == 2 { self.button } else { self.next_active(self.button) };
+        let big_blind_seat = self.next_active(small_blind_seat);
+        self.post_bet(small_blind_seat, self.config.stakes.small_blind);
+        self.post_bet(big_blind_seat, self.config.stakes.big_blind);
+        big_blind_seat
+    }
+
+    /// Puts a forced bet for `seat` in front of them, or their whole stack if it is smaller.
+    pub fn post_bet(&mut self, seat: usize, amount: u32) -> u32 {
+        let player = &mut self.players[seat];
+        let posted = amount.min(player.get_total_chips());
+        player.place_bet(posted);
+        self.pot += posted;
+        self.contributions[seat] += posted;
+        posted
+    }
+
+    /// Opens a betting round with `first` to act and `current_bet` to match.
+    /// Fixed-limit games use the big bet when `big_street` is set.
+    pub fn open_betting(&mut self, first: usize, current_bet: u32, big_street: bool) {
+        let limits = Limits {
+            structure: self.config.structure,
+            min_bet: self.config.stakes.big_blind,
+            pot: self.pot,
+            big_street,
+        };
+        let betting = BettingRound::new(&self.players, first, current_bet, limits);
+        self.current_player = betting.to_act().unwrap_or(first);
+        self.betting = Some(betting);
+    }
+
+    /// Opens stud betting after `seat` has been forced to bring it in.
+    pub fn open_bring_in_betting(&mut self, seat: usize, bring_in: u32) {
+        let limits = Limits {
+            structure: self.config.structure,
+            min_bet: self.config.stakes.big_blind,
+            pot: self.pot,
+            big_street: false,
+        };
+        let betting = BettingRound::with_bring_in(&self.players, seat, bring_in, limits);
+        self.current_player = betting.to_act().unwrap_or(seat);
+        self.betting = Some(betting);
+    }
+
+    /// Applies a betting action and returns whether the betting round is now
+    /// complete. A folded hand goes straight into the muck.
+    pub fn apply_action(&mut self, seat: usize, action: PlayerAction) -> Result<bool, DealerError> {
+        let betting = self.betting.as_mut().ok_or(DealerError::BettingNotOpen)?;
+        let chips = betting.apply(&mut self.players, seat, action)?;
+        self.pot += chips;
+        self.contributions[seat] += chips;
+        let complete = betting.is_complete();
+        if let Some(next) = betting.to_act() {
+            self.current_player = next;
+        }
+
+        if action == PlayerAction::Fold {
+            self.muck_hand(seat);
+            self.players[seat].game_folded();
+        }
+        Ok(complete)
+    }
+
+    /// If everyone else has folded, gives the last player the pot and returns true.
+    pub fn award_if_uncontested(&mut self) -> bool {
+        let remaining = self.in_hand();
+        if remaining.len() != 1 {
+            return false;
+        }
+        self.players[remaining[0]].game_won(self.pot);
+        self.awards = vec![(remaining[0], self.pot)];
+        self.pot = 0;
+        true
+    }
+
+    pub fn end_betting_round(&mut self) -> Result<(), DealerError> {
+        if !self.is_betting_complete() {
+            return Err(DealerError::BettingNotComplete);
+        }
+        for player in self.players.iter_mut() {
+            player.set_current_bet(0);
+        }
+        self.betting = None;
+        Ok(())
+    }
+
+    pub fn deal_card(&mut self) -> Result<Card, DealerError> {
+        self.deck.deal().ok_or(DealerError::DeckExhausted)
+    }
+
+    pub fn deal_to(&mut self, seat: usize) -> Result<(), DealerError> {
+        let card = self.deal_card()?;
+        self.players[seat].add_card(card);
+        Ok(())
+    }
+
+    pub fn burn(&mut self) -> Result<(), DealerError> {
+        if !self.deck.burn() {
+            return Err(DealerError::DeckExhausted);
+        }
+        Ok(())
+    }
+
+    pub fn muck_hand(&mut self, seat: usize) {
+        for card in self.players[seat].get_hand().clone() {
+            self.deck.discard(card);
+        }
+        self.players[seat].clear_hand();
+    }
+
+    /// Awards the main pot and each side pot to the eligible seat with the
+    /// highest key, and returns each winner's seat with their total winnings.
+    /// Seats with no key (folded, or no qualifying hand) cannot win.
+    ///
+    /// A tied pot is split evenly; chips that cannot be split go one at a time
+    /// to the tied winners starting from the first seat left of the button.
+    pub fn award_pots<K: Ord>(&mut self, keys: &[Option<K>]) -> Vec<(usize, u32)> {
+        let mut awards: Vec<(usize, u32)> = Vec::new();
+        for side_pot in self.pots() {
+            let mut winners = self.best_among(&side_pot.eligible, keys);
+            winners.sort_by_key(|&seat| self.seats_from_button(seat));
+            add_awards(&mut awards, pot::split_pot(side_pot.amount, &winners));
+        }
+        self.record_results(&awards);
+        awards
+    }
+
+    /// Splits each pot between the best `high` key and the best `low` key
+    /// among the seats eligible for it, with the odd chip going to the high
+    /// half. A pot nobody qualifies for low goes to the high hand alone.
+    ///
+    /// Ties split their half again, so a player who ties for low with one
+    /// other player is quartered.
+    pub fn award_hi_lo_pots<H: Ord, L: Ord>(&mut self, high: &[Option<H>], low: &[Option<L>]) -> Vec<(usize, u32)> {
+        let mut awards: Vec<(usize, u32)> = Vec::new();
+        for side_pot in self.pots() {
+            let mut high_winners = self.best_among(&side_pot.eligible, high);
+            let mut low_winners = self.best_among(&side_pot.eligible, low);
+            high_winners.sort_by_key(|&seat| self.seats_from_button(seat));
+            low_winners.sort_by_key(|&seat| self.seats_from_button(seat));
+
+            if low_winners.is_empty() {
+                add_awards(&mut awards, pot::split_pot(side_pot.amount, &high_winners));
+            } else {
+                let low_half = side_pot.amount / 2;
+                add_awards(&mut awards, pot::split_pot(side_pot.amount - low_half, &high_winners));
+                add_awards(&mut awards, pot::split_pot(low_half, &low_winners));
+            }
+        }
+        self.record_results(&awards);
+        awards
+    }
+
+    /// The seats in `seats` holding the best key, everyone who ties it included.
+    pub fn best_among<K: Ord>(&self, seats: &[usize], keys: &[Option<K>]) -> Vec<usize> {
+        match seats.iter().filter_map(|&seat| keys[seat].as_ref()).max() {
+            Some(best) => seats.iter().copied().filter(|&seat| keys[seat].as_ref() == Some(best)).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    pub fn record_results(&mut self, awards: &[(usize, u32)]) {
+        for (seat, player) in self.players.iter_mut().enumerate() {
+            match awards.iter().find(|(winner, _)| *winner == seat) {
+                Some(&(_, amount)) => player.game_won(amount),
+                None if player.is_active() => player.game_lost(),
+                None => {}
+            }
+        }
+        self.awards = awards.to_vec();
+        self.pot = 0;
+    }
+
+    /// Clears the finished hand and moves the button for the next one.
+    pub fn end_hand(&mut self) {
+        self.awards.clear();
+        for player in self.players.iter_mut() {
+            player.clear_hand();
+            player.set_current_bet(0);
+            player.set_active(false);
+        }
+        self.betting = None;
+        if !self.players.is_empty() {
+            self.button = (self.button + 1) % self.players.len();
+        }
+    }
+
+    pub fn next_active(&self, seat: usize) -> usize {
+        let count = self.players.len();
+        (1..=count)
+            .map(|offset| (seat + offset) % count)
+            .find(|&s| self.players[s].is_active())
+            .unwrap_or(seat)
+    }
+
+    pub fn seats_from_button(&self, seat: usize) -> usize {
+        let count = self.players.len();
+        (seat + count - self.button - 1) % count
+    }
+
+    pub fn expect_turn(&self, seat: usize) -> Result<(), DealerError> {
+        if seat >= self.players.len() {
+            return Err(DealerError::InvalidSeat(seat));
+        }
+        if !self.players[seat].is_active() {
+            return Err(DealerError::PlayerNotInHand(seat));
+        }
+        if seat != self.current_player {
+            return Err(DealerError::NotPlayersTurn { seat, current: self.current_player });
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn add_awards(awards: &mut Vec<(usize, u32)>, more: Vec<(usize, u32)>) {
+    for (seat, amount) in more {
+        match awards.iter_mut().find(|(winner, _)| *winner == seat) {
+            Some(award) => award.1 += amount,
+            None => awards.push((seat, amount)),
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use std::collections::HashMap;
+use std::io;
+
+/// How long a session token stays valid after it was issued, in seconds.
+pub const TOKEN_TTL_SECS: u64 = 24 * 60 * 60;
+
+const TOKEN_BYTES: usize = 32;
+
+struct Session {
+    username: String,
+    expires: u64,
+}
+
+/// The session tokens handed out at login. A token is 32 random bytes in
+/// hex and carries no meaning of its own; it only names an entry here, so
+/// the server can revoke it at any time.
+#[derive(Default)]
+pub struct SessionTokens {
+    sessions: HashMap<String, Session>,
+}
+
+impl SessionTokens {
+    pub fn new() -> SessionTokens {
+        SessionTokens::default()
+    }
+
+    /// A new token for `username`, valid for `TOKEN_TTL_SECS` from `now`.
+    /// Any token the user already had is revoked, so only the latest login
+    /// can be resumed.
+    pub fn issue(&mut self, username: &str, now: u64) -> io::Result<String> {
+        let mut bytes = [0; TOKEN_BYTES];
+        getrandom::getrandom(&mut bytes)?;
+        let token: String = bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+
+        self.sessions.retain(|_, session| session.username != username && session.expires > now);
+        self.sessions.insert(token.clone(), Session { username: username.to_owned(), expires: now + TOKEN_TTL_SECS });
+        Ok(token)
+    }
+
+    /// The user a token belongs to, if it is known and hasn't expired.
+    pub fn resume(&self, token: &str, now: u64) -> Option<&str> {
+        self.sessions.get(token)
+            .filter(|session| now < session.expires)
+            .map(|session| session.username.as_str())
+    }
+
+    pub fn revoke(&mut self, token: &str) {
+        self.sessions.remove(token);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tokens::{SessionTokens, TOKEN_TTL_SECS};
+
+    #[test]
+    fn test_tokens_resume_until_they_expire() {
+        let mut tokens = SessionTokens::new();
+        let token = tokens.issue("jane", 100).unwrap();
+        assert_eq!(token.len(), 64);
+        assert_eq!(tokens.resume(&token, 100), Some("jane"));
+        assert_eq!(tokens.resume(&token, 100 + TOKEN_TTL_SECS - 1), Some("jane"));
+        assert_eq!(tokens.resume(&token, 100 + TOKEN_TTL_SECS), None);
+        assert_eq!(tokens.resume("not a token", 100), None);
+    }
+
+    #[test]
+    fn test_new_login_revokes_the_old_token() {
+        let mut tokens = SessionTokens::new();
+        let first = tokens.issue("jane", 0).unwrap();
+        let other = tokens.issue("john", 0).unwrap();
+        let second = tokens.issue("jane", 10).unwrap();
+        assert_ne!(first, second);
+        assert_eq!(tokens.resume(&first, 10), None);
+        assert_eq!(tokens.resume(&second, 10), Some("jane"));
+        assert_eq!(tokens.resume(&other, 10), Some("john"));
+
+        tokens.revoke(&second);
+        assert_eq!(tokens.resume(&second, 10), None);
+    }
+}
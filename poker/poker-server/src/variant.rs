@@ -0,0 +1,216 @@
+use poker_common::wild::WildCards;
+
+pub use poker_common::variant::Variant;
+
+use crate::betting::BettingStructure;
+use crate::dealer::{Dealer, DrawGame, FiveDrawDealer, TableConfig};
+use crate::holdem::{HoldemDealer, HoldemGame};
+use crate::stud::{StudDealer, StudGame};
+
+/// Builds the dealer for a variant, so the server can open a table for any
+/// game a client picks.
+pub trait DealerFactory {
+    /// A new dealer for this variant with no players seated. Pot-Limit
+    /// Omaha is always played pot-limit, whatever the table's structure, and
+    /// wild cards are dropped from every game but five card draw. Short deck
+    /// ranks three of a kind against a straight as the table chose.
+    fn dealer(&self, config: TableConfig) -> Box<dyn Dealer>;
+}
+
+impl DealerFactory for Variant {
+    fn dealer(&self, config: TableConfig) -> Box<dyn Dealer> {
+        let config = match self {
+            Variant::FiveCardDraw => config,
+            _ => TableConfig { wild: WildCards::none(), ..config },
+        };
+        match self {
+            Variant::FiveCardDraw => Box::new(FiveDrawDealer::with_config(config)),
+            Variant::DeuceToSevenTripleDraw => Box::new(FiveDrawDealer::with_game(DrawGame::DeuceToSevenTripleDraw, config)),
+            Variant::AceToFiveTripleDraw => Box::new(FiveDrawDealer::with_game(DrawGame::AceToFiveTripleDraw, config)),
+            Variant::Badugi => Box::new(FiveDrawDealer::with_game(DrawGame::Badugi, config)),
+            Variant::TexasHoldem => Box::new(HoldemDealer::with_config(config)),
+            Variant::PotLimitOmaha => {
+                let config = TableConfig { structure: BettingStructure::PotLimit, ..config };
+                Box::new(HoldemDealer::with_game(HoldemGame::Omaha, config))
+            }
+            Variant::OmahaHiLo => Box::new(HoldemDealer::with_game(HoldemGame::OmahaHiLo, config)),
+            Variant::ShortDeck => {
+                Box::new(HoldemDealer::with_game(HoldemGame::ShortDeck { trips_beat_straight: config.trips_beat_straight }, config))
+            }
+            Variant::SevenCardStud => Box::new(StudDealer::with_config(StudGame::SevenCardStud, config)),
+            Variant::StudHiLo => Box::new(StudDealer::with_config(StudGame::StudHiLo, config)),
+            Variant::Razz => Box::new(StudDealer::with_config(StudGame::Razz, config)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use poker_common::action::PlayerAction;
+    use poker_common::card::{cards, Card, Deck};
+    use poker_common::player::Player;
+    use poker_common::protocol::{Message, ServerMessage};
+
+    use crate::dealer::{Dealer, LegalAction, Round, TableConfig, Viewer};
+    use crate::variant::{DealerFactory, Variant};
+
+    // Checks or calls every bet and stands pat on every draw until showdown.
+    fn play_to_showdown(dealer: &mut dyn Dealer) {
+        loop {
+            match dealer.round() {
+                Round::Showdown | Round::Complete => return,
+                Round::Draw => {
+                    let view = dealer.view_for(Viewer::Seat(0));
+                    match view.to_act {
+                        Some(seat) => dealer.draw(seat, &[]).unwrap(),
+                        None => {
+                            dealer.advance().unwrap();
+                        }
+                    }
+                }
+                _ => {
+                    let to_act = (0..dealer.players().len()).find(|&seat| !dealer.legal_actions(seat).is_empty());
+                    match to_act {
+                        Some(seat) => {
+                            let action = match dealer.legal_actions(seat)[1] {
+                                LegalAction::Check => PlayerAction::Check,
+                                _ => PlayerAction::Call,
+                            };
+                            dealer.apply_action(seat, action).unwrap();
+                        }
+                        None => {
+                            dealer.advance().unwrap();
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_every_variant_plays_through_the_trait() {
+        for variant in Variant::ALL {
+            let mut dealer = variant.dealer(TableConfig::default());
+            assert_eq!(dealer.variant(), variant);
+            for i in 0..3 {
+                let seat = dealer.seat_player(Player::new(format!("Player {}", i), i, 1000));
+                assert_eq!(seat, i as usize);
+            }
+
+            dealer.start_hand(42).unwrap();
+            play_to_showdown(dealer.as_mut());
+            let pot = dealer.view_for(Viewer::Seat(0)).pot;
+            assert!(pot > 0);
+            let awards = dealer.showdown().unwrap();
+            assert_eq!(awards.iter().map(|(_, amount)| amount).sum::<u32>(), pot, "{}", variant);
+            dealer.end_hand().unwrap();
+            assert_eq!(dealer.round(), Round::Waiting);
+        }
+    }
+
+    #[test]
+    fn test_legal_actions_for_player_to_act() {
+        for variant in Variant::ALL.into_iter().filter(|variant| !variant.has_bring_in()) {
+            let mut dealer = variant.dealer(TableConfig::default());
+            for i in 0..3 {
+                dealer.seat_player(Player::new(format!("Player {}", i), i, 1000));
+            }
+            dealer.start_hand(1).unwrap();
+            // pot-limit raises are capped at the pot after calling
+            let pot_limit = variant == Variant::PotLimitOmaha;
+
+            // under the gun faces the big blind, everyone else waits
+            assert_eq!(dealer.legal_actions(0), vec![
+                LegalAction::Fold,
+                LegalAction::Call(10),
+                LegalAction::Raise { min: 20, max: if pot_limit { 35 } else { 1000 } },
+            ]);
+            assert!(dealer.legal_actions(1).is_empty());
+
+            dealer.apply_action(0, PlayerAction::Call).unwrap();
+            dealer.apply_action(1, PlayerAction::Call).unwrap();
+            assert_eq!(dealer.legal_actions(2), vec![
+                LegalAction::Fold,
+                LegalAction::Check,
+                LegalAction::Raise { min: 20, max: if pot_limit { 40 } else { 1000 } },
+            ]);
+        }
+    }
+
+    #[test]
+    fn test_view_shows_only_own_cards() {
+        for variant in Variant::ALL {
+            let mut dealer = variant.dealer(TableConfig::default());
+            for i in 0..3 {
+                dealer.seat_player(Player::new(format!("Player {}", i), i, 1000));
+            }
+            dealer.start_hand(5).unwrap();
+
+            let view = dealer.view_for(Viewer::Seat(1));
+            assert_eq!(view.variant, variant);
+            assert_eq!(view.hand(), dealer.players()[1].get_hand());
+            assert!(view.legal_actions.is_empty() || view.to_act == Some(1));
+            for (seat, player) in view.seats.iter().enumerate() {
+                assert_eq!(player.cards, dealer.players()[seat].get_hand().len());
+            }
+            for other in [0, 2] {
+                for card in dealer.players()[other].get_hand() {
+                    assert!(!view.hand().contains(card));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_serialized_views_hide_other_hands() {
+        for variant in Variant::ALL {
+            let mut dealer = variant.dealer(TableConfig::default());
+            for i in 0..3 {
+                dealer.seat_player(Player::new(format!("Player {}", i), i, 1000));
+            }
+            dealer.start_hand(7).unwrap();
+
+            for seat in 0..3 {
+                let message = ServerMessage::TableState { table: 1, view: dealer.view_for(Viewer::Seat(seat)) };
+                let ServerMessage::TableState { view, .. } = ServerMessage::decode(&message.encode()).unwrap() else {
+                    panic!("{:?}: not a table state", variant);
+                };
+                let sent: Vec<&Card> = view.seats.iter().flat_map(|seat| seat.up_cards.iter().chain(&seat.hand)).collect();
+                for other in (0..3).filter(|&other| other != seat) {
+                    let player = &dealer.players()[other];
+                    for (i, card) in player.get_hand().iter().enumerate() {
+                        assert_eq!(sent.contains(&card), player.is_face_up(i), "{:?}: seat {} sees {}", variant, seat, card);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_short_deck_plays_the_tables_trips_rule() {
+        // hole cards go out one at a time, so the first seat dealt to holds
+        // A-9 for a straight and the other T-K for trips, with a card burnt
+        // before each street
+        let deck = "Ah Tc 9d Kh 6s 6h 7d 8c 7s Ts 7c Td";
+        for (trips_beat_straight, winning_hand) in [(false, "Ah 9d"), (true, "Tc Kh")] {
+            let mut dealer = Variant::ShortDeck.dealer(TableConfig { trips_beat_straight, ..TableConfig::default() });
+            dealer.seat_player(Player::new("John".to_owned(), 1, 1000));
+            dealer.seat_player(Player::new("Jane".to_owned(), 2, 1000));
+            dealer.start_hand_from(Deck::stacked(cards(deck))).unwrap();
+            let winner = dealer.players().iter().position(|player| player.get_hand() == &cards(winning_hand)).unwrap();
+
+            play_to_showdown(dealer.as_mut());
+            let pot = dealer.view_for(Viewer::Spectator).pot;
+            assert_eq!(dealer.showdown().unwrap(), vec![(winner, pot)]);
+        }
+    }
+
+    #[test]
+    fn test_holdem_has_no_draw() {
+        let mut dealer = Variant::TexasHoldem.dealer(TableConfig::default());
+        dealer.seat_player(Player::new("John".to_owned(), 1, 1000));
+        dealer.seat_player(Player::new("Jane".to_owned(), 2, 1000));
+        dealer.start_hand(1).unwrap();
+        assert!(dealer.draw(0, &[]).is_err());
+    }
+}